@@ -0,0 +1,328 @@
+// Modo batch: decodifica un archivo de tramas capturadas de una sola vez.
+//
+// Formatos de entrada (se ignoran líneas vacías y las que empiezan con '#'):
+// - Registro por línea:  ALGO/PARAM/BITS     (p.ej. HAMMING/n=7;pad=3/0110011...)
+// - Bits crudos por línea, usando el algoritmo y n por defecto (--algo/--n).
+// - CSV con encabezado al estilo de `PT2/resultados/` (columna `algoritmo` obligatoria y
+//   la trama en `trama` o `bits`; opcionales `param`, `hamming_n` y `mensaje_original`).
+//
+// Salida: un CSV con el resultado de cada trama (datos en binario) y un resumen.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::correction::hamming::{self, HammingCode, HammingError};
+use crate::detection::crc32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Valid,
+    Corrected,
+    Uncorrectable,
+    Detected,
+    Error,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Valid => "valido",
+            Status::Corrected => "corregido",
+            Status::Uncorrectable => "no_corregible",
+            Status::Detected => "descartado",
+            Status::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub line: usize, // línea del archivo (1-based)
+    pub algo: String,
+    pub param: String,
+    pub bits: String,
+    pub expected: Option<String>, // mensaje original (texto o bits), si el CSV lo trae
+}
+
+// Registro válido, o (línea, motivo) si no se pudo interpretar.
+pub type ParsedRecord = Result<Record, (usize, String)>;
+
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub frames: usize,
+    pub by_status: HashMap<&'static str, usize>,
+    pub corrected_bits: usize,
+    pub mismatches: usize,
+}
+
+impl BatchSummary {
+    pub fn render(&self) -> String {
+        let mut out = format!("Tramas procesadas: {}\n", self.frames);
+        for st in [Status::Valid, Status::Corrected, Status::Uncorrectable, Status::Detected, Status::Error] {
+            out.push_str(&format!("  {:<14} {}\n", st.as_str(), self.by_status.get(st.as_str()).unwrap_or(&0)));
+        }
+        out.push_str(&format!("Bits corregidos: {}\n", self.corrected_bits));
+        if self.mismatches > 0 {
+            out.push_str(&format!("Datos entregados distintos del original: {}\n", self.mismatches));
+        }
+        out
+    }
+}
+
+fn parse_record_line(line: &str, default_algo: Option<&str>, default_param: &str) -> Result<(String, String, String), String> {
+    let parts: Vec<&str> = line.splitn(3, '/').collect();
+    match parts.as_slice() {
+        [algo, param, bits] => Ok((algo.trim().to_ascii_uppercase(), param.trim().to_string(), bits.trim().to_string())),
+        [bits] if bits.chars().all(|c| c == '0' || c == '1') => match default_algo {
+            Some(a) => Ok((a.to_ascii_uppercase(), default_param.to_string(), bits.to_string())),
+            None => Err("trama sin algoritmo (use ALGO/PARAM/BITS o --algo)".into()),
+        },
+        _ => Err(format!("registro mal formado: {}", line)),
+    }
+}
+
+// Campos de una fila CSV (RFC 4180, la inversa de csv_field): un campo entre
+// comillas puede tener comas y comillas dobladas (""). Las filas se leen de a una,
+// así que un campo con un salto de línea queda como comilla sin cerrar.
+fn csv_split(row: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false; // dentro de las comillas
+    let mut was_quoted = false; // el campo empezó con comillas
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if !was_quoted && field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+                was_quoted = true;
+            }
+            '"' => return Err(format!("comilla en medio del campo {}", fields.len() + 1)),
+            _ if quoted => field.push(c),
+            ',' => {
+                fields.push(if was_quoted { std::mem::take(&mut field) } else { field.trim().to_string() });
+                field.clear();
+                was_quoted = false;
+            }
+            _ if was_quoted && !c.is_whitespace() => {
+                return Err(format!("texto después de la comilla de cierre en el campo {}", fields.len() + 1));
+            }
+            _ if was_quoted => {}
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("comilla sin cerrar en el campo {}", fields.len() + 1));
+    }
+    fields.push(if was_quoted { field } else { field.trim().to_string() });
+    Ok(fields)
+}
+
+fn parse_csv(lines: &[(usize, &str)]) -> Result<Vec<ParsedRecord>, String> {
+    let (_, header) = lines[0];
+    let cols = csv_split(header).map_err(|e| format!("encabezado CSV mal formado: {}", e))?;
+    let idx = |name: &str| cols.iter().position(|c| c == name);
+    let algo_i = idx("algoritmo").ok_or("CSV sin columna 'algoritmo'")?;
+    let bits_i = idx("trama").or_else(|| idx("bits")).ok_or(
+        "CSV sin columna 'trama' ni 'bits' (un CSV de resultados generado por una versión de pruebas.py \
+         anterior a esa columna no guarda la trama y no se puede decodificar)",
+    )?;
+    let (param_i, n_i, expected_i) = (idx("param"), idx("hamming_n"), idx("mensaje_original"));
+
+    let records = lines[1..]
+        .iter()
+        .map(|&(line, row)| {
+            let cells = csv_split(row).map_err(|e| (line, format!("fila CSV mal formada: {}", e)))?;
+            let get = |i: Option<usize>| i.and_then(|i| cells.get(i)).cloned();
+            let algo = get(Some(algo_i)).unwrap_or_default().to_ascii_uppercase();
+            let bits = get(Some(bits_i)).ok_or((line, "fila sin trama".to_string()))?;
+            let param = match (get(param_i), get(n_i)) {
+                (Some(p), _) if !p.is_empty() => p,
+                (_, Some(n)) if !n.is_empty() => format!("n={}", n),
+                _ => String::new(),
+            };
+            Ok(Record { line, algo, param, bits, expected: get(expected_i) })
+        })
+        .collect();
+    Ok(records)
+}
+
+pub fn parse_input(content: &str, default_algo: Option<&str>, default_param: &str) -> Result<Vec<ParsedRecord>, String> {
+    let lines: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+    if lines.is_empty() {
+        return Ok(Vec::new());
+    }
+    if lines[0].1.contains(',') && lines[0].1.split(',').any(|c| c.trim().trim_matches('"') == "algoritmo") {
+        return parse_csv(&lines);
+    }
+    Ok(lines
+        .iter()
+        .map(|&(line, l)| {
+            parse_record_line(l, default_algo, default_param)
+                .map(|(algo, param, bits)| Record { line, algo, param, bits, expected: None })
+                .map_err(|e| (line, e))
+        })
+        .collect())
+}
+
+fn param_value(param: &str, key: &str) -> Option<usize> {
+    param
+        .split(';')
+        .filter_map(|p| p.trim().split_once('='))
+        .find(|(k, _)| *k == key)
+        .and_then(|(_, v)| v.parse().ok())
+}
+
+// Decodifica un registro: (estado, datos entregados, correcciones, detalle).
+fn decode_record(r: &Record) -> (Status, Option<String>, Vec<(usize, usize)>, String) {
+    match r.algo.as_str() {
+        "HAMMING" => {
+            let n = param_value(&r.param, "n").unwrap_or(7);
+            let pad = param_value(&r.param, "pad").unwrap_or(0);
+            let code = match param_value(&r.param, "k") {
+                Some(k) => HammingCode::new(n, k),
+                None => HammingCode::from_n(n),
+            };
+            let code = match code {
+                Ok(c) => c,
+                Err(e) => return (Status::Error, None, Vec::new(), e.to_string()),
+            };
+            match hamming::decode_stream_code(&r.bits, &code) {
+                Ok(res) => {
+                    let mut data = res.data_bits;
+                    if pad > 0 && pad <= data.len() { data.truncate(data.len() - pad); }
+                    let st = if res.corrected_positions.is_empty() { Status::Valid } else { Status::Corrected };
+                    (st, Some(data), res.corrected_positions, String::new())
+                }
+                Err(e @ HammingError::Uncorrectable { .. }) => (Status::Uncorrectable, None, Vec::new(), e.to_string()),
+                Err(e) => (Status::Error, None, Vec::new(), e.to_string()),
+            }
+        }
+        "CRC32" => match crc32::verify_crc32_poly(&r.bits) {
+            Ok(v) if v.valid => (Status::Valid, v.original_message, Vec::new(), String::new()),
+            Ok(_) => (Status::Detected, None, Vec::new(), "CRC inválido".into()),
+            Err(e) => (Status::Error, None, Vec::new(), e),
+        },
+        other => (Status::Error, None, Vec::new(), format!("Algoritmo no soportado: {}", other)),
+    }
+}
+
+// El CSV de PT2 guarda el mensaje como texto ASCII; aquí se compara en bits.
+fn expected_bits(expected: &str) -> String {
+    if expected.chars().all(|c| c == '0' || c == '1') {
+        return expected.to_string();
+    }
+    expected.bytes().map(|b| format!("{:08b}", b)).collect()
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn run_batch<W: Write>(content: &str, default_algo: Option<&str>, default_param: &str, out: &mut W) -> io::Result<BatchSummary> {
+    let records = parse_input(content, default_algo, default_param)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writeln!(out, "frame_idx,linea,algoritmo,param,bits_rx,resultado,correcciones,datos,coincide,detalle")?;
+
+    let mut summary = BatchSummary::default();
+    for (i, rec) in records.into_iter().enumerate() {
+        summary.frames += 1;
+        let (line, algo, param, bits_len, st, corrections, data, matches, detail) = match rec {
+            Ok(r) => {
+                let (st, data, corrected, detail) = decode_record(&r);
+                summary.corrected_bits += corrected.len();
+                let corrections: Vec<String> = corrected.iter().map(|(b, p)| format!("{}:{}", b + 1, p)).collect();
+                let matches = match (&r.expected, &data) {
+                    (Some(exp), Some(d)) => (expected_bits(exp) == *d).to_string(),
+                    (Some(_), None) => "false".to_string(),
+                    _ => String::new(),
+                };
+                (r.line, r.algo, r.param, r.bits.len(), st, corrections.join(" "), data, matches, detail)
+            }
+            Err((line, e)) => (line, String::new(), String::new(), 0, Status::Error, String::new(), None, String::new(), e),
+        };
+        *summary.by_status.entry(st.as_str()).or_insert(0) += 1;
+        if matches == "false" {
+            summary.mismatches += 1;
+        }
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{}",
+            i + 1, line, csv_field(&algo), csv_field(&param), bits_len, st.as_str(), corrections,
+            data.unwrap_or_default(), matches, csv_field(&detail)
+        )?;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_registros_y_bits_crudos() {
+        let h = hamming::encode_stream_code("1011", &HammingCode::from_n(7).unwrap()).unwrap();
+        let c = crc32::append_crc32_poly("10110010").unwrap();
+        let input = format!("# captura\nHAMMING/n=7;pad=0/{}\nCRC32//{}\n0110001\n", h, c);
+        let mut out = Vec::new();
+        let summary = run_batch(&input, Some("hamming"), "n=7", &mut out).expect("batch");
+        assert_eq!(summary.frames, 3);
+        assert_eq!(summary.by_status.get("valido"), Some(&2));
+        assert_eq!(summary.by_status.get("corregido"), Some(&1));
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.lines().nth(3).unwrap().contains(",corregido,1:6,1011,"));
+    }
+
+    #[test]
+    fn batch_formato_csv_con_texto_original() {
+        let h = hamming::encode_stream_code("01000001", &HammingCode::from_n(7).unwrap()).unwrap(); // 'A'
+        let input = format!("mensaje_original,algoritmo,hamming_n,trama\nA,HAMMING,7,{}\nB,HAMMING,7,{}\n", h, h);
+        let mut out = Vec::new();
+        let summary = run_batch(&input, None, "", &mut out).expect("batch");
+        assert_eq!(summary.frames, 2);
+        assert_eq!(summary.mismatches, 1);
+    }
+
+    #[test]
+    fn batch_csv_con_campos_entre_comillas() {
+        assert_eq!(csv_split(r#"1, "Hola, ""mundo""" ,HAMMING,"#).unwrap(), ["1", "Hola, \"mundo\"", "HAMMING", ""]);
+        assert!(csv_split(r#"1,"sin cerrar"#).is_err());
+        assert!(csv_split(r#"1,"a"b"#).is_err());
+        assert!(csv_split(r#"1,a"b"#).is_err());
+
+        let h = hamming::encode_stream_code("01000001", &HammingCode::from_n(7).unwrap()).unwrap(); // 'A'
+        let input = format!(
+            "mensaje_original,algoritmo,param,trama\n\"A\",HAMMING,\"n=7;pad=0\",{}\n\"A, con coma\",HAMMING,n=7,{}\n\"roto,HAMMING,n=7,{}\n",
+            h, h, h
+        );
+        let recs = parse_input(&input, None, "").unwrap();
+        assert_eq!(recs[0].as_ref().unwrap().param, "n=7;pad=0");
+        // La coma entre comillas no corre las columnas
+        assert_eq!(recs[1].as_ref().unwrap().algo, "HAMMING");
+        assert_eq!(recs[1].as_ref().unwrap().expected.as_deref(), Some("A, con coma"));
+        assert!(recs[2].as_ref().unwrap_err().1.contains("comilla sin cerrar"));
+
+        let mut out = Vec::new();
+        let summary = run_batch(&input, None, "", &mut out).expect("batch");
+        assert_eq!(summary.by_status.get("valido"), Some(&2));
+        assert_eq!(summary.by_status.get("error"), Some(&1));
+        assert_eq!(summary.mismatches, 1);
+    }
+}
@@ -0,0 +1,355 @@
+// Modo no interactivo (subcomandos) para usar el receptor desde scripts.
+//
+//   receptor decode hamming --n 7 [--k 4] [--explain] [--partial] [BITS | --file RUTA | -]
+//   receptor verify crc32 [--explain] [BITS | --file RUTA | -]
+//   receptor encode hamming --n 7 [--k 4] [BITS | --file RUTA | -]
+//   receptor encode crc32 [BITS | --file RUTA | -]
+//   receptor matrices --n 7 [--systematic] [--format text|csv|latex] [--out RUTA]
+//   receptor batch ENTRADA [--out RESULTADOS.csv] [--algo hamming|crc32] [--n 7]
+//
+// La salida útil (datos o trama) va a stdout; diagnósticos y trazas a stderr.
+// Si no se da BITS ni --file, la trama se lee de stdin.
+
+use std::io::Read;
+
+use crate::batch;
+use crate::correction::hamming::{self, BlockStatus, HammingCode, HammingError};
+use crate::correction::hamming_matrix::{self, MatrixForm, MatrixFormat};
+use crate::detection::crc32;
+use crate::explain;
+
+// Códigos de salida
+pub const EXIT_VALID: i32 = 0; // trama sin errores
+pub const EXIT_CORRECTED: i32 = 1; // Hamming corrigió al menos un bit
+pub const EXIT_UNCORRECTABLE: i32 = 2; // Hamming detectó errores que no puede corregir
+pub const EXIT_DETECTED: i32 = 3; // CRC-32 detectó errores (trama descartada)
+pub const EXIT_USAGE: i32 = 64; // argumentos inválidos
+pub const EXIT_NOINPUT: i32 = 66; // no se pudo leer la entrada
+pub const EXIT_CANTCREAT: i32 = 73; // no se pudo escribir la salida
+
+const USAGE: &str = "Uso:
+  receptor                                   (modo interactivo)
+  receptor decode hamming --n N [--k K] [--explain] [--partial] [BITS | --file RUTA | -]
+  receptor verify crc32 [--explain] [BITS | --file RUTA | -]
+  receptor encode hamming --n N [--k K] [BITS | --file RUTA | -]
+  receptor encode crc32 [BITS | --file RUTA | -]
+  receptor matrices --n N [--systematic] [--format text|csv|latex] [--out RUTA]
+  receptor batch ENTRADA [--out RESULTADOS.csv] [--algo hamming|crc32] [--n N]
+
+Con --partial, los bloques no corregibles no descartan la trama: sus bits de datos
+salen como '?' y el resto se entrega igual (código de salida 2).
+
+Códigos de salida: 0 válido, 1 corregido, 2 no corregible, 3 error detectado (CRC),
+                   64 uso incorrecto, 66 entrada ilegible, 73 salida no escribible";
+
+#[derive(Debug, Default)]
+struct Options {
+    positional: Vec<String>,
+    n: Option<usize>,
+    k: Option<usize>,
+    file: Option<String>,
+    out: Option<String>,
+    format: Option<String>,
+    algo: Option<String>,
+    explain: bool,
+    systematic: bool,
+    partial: bool,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut opts = Options::default();
+    let mut it = args.iter();
+    while let Some(a) = it.next() {
+        let mut value = |name: &str| {
+            it.next().cloned().ok_or_else(|| format!("Falta valor para {}", name))
+        };
+        match a.as_str() {
+            "--n" | "-n" => {
+                let v = value("--n")?;
+                opts.n = Some(v.parse().map_err(|_| format!("Valor de n inválido: {}", v))?);
+            }
+            "--k" | "-k" => {
+                let v = value("--k")?;
+                opts.k = Some(v.parse().map_err(|_| format!("Valor de k inválido: {}", v))?);
+            }
+            "--file" | "-f" => opts.file = Some(value("--file")?),
+            "--out" | "-o" => opts.out = Some(value("--out")?),
+            "--format" => opts.format = Some(value("--format")?),
+            "--algo" => opts.algo = Some(value("--algo")?),
+            "--explain" => opts.explain = true,
+            "--systematic" => opts.systematic = true,
+            "--partial" => opts.partial = true,
+            "-" => opts.positional.push("-".into()),
+            s if s.starts_with("--") => return Err(format!("Opción desconocida: {}", s)),
+            s => opts.positional.push(s.to_string()),
+        }
+    }
+    Ok(opts)
+}
+
+fn require_n(opts: &Options) -> Result<usize, String> {
+    match opts.n {
+        Some(n) if n >= 3 => Ok(n),
+        Some(_) => Err("Valor de n inválido (debe ser >= 3).".into()),
+        None => Err("Falta --n".into()),
+    }
+}
+
+// Código (n, k) de --n y --k; sin --k, con el mínimo de bits de paridad.
+fn require_code(opts: &Options) -> Result<HammingCode, (i32, String)> {
+    let n = require_n(opts).map_err(|e| (EXIT_USAGE, e))?;
+    match opts.k {
+        Some(k) => HammingCode::new(n, k),
+        None => HammingCode::from_n(n),
+    }
+    .map_err(|e| (EXIT_USAGE, e.to_string()))
+}
+
+// Obtiene la trama del argumento, de --file o de stdin (ignorando espacios y saltos).
+fn read_bits(opts: &Options, arg: Option<&String>) -> Result<String, (i32, String)> {
+    let raw = match (arg.map(|s| s.as_str()), &opts.file) {
+        (Some(_), Some(_)) => return Err((EXIT_USAGE, "Use BITS o --file, no ambos".into())),
+        (Some("-"), None) | (None, None) => {
+            let mut s = String::new();
+            std::io::stdin()
+                .read_to_string(&mut s)
+                .map_err(|e| (EXIT_NOINPUT, format!("No se pudo leer stdin: {}", e)))?;
+            s
+        }
+        (Some(bits), None) => bits.to_string(),
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .map_err(|e| (EXIT_NOINPUT, format!("No se pudo leer {}: {}", path, e)))?,
+    };
+    let bits: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+    if bits.is_empty() || !bits.chars().all(|c| c == '0' || c == '1') {
+        return Err((EXIT_USAGE, "La trama debe contener solo caracteres '0' y '1'.".into()));
+    }
+    Ok(bits)
+}
+
+fn decode_hamming(opts: &Options, arg: Option<&String>) -> Result<i32, (i32, String)> {
+    let code = require_code(opts)?;
+    let n = code.n;
+    let bits = read_bits(opts, arg)?;
+    if !bits.len().is_multiple_of(n) {
+        return Err((EXIT_USAGE, format!("La longitud de la trama ({}) no es múltiplo de n={}.", bits.len(), n)));
+    }
+    if opts.explain {
+        let traces = hamming::trace_stream(&bits, &code).map_err(|e| (EXIT_USAGE, e.to_string()))?;
+        eprint!("{}", explain::render_hamming_trace(&traces));
+    }
+    if opts.partial {
+        return decode_hamming_partial(&bits, &code);
+    }
+    match hamming::decode_stream_code(&bits, &code) {
+        Ok(res) => {
+            println!("{}", res.data_bits);
+            if res.corrected_positions.is_empty() {
+                Ok(EXIT_VALID)
+            } else {
+                for (block_idx, pos) in res.corrected_positions {
+                    eprintln!("corregido: bloque {}, bit {}", block_idx + 1, pos);
+                }
+                Ok(EXIT_CORRECTED)
+            }
+        }
+        Err(e @ HammingError::Uncorrectable { .. }) => {
+            eprintln!("no corregible: {}", e);
+            Ok(EXIT_UNCORRECTABLE)
+        }
+        Err(e) => Err((EXIT_USAGE, e.to_string())),
+    }
+}
+
+// Entrega parcial: los datos de los bloques no corregibles se marcan con '?'.
+fn decode_hamming_partial(bits: &str, code: &HammingCode) -> Result<i32, (i32, String)> {
+    let res = hamming::decode_stream_partial(bits, code).map_err(|e| (EXIT_USAGE, e.to_string()))?;
+    println!("{}", res.data_bits_marked('?'));
+    for (block_idx, pos) in res.corrected_positions() {
+        eprintln!("corregido: bloque {}, bit {}", block_idx + 1, pos);
+    }
+    for b in res.uncorrectable_blocks() {
+        if let BlockStatus::Uncorrectable(syndrome) = res.blocks[b] {
+            eprintln!("no corregible: bloque {}, síndrome {} (datos marcados con '?')", b + 1, syndrome);
+        }
+    }
+    Ok(if !res.is_complete() {
+        EXIT_UNCORRECTABLE
+    } else if res.corrected_positions().is_empty() {
+        EXIT_VALID
+    } else {
+        EXIT_CORRECTED
+    })
+}
+
+fn verify_crc(opts: &Options, arg: Option<&String>) -> Result<i32, (i32, String)> {
+    let bits = read_bits(opts, arg)?;
+    if opts.explain {
+        let trace = crc32::trace_crc32_poly(&bits).map_err(|e| (EXIT_USAGE, e))?;
+        eprint!("{}", explain::render_crc_trace(&trace));
+    }
+    let v = crc32::verify_crc32_poly(&bits).map_err(|e| (EXIT_USAGE, e))?;
+    if v.valid {
+        println!("{}", v.original_message.unwrap_or_default());
+        Ok(EXIT_VALID)
+    } else {
+        eprintln!("CRC inválido: trama descartada");
+        Ok(EXIT_DETECTED)
+    }
+}
+
+fn encode(opts: &Options, algo: &str, arg: Option<&String>) -> Result<i32, (i32, String)> {
+    match algo {
+        "hamming" => {
+            let code = require_code(opts)?;
+            let bits = read_bits(opts, arg)?;
+            let (frame, pad) = hamming::encode_stream_padded(&bits, &code).map_err(|e| (EXIT_USAGE, e))?;
+            println!("{}", frame);
+            match opts.k {
+                Some(k) => eprintln!("PARAM=n={};k={};pad={}", code.n, k, pad),
+                None => eprintln!("PARAM=n={};pad={}", code.n, pad),
+            }
+        }
+        "crc32" => {
+            let bits = read_bits(opts, arg)?;
+            let frame = crc32::append_crc32_poly(&bits).map_err(|e| (EXIT_USAGE, e))?;
+            println!("{}", frame);
+        }
+        other => return Err((EXIT_USAGE, format!("Algoritmo no soportado: {}", other))),
+    }
+    Ok(EXIT_VALID)
+}
+
+fn matrices(opts: &Options) -> Result<i32, (i32, String)> {
+    let n = opts.n.ok_or((EXIT_USAGE, "Falta --n".to_string()))?;
+    let form = if opts.systematic { MatrixForm::Systematic } else { MatrixForm::NonSystematic };
+    let format = match &opts.format {
+        Some(f) => MatrixFormat::parse(f).map_err(|e| (EXIT_USAGE, e))?,
+        None => MatrixFormat::Text,
+    };
+    let report = hamming_matrix::render_report(n, form, format).map_err(|e| (EXIT_USAGE, e))?;
+    match &opts.out {
+        Some(path) => std::fs::write(path, report)
+            .map_err(|e| (EXIT_CANTCREAT, format!("No se pudo escribir {}: {}", path, e)))?,
+        None => print!("{}", report),
+    }
+    Ok(EXIT_VALID)
+}
+
+// Procesa un archivo de tramas; el resumen va a stdout salvo que los resultados
+// también vayan ahí (sin --out), en cuyo caso va a stderr.
+fn batch(opts: &Options) -> Result<i32, (i32, String)> {
+    let input = match (opts.positional.first(), &opts.file) {
+        (Some(p), None) | (None, Some(p)) => p,
+        _ => return Err((EXIT_USAGE, "Indique un único archivo de entrada".into())),
+    };
+    let content = std::fs::read_to_string(input)
+        .map_err(|e| (EXIT_NOINPUT, format!("No se pudo leer {}: {}", input, e)))?;
+    let default_param = opts.n.map(|n| format!("n={}", n)).unwrap_or_default();
+    let algo = opts.algo.as_deref();
+
+    match &opts.out {
+        Some(path) => {
+            let cant_create = |e: std::io::Error| (EXIT_CANTCREAT, format!("No se pudo escribir {}: {}", path, e));
+            let mut f = std::io::BufWriter::new(std::fs::File::create(path).map_err(cant_create)?);
+            let s = batch::run_batch(&content, algo, &default_param, &mut f).map_err(|e| (EXIT_USAGE, e.to_string()))?;
+            std::io::Write::flush(&mut f).map_err(cant_create)?;
+            print!("{}", s.render());
+        }
+        None => {
+            let s = batch::run_batch(&content, algo, &default_param, &mut std::io::stdout().lock())
+                .map_err(|e| (EXIT_USAGE, e.to_string()))?;
+            eprint!("{}", s.render());
+        }
+    }
+    Ok(EXIT_VALID)
+}
+
+// Ejecuta un subcomando y devuelve el código de salida del proceso.
+pub fn run(args: &[String]) -> i32 {
+    let (cmd, rest) = match args.split_first() {
+        Some((c, r)) => (c.as_str(), r),
+        None => ("", args),
+    };
+    if matches!(cmd, "-h" | "--help" | "help") {
+        println!("{}", USAGE);
+        return EXIT_VALID;
+    }
+    let opts = match parse_options(rest) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+    let algo = opts.positional.first().map(|s| s.to_ascii_lowercase());
+    let arg = opts.positional.get(1);
+
+    let result = match (cmd, algo.as_deref()) {
+        ("decode", Some("hamming")) => decode_hamming(&opts, arg),
+        ("verify", Some("crc32")) | ("decode", Some("crc32")) => verify_crc(&opts, arg),
+        ("encode", Some(a)) => encode(&opts, a, arg),
+        ("matrices", None) => matrices(&opts),
+        ("batch", _) => batch(&opts),
+        _ => Err((EXIT_USAGE, format!("Subcomando inválido: {}", args.join(" ")))),
+    };
+    match result {
+        Ok(code) => code,
+        Err((code, msg)) => {
+            if code == EXIT_USAGE {
+                eprintln!("{}\n\n{}", msg, USAGE);
+            } else {
+                eprintln!("{}", msg);
+            }
+            code
+        }
+    }
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_args(args: &[&str]) -> i32 {
+        run(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    fn flip(bits: &str, idx: usize) -> String {
+        bits.char_indices().map(|(i, c)| if i == idx { if c == '0' { '1' } else { '0' } } else { c }).collect()
+    }
+
+    #[test]
+    fn codigos_de_salida_al_decodificar() {
+        let h = hamming::encode_stream_code("10110010", &HammingCode::from_n(7).unwrap()).unwrap();
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", &h]), EXIT_VALID);
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", "--explain", &flip(&h, 5)]), EXIT_CORRECTED);
+        // Dos errores en (12, 8) con síndrome 13: detectados, no corregibles
+        let h12 = hamming::encode_stream_code("10110010", &HammingCode::from_n(12).unwrap()).unwrap();
+        let bad = flip(&flip(&h12, 4), 7);
+        assert_eq!(run_args(&["decode", "hamming", "--n", "12", &bad]), EXIT_UNCORRECTABLE);
+        assert_eq!(run_args(&["decode", "hamming", "--n", "12", "--partial", &bad]), EXIT_UNCORRECTABLE);
+
+        let c = crc32::append_crc32_poly("10110010").unwrap();
+        assert_eq!(run_args(&["verify", "crc32", &c]), EXIT_VALID);
+        assert_eq!(run_args(&["verify", "crc32", "--explain", &flip(&c, 3)]), EXIT_DETECTED);
+    }
+
+    #[test]
+    fn codigos_de_salida_por_uso_incorrecto() {
+        assert_eq!(run_args(&["decode"]), EXIT_USAGE);
+        assert_eq!(run_args(&["decode", "hamming", "1011010"]), EXIT_USAGE); // falta --n
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", "--k", "5", "1011010"]), EXIT_USAGE);
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", "10110"]), EXIT_USAGE); // no es múltiplo de n
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", "10a1010"]), EXIT_USAGE);
+        assert_eq!(run_args(&["verify", "crc32", "--bogus", "1"]), EXIT_USAGE);
+        assert_eq!(run_args(&["encode", "hamming", "--n", "7", "--k", "9", "1"]), EXIT_USAGE);
+        // n enorme: error de parámetros, no desborde ni una reserva de memoria gigante
+        assert_eq!(run_args(&["decode", "hamming", "--n", "18446744073709551615", "1011"]), EXIT_USAGE);
+        assert_eq!(run_args(&["encode", "hamming", "--n", "1099511627776", "1011"]), EXIT_USAGE);
+        assert_eq!(run_args(&["decode", "hamming", "--n", "7", "--file", "/no/existe.txt"]), EXIT_NOINPUT);
+        // La traza de --explain también falla con una trama que no se puede decodificar
+        assert_eq!(run_args(&["verify", "crc32", "--explain", "0101"]), EXIT_USAGE);
+    }
+}
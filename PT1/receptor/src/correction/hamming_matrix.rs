@@ -0,0 +1,362 @@
+// Vista matricial de los códigos de Hamming que usa `hamming.rs`.
+// - H (no sistemática): la columna j (1-based) es la representación binaria de j,
+//   exactamente la comprobación que hace `decode_block` con máscaras de bits.
+// - G (no sistemática): la fila i es la codificación del vector unitario e_i.
+// - Forma sistemática: se reordenan las columnas poniendo primero las posiciones
+//   de datos y luego las de paridad, quedando G = [I_k | P] y H = [P^T | I_r].
+
+use super::hamming::{data_positions, encode_block, format_ranges, parity_bits_count, HammingCode};
+
+// n máximo soportado (las palabras código se manejan como máscaras u64).
+const MAX_N: usize = 63;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixForm {
+    NonSystematic,
+    Systematic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    Text,
+    Csv,
+    Latex,
+}
+
+impl MatrixFormat {
+    pub fn parse(s: &str) -> Result<MatrixFormat, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "texto" | "txt" => Ok(MatrixFormat::Text),
+            "csv" => Ok(MatrixFormat::Csv),
+            "latex" | "tex" => Ok(MatrixFormat::Latex),
+            other => Err(format!("Formato desconocido: {} (use text, csv o latex)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    pub rows: Vec<Vec<u8>>,
+}
+
+impl BitMatrix {
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.rows.first().map(|r| r.len()).unwrap_or(0)
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|b| b.to_string()).collect();
+            out.push_str(&format!("[ {} ]\n", cells.join(" ")));
+        }
+        out
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(|b| b.to_string()).collect();
+            out.push_str(&cells.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_latex(&self) -> String {
+        let mut out = String::from("\\begin{pmatrix}\n");
+        for (i, row) in self.rows.iter().enumerate() {
+            let cells: Vec<String> = row.iter().map(|b| b.to_string()).collect();
+            out.push_str("  ");
+            out.push_str(&cells.join(" & "));
+            if i + 1 < self.rows.len() {
+                out.push_str(" \\\\");
+            }
+            out.push('\n');
+        }
+        out.push_str("\\end{pmatrix}\n");
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HammingMatrices {
+    pub n: usize,
+    pub k: usize,
+    pub r: usize,
+    pub column_order: Vec<usize>, // posición original (1-based) de cada columna
+    pub g: BitMatrix,
+    pub h: BitMatrix,
+}
+
+fn validate_n(n: usize) -> Result<(), String> {
+    if !(3..=MAX_N).contains(&n) {
+        return Err(format!("n={} fuera de rango (3..={})", n, MAX_N));
+    }
+    Ok(())
+}
+
+// Construye G y H para el código de Hamming de longitud n en la forma pedida.
+pub fn build_matrices(n: usize, form: MatrixForm) -> Result<HammingMatrices, String> {
+    validate_n(n)?;
+    let r = parity_bits_count(n);
+    let k = n - r;
+    let data_pos = data_positions(n);
+    let positions = HammingCode::from_n(n).map_err(|e| e.to_string())?.positions();
+
+    match form {
+        MatrixForm::NonSystematic => {
+            let mut g_rows = Vec::with_capacity(k);
+            for i in 0..k {
+                let mut unit = vec![0u8; k];
+                unit[i] = 1;
+                g_rows.push(encode_block(&unit, &positions)?);
+            }
+            // Fila i de H: bit i de cada posición 1..=n
+            let h_rows = (0..r)
+                .map(|i| (1..=n).map(|pos| ((pos >> i) & 1) as u8).collect())
+                .collect();
+            Ok(HammingMatrices {
+                n,
+                k,
+                r,
+                column_order: (1..=n).collect(),
+                g: BitMatrix { rows: g_rows },
+                h: BitMatrix { rows: h_rows },
+            })
+        }
+        MatrixForm::Systematic => {
+            // P[j][i] = 1 si la posición de datos j participa en la paridad 2^i
+            let p: Vec<Vec<u8>> = data_pos
+                .iter()
+                .map(|&d| (0..r).map(|i| ((d >> i) & 1) as u8).collect())
+                .collect();
+            let mut g_rows = Vec::with_capacity(k);
+            for (j, p_row) in p.iter().enumerate() {
+                let mut row = vec![0u8; n];
+                row[j] = 1;
+                row[k..].copy_from_slice(p_row);
+                g_rows.push(row);
+            }
+            let mut h_rows = Vec::with_capacity(r);
+            for i in 0..r {
+                let mut row = vec![0u8; n];
+                for (j, p_row) in p.iter().enumerate() {
+                    row[j] = p_row[i];
+                }
+                row[k + i] = 1;
+                h_rows.push(row);
+            }
+            let mut column_order = data_pos.clone();
+            column_order.extend((0..r).map(|i| 1usize << i));
+            Ok(HammingMatrices {
+                n,
+                k,
+                r,
+                column_order,
+                g: BitMatrix { rows: g_rows },
+                h: BitMatrix { rows: h_rows },
+            })
+        }
+    }
+}
+
+fn row_mask(row: &[u8]) -> u64 {
+    row.iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, &b)| if b == 1 { acc | (1u64 << i) } else { acc })
+}
+
+// Distribución de pesos de todas las combinaciones lineales de las filas.
+fn span_weights(rows: &[Vec<u8>], n: usize) -> Vec<u64> {
+    let masks: Vec<u64> = rows.iter().map(|r| row_mask(r)).collect();
+    let mut weights = vec![0u64; n + 1];
+    for sel in 0u64..(1u64 << masks.len()) {
+        let mut word = 0u64;
+        for (i, m) in masks.iter().enumerate() {
+            if (sel >> i) & 1 == 1 {
+                word ^= m;
+            }
+        }
+        weights[word.count_ones() as usize] += 1;
+    }
+    weights
+}
+
+fn binomial(n: i128, k: i128) -> i128 {
+    if k < 0 || k > n {
+        return 0;
+    }
+    let mut acc: i128 = 1;
+    for i in 0..k {
+        acc = acc * (n - i) / (i + 1);
+    }
+    acc
+}
+
+// Polinomio de Krawtchouk K_i(j) para longitud n.
+fn krawtchouk(i: usize, j: usize, n: usize) -> i128 {
+    let (i, j, n) = (i as i128, j as i128, n as i128);
+    (0..=i)
+        .map(|s| {
+            let sign = if s % 2 == 0 { 1 } else { -1 };
+            sign * binomial(j, s) * binomial(n - j, i - s)
+        })
+        .sum()
+}
+
+// Enumerador de pesos A_0..A_n del código.
+// Se enumera el código dual (2^r palabras, generado por H) y se aplica la
+// identidad de MacWilliams: A_i = 2^-r * sum_j B_j * K_i(j).
+pub fn weight_enumerator(n: usize) -> Result<Vec<u64>, String> {
+    let m = build_matrices(n, MatrixForm::NonSystematic)?;
+    let dual = span_weights(&m.h.rows, n);
+    let dual_size: i128 = 1i128 << m.r;
+    let mut a = vec![0u64; n + 1];
+    for (i, slot) in a.iter_mut().enumerate() {
+        let sum: i128 = dual
+            .iter()
+            .enumerate()
+            .map(|(j, &b)| b as i128 * krawtchouk(i, j, n))
+            .sum();
+        *slot = (sum / dual_size) as u64;
+    }
+    Ok(a)
+}
+
+// Distancia mínima: menor peso no nulo presente en el enumerador.
+pub fn minimum_distance(n: usize) -> Result<usize, String> {
+    let a = weight_enumerator(n)?;
+    a.iter()
+        .enumerate()
+        .skip(1)
+        .find(|(_, &c)| c > 0)
+        .map(|(w, _)| w)
+        .ok_or_else(|| "El código no tiene palabras no nulas".to_string())
+}
+
+// Formatea el enumerador como polinomio: A(z) = 1 + 7z^3 + 7z^4 + z^7
+pub fn format_enumerator(a: &[u64]) -> String {
+    let mut terms = Vec::new();
+    for (w, &c) in a.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+        let term = match (w, c) {
+            (0, _) => c.to_string(),
+            (1, 1) => "z".to_string(),
+            (1, _) => format!("{}z", c),
+            (_, 1) => format!("z^{}", w),
+            _ => format!("{}z^{}", c, w),
+        };
+        terms.push(term);
+    }
+    format!("A(z) = {}", terms.join(" + "))
+}
+
+// Reporte completo (G, H, d_min y enumerador) en el formato pedido.
+pub fn render_report(n: usize, form: MatrixForm, format: MatrixFormat) -> Result<String, String> {
+    let m = build_matrices(n, form)?;
+    let a = weight_enumerator(n)?;
+    let d = minimum_distance(n)?;
+    let form_name = match form {
+        MatrixForm::NonSystematic => "no sistemática",
+        MatrixForm::Systematic => "sistemática",
+    };
+    let order: Vec<String> = m.column_order.iter().map(|p| p.to_string()).collect();
+    let code = HammingCode::from_n(n).map_err(|e| e.to_string())?;
+    let shortened = if code.is_shortened() {
+        let s = format_ranges(&code.uncorrectable_syndromes());
+        format!("acortado de ({}, {}); síndromes {} = errores no corregibles", (1usize << code.r) - 1, (1usize << code.r) - 1 - code.r, s)
+    } else {
+        "perfecto (todo síndrome no nulo es corregible)".to_string()
+    };
+
+    let out = match format {
+        MatrixFormat::Text => format!(
+            "Hamming({}, {}) – forma {}\nCódigo {}\nOrden de columnas (posiciones originales): {}\n\nG ({}x{}):\n{}\nH ({}x{}):\n{}\nDistancia mínima: {}\n{}\n",
+            m.n, m.k, form_name, shortened, order.join(" "),
+            m.g.n_rows(), m.g.n_cols(), m.g.to_text(),
+            m.h.n_rows(), m.h.n_cols(), m.h.to_text(),
+            d, format_enumerator(&a)
+        ),
+        MatrixFormat::Csv => {
+            let weights: Vec<String> = a.iter().map(|c| c.to_string()).collect();
+            format!(
+                "# Hamming({},{}) {}\n# columnas,{}\n# G\n{}# H\n{}# d_min,{}\n# A_0..A_n,{}\n",
+                m.n, m.k, form_name, order.join(","),
+                m.g.to_csv(), m.h.to_csv(), d, weights.join(",")
+            )
+        }
+        MatrixFormat::Latex => format!(
+            "% Hamming({}, {}) -- forma {}\nG = {}\nH = {}\n% d_{{min}} = {}\n% {}\n",
+            m.n, m.k, form_name, m.g.to_latex(), m.h.to_latex(), d, format_enumerator(&a)
+        ),
+    };
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Producto M * N^T sobre GF(2). Sirve para comprobar G * H^T = 0.
+    fn mul_transpose(a: &BitMatrix, b: &BitMatrix) -> BitMatrix {
+        let rows = a
+            .rows
+            .iter()
+            .map(|x| {
+                b.rows
+                    .iter()
+                    .map(|y| x.iter().zip(y.iter()).fold(0u8, |acc, (p, q)| acc ^ (p & q)))
+                    .collect()
+            })
+            .collect();
+        BitMatrix { rows }
+    }
+
+    fn is_zero(m: &BitMatrix) -> bool {
+        m.rows.iter().all(|r| r.iter().all(|&b| b == 0))
+    }
+
+    #[test]
+    fn matrices_ortogonales_ambas_formas() {
+        for n in [3, 7, 12, 15, 31] {
+            for form in [MatrixForm::NonSystematic, MatrixForm::Systematic] {
+                let m = build_matrices(n, form).expect("matrices");
+                assert_eq!(m.g.n_rows(), m.k);
+                assert_eq!(m.h.n_rows(), m.r);
+                assert!(is_zero(&mul_transpose(&m.g, &m.h)), "G*H^T debe ser 0 (n={})", n);
+            }
+        }
+    }
+
+    #[test]
+    fn enumerador_hamming_7_4() {
+        let a = weight_enumerator(7).expect("enumerador");
+        assert_eq!(a, vec![1, 0, 0, 7, 7, 0, 0, 1]);
+        assert_eq!(minimum_distance(7).unwrap(), 3);
+        assert_eq!(format_enumerator(&a), "A(z) = 1 + 7z^3 + 7z^4 + z^7");
+    }
+
+    #[test]
+    fn enumerador_coincide_con_fuerza_bruta() {
+        for n in [5, 10, 15] {
+            let m = build_matrices(n, MatrixForm::NonSystematic).unwrap();
+            let bruta = span_weights(&m.g.rows, n);
+            assert_eq!(weight_enumerator(n).unwrap(), bruta, "n={}", n);
+        }
+    }
+
+    #[test]
+    fn formatos_de_exportacion() {
+        let m = build_matrices(7, MatrixForm::Systematic).unwrap();
+        assert_eq!(m.g.to_csv().lines().next().unwrap(), "1,0,0,0,1,1,0");
+        assert!(m.h.to_latex().starts_with("\\begin{pmatrix}"));
+        assert!(build_matrices(2, MatrixForm::Systematic).is_err());
+    }
+}
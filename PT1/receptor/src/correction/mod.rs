@@ -1 +1,2 @@
 pub mod hamming;
+pub mod hamming_matrix;
@@ -11,6 +11,25 @@ pub struct CrcVerify {
     pub original_message: Option<String>, // presente cuando valid = true
 }
 
+// Un paso de la división larga: XOR del divisor alineado en `offset`. Solo cambian
+// los bits bajo el divisor, así que se guarda esa ventana y no todo el dividendo
+// (la de antes del XOR es `after ^ divisor`).
+#[derive(Debug)]
+pub struct DivisionStep {
+    pub offset: usize,  // índice (0-based) del bit líder donde se alinea G(x)
+    pub after: Vec<u8>, // bits offset..offset+33 después del XOR
+}
+
+// Traza completa de la verificación (modo explicación).
+#[derive(Debug)]
+pub struct CrcTrace {
+    pub dividend: Vec<u8>, // la trama recibida
+    pub divisor: Vec<u8>,
+    pub steps: Vec<DivisionStep>,
+    pub remainder: Vec<u8>,
+    pub valid: bool,
+}
+
 fn parse_bits(s: &str) -> Result<Vec<u8>, String> {
     if !s.chars().all(|c| c == '0' || c == '1') {
         return Err("Solo se aceptan '0' y '1'".to_string());
@@ -38,7 +57,13 @@ fn crc32_poly_bits() -> Vec<u8> {
 
 // División módulo-2 sobre bits MSB->LSB.
 // Modifica una copia del dividendo haciendo XOR con el divisor alineado cuando el bit líder es 1.
-fn mod2_divide(mut dividend: Vec<u8>, divisor: &[u8]) -> Vec<u8> {
+fn mod2_divide(dividend: Vec<u8>, divisor: &[u8]) -> Vec<u8> {
+    mod2_divide_steps(dividend, divisor, |_, _| {})
+}
+
+// La división de mod2_divide; `on_step` recibe cada alineación XOR (offset y la
+// ventana de m bits ya modificada), para la traza.
+fn mod2_divide_steps(mut dividend: Vec<u8>, divisor: &[u8], mut on_step: impl FnMut(usize, &[u8])) -> Vec<u8> {
     let n = dividend.len();
     let m = divisor.len();
     if n < m {
@@ -49,12 +74,27 @@ fn mod2_divide(mut dividend: Vec<u8>, divisor: &[u8]) -> Vec<u8> {
             for j in 0..m {
                 dividend[i + j] ^= divisor[j];
             }
+            on_step(i, &dividend[i..i + m]);
         }
     }
     // residuo: últimos m-1 bits
     dividend[(n - (m - 1))..].to_vec()
 }
 
+pub fn trace_crc32_poly(received_bits: &str) -> Result<CrcTrace, String> {
+    let bits = parse_bits(received_bits)?;
+    if bits.len() < 33 {
+        return Err("La trama debe tener al menos 33 bits (>= 1 de datos + 32 de CRC).".into());
+    }
+    let divisor = crc32_poly_bits();
+    let mut steps = Vec::new();
+    let remainder = mod2_divide_steps(bits.clone(), &divisor, |offset, after| {
+        steps.push(DivisionStep { offset, after: after.to_vec() });
+    });
+    let valid = remainder.iter().all(|&b| b == 0);
+    Ok(CrcTrace { dividend: bits, divisor, steps, remainder, valid })
+}
+
 pub fn verify_crc32_poly(received_bits: &str) -> Result<CrcVerify, String> {
     let bits = parse_bits(received_bits)?;
     if bits.len() < 33 {
@@ -73,16 +113,15 @@ pub fn verify_crc32_poly(received_bits: &str) -> Result<CrcVerify, String> {
     }
 }
 
-// --------------------------------- Tests ---------------------------------
-// === Helpers para pruebas (emulan al emisor) ===
-fn append_crc32_poly(msg_bits_str: &str) -> Result<String, String> {
+// === Emisor CRC-32 (subcomando `encode` y pruebas) ===
+pub fn append_crc32_poly(msg_bits_str: &str) -> Result<String, String> {
     let mut msg = parse_bits(msg_bits_str)?;
     // Asegurar que el mensaje tenga al menos 1 bit para que verificación (>=33) sea válida
     if msg.is_empty() {
-        return Err("El mensaje para CRC no puede ser vacío en estas pruebas".into());
+        return Err("El mensaje para CRC no puede ser vacío".into());
     }
     // Padding: agregar 32 ceros (grado del polinomio)
-    msg.extend(std::iter::repeat(0u8).take(32));
+    msg.extend(std::iter::repeat_n(0u8, 32));
     let divisor = crc32_poly_bits();
     let remainder = mod2_divide(msg.clone(), &divisor); // 32 bits
     if remainder.len() != 32 {
@@ -94,6 +133,7 @@ fn append_crc32_poly(msg_bits_str: &str) -> Result<String, String> {
     Ok(bits_to_string(&original_msg))
 }
 
+// --------------------------------- Tests ---------------------------------
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,4 +194,27 @@ mod tests {
             assert!(!v.valid, "debería ser inválido por 2+ errores");
         }
     }
+
+    #[test]
+    fn crc_traza_coincide_con_division() {
+        let codeword = append_crc32_poly("10110010").expect("emisor crc");
+        let t = trace_crc32_poly(&codeword).expect("traza");
+        assert!(t.valid);
+        assert_eq!(t.steps[0].offset, 0);
+        let tampered = flip_bit_at(codeword, 3);
+        let t = trace_crc32_poly(&tampered).expect("traza");
+        let bits = parse_bits(&tampered).unwrap();
+        assert_eq!(t.remainder, mod2_divide(bits.clone(), &crc32_poly_bits()));
+        assert!(!t.valid);
+        let mut work = bits.clone();
+        for s in &t.steps {
+            assert_eq!(s.after.len(), 33, "solo se guarda la ventana bajo el divisor");
+            let before: Vec<u8> = s.after.iter().zip(&t.divisor).map(|(a, d)| a ^ d).collect();
+            assert_eq!(before, work[s.offset..s.offset + 33]);
+            assert_eq!(before[0], 1, "solo se alinea sobre un bit líder en 1");
+            assert_eq!(s.after[0], 0);
+            work[s.offset..s.offset + 33].copy_from_slice(&s.after);
+        }
+        assert_eq!(work[work.len() - 32..], t.remainder);
+    }
 }
@@ -0,0 +1,79 @@
+// Modo explicación: convierte las trazas de los decodificadores en texto anotado
+// para seguir paso a paso lo que hicieron decode_block y mod2_divide.
+
+use crate::correction::hamming::{format_ranges, BlockTrace};
+use crate::detection::crc32::CrcTrace;
+
+fn bits_str(bits: &[u8]) -> String {
+    bits.iter().map(|&b| if b == 1 {'1'} else {'0'}).collect()
+}
+
+fn join_usize(v: &[usize]) -> String {
+    v.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(",")
+}
+
+pub fn render_hamming_trace(traces: &[BlockTrace]) -> String {
+    let mut out = String::new();
+    for (b, t) in traces.iter().enumerate() {
+        out.push_str(&format!("Bloque {}: {}\n", b + 1, bits_str(&t.block)));
+        for c in &t.checks {
+            let vals: Vec<String> = c.covered.iter().map(|&pos| t.block[pos - 1].to_string()).collect();
+            let verdict = if c.value == 0 { "ok" } else { "FALLA" };
+            out.push_str(&format!(
+                "  Grupo p{} (posiciones {}): bits {} -> XOR = {} [{}]\n",
+                c.parity_pos, join_usize(&c.covered), vals.join(","), c.value, verdict
+            ));
+        }
+        // Síndrome leído de la paridad más alta a la más baja (p_{2^(r-1)} ... p1)
+        let syn_bits: String = t.checks.iter().rev().map(|c| if c.value == 1 {'1'} else {'0'}).collect();
+        out.push_str(&format!("  Síndrome = {} (binario) = {}\n", syn_bits, t.syndrome));
+        match &t.result {
+            Ok((data, corrected)) => {
+                match corrected {
+                    Some(pos) => {
+                        let mut fixed = t.block.clone();
+                        fixed[pos - 1] ^= 1;
+                        out.push_str(&format!("  Se invierte el bit {} -> bloque corregido: {}\n", pos, bits_str(&fixed)));
+                    }
+                    None => out.push_str("  Síndrome 0: bloque sin errores\n"),
+                }
+                let positions = t.code.positions();
+                let data_pos: Vec<usize> = (1..=t.block.len()).filter(|&i| !positions[i - 1].is_power_of_two()).collect();
+                out.push_str(&format!("  Datos extraídos (posiciones {}): {}\n", join_usize(&data_pos), bits_str(data)));
+            }
+            Err(syndrome) => {
+                out.push_str(&format!(
+                    "  Síndrome {} en {} (posiciones quitadas al acortar a ({}, {})) -> \
+                     errores detectados (>= 2), bloque no corregible\n",
+                    syndrome, format_ranges(&t.code.uncorrectable_syndromes()), t.code.n, t.code.k
+                ));
+            }
+        }
+    }
+    out
+}
+
+pub fn render_crc_trace(t: &CrcTrace) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("Divisor G(x) = {} ({} bits)\n", bits_str(&t.divisor), t.divisor.len()));
+    if !t.steps.is_empty() {
+        out.push_str(&format!("Dividendo     = {}\n", bits_str(&t.dividend)));
+    }
+    // Cada paso guarda solo la ventana bajo el divisor; aquí se rearma el dividendo entero
+    let mut work = t.dividend.clone();
+    for (i, s) in t.steps.iter().enumerate() {
+        let pad = " ".repeat(s.offset);
+        out.push_str(&format!("Paso {} (bit líder en posición {}):\n", i + 1, s.offset));
+        out.push_str(&format!("    {}\n", bits_str(&work)));
+        out.push_str(&format!("  ^ {}{}\n", pad, bits_str(&t.divisor)));
+        work[s.offset..s.offset + s.after.len()].copy_from_slice(&s.after);
+        out.push_str(&format!("  = {}\n", bits_str(&work)));
+    }
+    out.push_str(&format!(
+        "Residuo (últimos {} bits) = {} -> {}\n",
+        t.remainder.len(),
+        bits_str(&t.remainder),
+        if t.valid { "cero, trama válida" } else { "distinto de cero, trama con errores" }
+    ));
+    out
+}
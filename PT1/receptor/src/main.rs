@@ -1,8 +1,13 @@
 mod detection;
 mod correction;
+mod explain;
+mod cli;
+mod batch;
 
 use std::io::{self, Write};
 
+use correction::hamming_matrix::{self, MatrixForm, MatrixFormat};
+
 fn read_line(prompt: &str) -> String {
     print!("{}", prompt);
     let _ = io::stdout().flush();
@@ -11,22 +16,87 @@ fn read_line(prompt: &str) -> String {
     s.trim().to_string()
 }
 
-fn main() {
-    println!("=== RECEPTOR de Capa de Enlace ===");
-    println!("Algoritmos disponibles:");
-    println!("  1) Hamming (corrección de errores)");
-    println!("  2) CRC-32 (detección de errores)");
-    let choice = read_line("Selecciona algoritmo [1/2]: ");
+fn ask_explain() -> bool {
+    let ans = read_line("¿Modo explicación (traza paso a paso)? [s/N]: ");
+    matches!(ans.to_ascii_lowercase().as_str(), "s" | "si" | "sí" | "y" | "yes")
+}
 
+fn read_bits() -> String {
     let bits = read_line("Ingresa la trama en binario (solo 0/1): ");
     if !bits.chars().all(|c| c == '0' || c == '1') {
         eprintln!("Error: la trama debe contener solo caracteres '0' y '1'.");
         std::process::exit(1);
     }
+    bits
+}
+
+// Muestra o exporta G, H, distancia mínima y enumerador de pesos.
+fn show_matrices() {
+    let n: usize = match read_line("Hamming: tamaño de bloque (n), 3..=63: ").parse() {
+        Ok(v) => v,
+        Err(_) => {
+            eprintln!("Valor de n inválido.");
+            std::process::exit(1);
+        }
+    };
+    let form = match read_line("Forma: 1) no sistemática  2) sistemática [1]: ").as_str() {
+        "" | "1" => MatrixForm::NonSystematic,
+        "2" => MatrixForm::Systematic,
+        _ => {
+            eprintln!("Forma inválida.");
+            std::process::exit(1);
+        }
+    };
+    let format_str = read_line("Formato (text/csv/latex) [text]: ");
+    let format = if format_str.is_empty() {
+        MatrixFormat::Text
+    } else {
+        match MatrixFormat::parse(&format_str) {
+            Ok(f) => f,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    };
+
+    let report = match hamming_matrix::render_report(n, form, format) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let path = read_line("Archivo de salida (vacío = pantalla): ");
+    if path.is_empty() {
+        print!("{}", report);
+    } else if let Err(e) = std::fs::write(&path, report) {
+        eprintln!("No se pudo escribir {}: {}", path, e);
+        std::process::exit(1);
+    } else {
+        println!("Matrices exportadas a {}", path);
+    }
+}
+
+fn main() {
+    // Con argumentos se usa el modo por subcomandos (ver cli.rs); sin ellos, el menú interactivo.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
+    println!("=== RECEPTOR de Capa de Enlace ===");
+    println!("Algoritmos disponibles:");
+    println!("  1) Hamming (corrección de errores)");
+    println!("  2) CRC-32 (detección de errores)");
+    println!("  3) Matrices G y H de Hamming (inspección del código)");
+    let choice = read_line("Selecciona opción [1/2/3]: ");
 
     match choice.as_str() {
         "1" => {
             // Hamming
+            let bits = read_bits();
             let n_str = read_line("Hamming: especifica el tamaño de bloque (n) del código (p.ej., 7, 12, 15): ");
             let n: usize = match n_str.parse() {
                 Ok(v) if v >= 3 => v,
@@ -36,6 +106,13 @@ fn main() {
                 }
             };
 
+            if ask_explain() {
+                match correction::hamming::HammingCode::from_n(n).and_then(|code| correction::hamming::trace_stream(&bits, &code)) {
+                    Ok(traces) => print!("{}", explain::render_hamming_trace(&traces)),
+                    Err(e) => println!("No se pudo generar la traza: {}", e),
+                }
+            }
+
             match correction::hamming::decode_stream(&bits, n) {
                 Ok(res) => {
                     if res.corrected_positions.is_empty() {
@@ -50,6 +127,14 @@ fn main() {
                         println!("Mensaje corregido (datos sin bits de paridad): {}", res.data_bits);
                     }
                 }
+                Err(e @ correction::hamming::HammingError::Uncorrectable { code, .. }) => {
+                    println!("Resultado: Se detectaron errores no corregibles. Se descarta el mensaje.");
+                    println!("Detalle: {}", e);
+                    // Aun así se muestran los bloques buenos; '?' marca los bits no confiables
+                    if let Ok(partial) = correction::hamming::decode_stream_partial(&bits, &code) {
+                        println!("Datos parciales (? = bit no confiable): {}", partial.data_bits_marked('?'));
+                    }
+                }
                 Err(e) => {
                     println!("Resultado: Se detectaron errores no corregibles. Se descarta el mensaje.");
                     println!("Detalle: {}", e);
@@ -58,6 +143,13 @@ fn main() {
         }
         "2" => {
             // CRC-32
+            let bits = read_bits();
+            if ask_explain() {
+                match detection::crc32::trace_crc32_poly(&bits) {
+                    Ok(trace) => print!("{}", explain::render_crc_trace(&trace)),
+                    Err(e) => println!("No se pudo generar la traza: {}", e),
+                }
+            }
             match detection::crc32::verify_crc32_poly(&bits) {
                 Ok(ok) => {
                     if ok.valid {
@@ -73,6 +165,7 @@ fn main() {
                 }
             }
         }
+        "3" => show_matrices(),
         _ => {
             eprintln!("Opción inválida.");
             std::process::exit(1);
@@ -3,9 +3,10 @@ package app;
 import correction.Hamming;
 import detection.CRC32;
 
-import java.io.OutputStreamWriter;
-import java.io.PrintWriter;
-import java.net.Socket;
+import java.io.BufferedReader;
+import java.io.InputStreamReader;
+import java.util.ArrayList;
+import java.util.List;
 import java.util.Random;
 
 public class Bench {
@@ -31,65 +32,75 @@ public class Bench {
         return sb.toString();
     }
 
-    private static void sendToReceiver(String host, int port, String algo, String param, String bits) throws Exception {
-        try (Socket sock = new Socket(host, port);
-             PrintWriter out = new PrintWriter(new OutputStreamWriter(sock.getOutputStream()), true)) {
-            out.println("ALGO=" + algo);
-            out.println("PARAM=" + param);
-            out.println("BITS=" + bits);
-        }
+    // Trama enviada (con ruido), para que pruebas.py la guarde y el receptor pueda
+    // reprocesarla luego en modo batch.
+    private static void printFrame(String param, String frameBits) {
+        System.out.println("PARAM=" + param);
+        System.out.println("TRAMA=" + frameBits);
     }
 
-    // Uso:
+    // Uso (agregar -Dwire=bin antes de -cp para el formato binario):
     // Hamming: java -cp out app.Bench <texto> 1 <n> <ber> <host> <port>
     // CRC32 :  java -cp out app.Bench <texto> 2 <ber> <host> <port>
+    // Con <texto> = "-" se lee un mensaje por línea de stdin y todos viajan por la
+    // misma conexión (una trama por mensaje, cada una con su bloque de salida).
+    // Con -Dtransport=udp cada trama lleva seq=<índice> en PARAM para que el receptor
+    // cuente datagramas perdidos o fuera de orden.
     public static void main(String[] args) throws Exception {
         if (args.length < 5) {
-            System.err.println("Uso:\n  Hamming: java -cp out app.Bench <texto> 1 <n> <ber> <host> <port>\n  CRC32 :  java -cp out app.Bench <texto> 2 <ber> <host> <port>");
+            System.err.println("Uso:\n  Hamming: java -cp out app.Bench <texto|-> 1 <n> <ber> <host> <port>\n  CRC32 :  java -cp out app.Bench <texto|-> 2 <ber> <host> <port>");
             return;
         }
 
         String text = args[0];
         String algoSel = args[1];
+        boolean hamming = "1".equals(algoSel);
+        if (!hamming && !"2".equals(algoSel)) {
+            System.err.println("Algoritmo desconocido: " + algoSel);
+            return;
+        }
+        if (hamming && args.length < 6) {
+            System.err.println("Faltan args para Hamming.");
+            return;
+        }
+        int base = hamming ? 3 : 2; // índice de <ber>
+        int n = hamming ? Integer.parseInt(args[2]) : 0;
+        double ber = Double.parseDouble(args[base]);
+        String host = args[base + 1];
+        int port = Integer.parseInt(args[base + 2]);
 
-        String dataBits = asciiToBits(text);
-        String algo, param, frameBits;
-
-        if ("1".equals(algoSel)) {
-            if (args.length < 6) {
-                System.err.println("Faltan args para Hamming.");
-                return;
+        List<String> messages = new ArrayList<>();
+        if ("-".equals(text)) {
+            BufferedReader br = new BufferedReader(new InputStreamReader(System.in));
+            for (String line; (line = br.readLine()) != null; ) {
+                if (!line.isEmpty()) messages.add(line);
             }
-            int n = Integer.parseInt(args[2]);
-            double ber = Double.parseDouble(args[3]);
-            String host = args[4];
-            int port = Integer.parseInt(args[5]);
-
-            Hamming.EncodeResult res = Hamming.encodeStream(dataBits, n);
-            algo = "HAMMING";
-            param = "n=" + n + ";pad=" + res.paddingZeros;
-            frameBits = applyNoise(res.encodedBits, ber);
-            sendToReceiver(host, port, algo, param, frameBits);
-            System.out.println("OK Hamming -> bits=" + frameBits.length());
+        } else {
+            messages.add(text);
+        }
 
-        } else if ("2".equals(algoSel)) {
-            if (args.length < 5) {
-                System.err.println("Faltan args para CRC32.");
-                return;
+        try (Link.Session session = new Link.Session(host, port)) {
+            for (int i = 0; i < messages.size(); i++) {
+                String msg = messages.get(i);
+                String dataBits = asciiToBits(msg);
+                String algo, param, frameBits;
+                if (hamming) {
+                    Hamming.EncodeResult res = Hamming.encodeStream(dataBits, n);
+                    algo = "HAMMING";
+                    param = "n=" + n + ";pad=" + res.paddingZeros;
+                    frameBits = applyNoise(res.encodedBits, ber);
+                } else {
+                    CRC32.Result r = CRC32.computePure(dataBits);
+                    algo = "CRC32";
+                    param = "mode=PURE";
+                    frameBits = applyNoise(r.codeword, ber);
+                }
+                if (Link.udp()) param += ";seq=" + i;
+                Link.Sent sent = session.send(algo, param, frameBits);
+                System.out.println("OK " + (hamming ? "Hamming" : "CRC32") + " -> bits=" + frameBits.length() + " bytes_tx=" + sent.bytes);
+                printFrame(param, frameBits);
+                if (sent.reply != null) System.out.println("RESPUESTA=" + sent.reply);
             }
-            double ber = Double.parseDouble(args[2]);
-            String host = args[3];
-            int port = Integer.parseInt(args[4]);
-
-            CRC32.Result r = CRC32.computePure(dataBits);
-            algo = "CRC32";
-            param = "mode=PURE";
-            frameBits = applyNoise(r.codeword, ber);
-            sendToReceiver(host, port, algo, param, frameBits);
-            System.out.println("OK CRC32 -> bits=" + frameBits.length());
-
-        } else {
-            System.err.println("Algoritmo desconocido: " + algoSel);
         }
     }
 }
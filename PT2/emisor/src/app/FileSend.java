@@ -0,0 +1,97 @@
+package app;
+
+import correction.Hamming;
+import detection.CRC32;
+
+import java.nio.file.Files;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.Arrays;
+import java.util.Random;
+
+// Transferencia de archivos: parte el archivo en fragmentos, cada uno viaja como una
+// trama normal (content=bytes) con file/seq/total/size/filecrc en PARAM para que el
+// receptor lo arme y verifique el CRC-32 del archivo completo.
+public class FileSend {
+
+    private static final int DEFAULT_CHUNK = 64; // bytes por trama
+
+    private static String bytesToBits(byte[] data) {
+        StringBuilder out = new StringBuilder(data.length * 8);
+        for (byte b : data) {
+            out.append(String.format("%8s", Integer.toBinaryString(b & 0xFF)).replace(' ', '0'));
+        }
+        return out.toString();
+    }
+
+    private static String applyNoise(String bits, double p, Random rnd) {
+        StringBuilder sb = new StringBuilder(bits.length());
+        for (int i = 0; i < bits.length(); i++) {
+            char c = bits.charAt(i);
+            boolean flip = rnd.nextDouble() < p;
+            sb.append(flip ? (c == '1' ? '0' : '1') : c);
+        }
+        return sb.toString();
+    }
+
+    // Uso:
+    // Hamming: java -cp out app.FileSend <archivo> 1 <n> <ber> <host> <port> [bytes_por_trama]
+    // CRC32 :  java -cp out app.FileSend <archivo> 2 <ber> <host> <port> [bytes_por_trama]
+    public static void main(String[] args) throws Exception {
+        boolean hamming = args.length >= 2 && "1".equals(args[1]);
+        int base = hamming ? 3 : 2; // índice de <ber>
+        if (args.length < base + 3) {
+            System.err.println("Uso:\n  Hamming: java -cp out app.FileSend <archivo> 1 <n> <ber> <host> <port> [bytes_por_trama]\n  CRC32 :  java -cp out app.FileSend <archivo> 2 <ber> <host> <port> [bytes_por_trama]");
+            return;
+        }
+        if (!hamming && !"2".equals(args[1])) {
+            System.err.println("Algoritmo desconocido: " + args[1]);
+            return;
+        }
+
+        Path path = Paths.get(args[0]);
+        byte[] data = Files.readAllBytes(path);
+        int n = hamming ? Integer.parseInt(args[2]) : 0;
+        double ber = Double.parseDouble(args[base]);
+        String host = args[base + 1];
+        int port = Integer.parseInt(args[base + 2]);
+        int chunk = args.length > base + 3 ? Integer.parseInt(args[base + 3]) : DEFAULT_CHUNK;
+
+        java.util.zip.CRC32 fileCrc = new java.util.zip.CRC32();
+        fileCrc.update(data);
+        // ';' y '=' separan campos de PARAM: no pueden ir en el nombre
+        String name = path.getFileName().toString().replace(';', '_').replace('=', '_');
+        int total = Math.max(1, (data.length + chunk - 1) / chunk);
+        String fileParam = ";content=bytes;file=" + name + ";total=" + total + ";size=" + data.length
+                + ";filecrc=" + String.format("%08x", fileCrc.getValue());
+
+        Random rnd = new Random();
+        long bitsTx = 0, bytesTx = 0;
+        try (Link.Session session = new Link.Session(host, port)) {
+            for (int seq = 0; seq < total; seq++) {
+                byte[] part = Arrays.copyOfRange(data, seq * chunk, Math.min(data.length, (seq + 1) * chunk));
+                String dataBits = bytesToBits(part);
+                String algo, param, frameBits;
+                if (hamming) {
+                    Hamming.EncodeResult res = Hamming.encodeStream(dataBits, n);
+                    algo = "HAMMING";
+                    param = "n=" + n + ";pad=" + res.paddingZeros;
+                    frameBits = res.encodedBits;
+                } else {
+                    CRC32.Result r = CRC32.computePure(dataBits);
+                    algo = "CRC32";
+                    param = "mode=PURE";
+                    frameBits = r.codeword;
+                }
+                param += fileParam + ";seq=" + seq;
+                String noisy = applyNoise(frameBits, ber, rnd);
+                Link.Sent sent = session.send(algo, param, noisy);
+                bitsTx += noisy.length();
+                bytesTx += sent.bytes;
+                System.out.println("OK trama " + (seq + 1) + "/" + total + " -> bits=" + noisy.length() + " bytes_tx=" + sent.bytes
+                        + (sent.reply != null ? " (" + sent.reply + ")" : ""));
+            }
+        }
+        System.out.println("Archivo " + name + " enviado: " + data.length + " bytes en " + total + " tramas, bits=" + bitsTx + " bytes_tx=" + bytesTx);
+    }
+}
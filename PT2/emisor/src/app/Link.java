@@ -0,0 +1,245 @@
+package app;
+
+import java.io.BufferedReader;
+import java.io.ByteArrayOutputStream;
+import java.io.Closeable;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.InputStreamReader;
+import java.io.OutputStream;
+import java.io.PrintStream;
+import java.net.DatagramPacket;
+import java.net.DatagramSocket;
+import java.net.InetSocketAddress;
+import java.net.Socket;
+import java.net.SocketTimeoutException;
+import java.net.UnixDomainSocketAddress;
+import java.nio.channels.Channels;
+import java.nio.channels.SocketChannel;
+import java.nio.charset.StandardCharsets;
+import java.util.HashSet;
+import java.util.Set;
+
+// Capa de TRANSMISIÓN compartida por Main, Bench y FileSend.
+//
+// Formato texto (por defecto): ALGO=/PARAM=/BITS= con un carácter por bit.
+// Formato binario (java -Dwire=bin ...): encabezado compacto + bits empaquetados.
+//   MAGIC(0xB7) VERSION(1) ALGO_ID(1 HAMMING, 2 CRC32) PARAM_LEN(u16) PARAM BIT_LEN(u32) DATOS
+// Ver receptor/src/wire.rs.
+//
+// Antes de la trama se negocia con HELLO (receptor/src/protocol.rs); con -Dhello=false
+// se envía la trama directamente, como el emisor original.
+//
+// Con -Dtransport=udp cada trama viaja en un datagrama, sin negociación (el receptor
+// debe escuchar con --listen udp://...). La respuesta se espera -Dreply.timeout ms
+// (por defecto 200); si no llega, reply queda en null.
+//
+// Con -Dtransport=unix:/RUTA se usa un socket de dominio Unix en lugar de host:puerto
+// (receptor con --listen unix:///RUTA). Con -Dtransport=stdout las tramas se escriben
+// en stdout, sin negociación ni respuestas, para encadenar el emisor con el receptor
+// (--listen -) en una tubería; los mensajes del emisor pasan a stderr.
+public class Link {
+    private static final int MAGIC = 0xB7;
+    private static final int VERSION = 1;
+    private static final int PROTOCOL_VERSION = 1;
+
+    // Resultado del envío: bytes de la trama en el cable y respuesta del receptor
+    // ("OK <estado>" o "ERR <código> <motivo>"; null sin HELLO o si por UDP no llegó).
+    public static class Sent {
+        public final int bytes;
+        public final String reply;
+        Sent(int bytes, String reply) {
+            this.bytes = bytes;
+            this.reply = reply;
+        }
+    }
+
+    public static boolean useHello() {
+        return !"false".equalsIgnoreCase(System.getProperty("hello", "true"));
+    }
+
+    private static String transport() {
+        return System.getProperty("transport", "tcp");
+    }
+
+    public static boolean udp() {
+        return "udp".equalsIgnoreCase(transport());
+    }
+
+    private static boolean toStdout() {
+        return "stdout".equalsIgnoreCase(transport());
+    }
+
+    // Ruta del socket Unix, o null si el transporte no es unix:
+    private static String unixPath() {
+        String t = transport();
+        return t.startsWith("unix:") ? t.substring("unix:".length()) : null;
+    }
+
+    private static int replyTimeoutMs() {
+        return Integer.parseInt(System.getProperty("reply.timeout", "200"));
+    }
+
+    public static boolean binaryWire() {
+        return "bin".equalsIgnoreCase(System.getProperty("wire", "text"));
+    }
+
+    static byte[] encodeText(String algo, String param, String bits) {
+        String s = "ALGO=" + algo + "\nPARAM=" + param + "\nBITS=" + bits + "\n";
+        return s.getBytes(StandardCharsets.US_ASCII);
+    }
+
+    static byte[] encodeBinary(String algo, String param, String bits) {
+        int algoId;
+        if ("HAMMING".equals(algo)) algoId = 1;
+        else if ("CRC32".equals(algo)) algoId = 2;
+        else throw new IllegalArgumentException("Algoritmo no soportado: " + algo);
+
+        byte[] p = param.getBytes(StandardCharsets.US_ASCII);
+        ByteArrayOutputStream out = new ByteArrayOutputStream();
+        out.write(MAGIC);
+        out.write(VERSION);
+        out.write(algoId);
+        out.write((p.length >> 8) & 0xFF);
+        out.write(p.length & 0xFF);
+        out.write(p, 0, p.length);
+        int len = bits.length();
+        for (int shift = 24; shift >= 0; shift -= 8) out.write((len >> shift) & 0xFF);
+        // Bits empaquetados MSB primero; el último byte se completa con ceros
+        for (int i = 0; i < len; i += 8) {
+            int b = 0;
+            for (int j = 0; j < 8; j++) {
+                b <<= 1;
+                if (i + j < len && bits.charAt(i + j) == '1') b |= 1;
+            }
+            out.write(b);
+        }
+        return out.toByteArray();
+    }
+
+    // HELLO -> capacidades -> SELECT; falla si el receptor no soporta el algoritmo.
+    private static void negotiate(OutputStream out, BufferedReader in, String algo) throws IOException {
+        out.write(("HELLO " + PROTOCOL_VERSION + "\n").getBytes(StandardCharsets.US_ASCII));
+        out.flush();
+        String line = in.readLine();
+        if (line == null || !line.startsWith("HELLO ")) {
+            throw new IOException("Negociación rechazada: " + line);
+        }
+        Set<String> algos = new HashSet<>();
+        while ((line = in.readLine()) != null && !line.equals("END")) {
+            String[] parts = line.split(" ");
+            if (parts.length > 1 && parts[0].equals("CAP")) algos.add(parts[1]);
+        }
+        if (!algos.contains(algo)) {
+            throw new IOException("El receptor no soporta " + algo + " (soporta " + algos + ")");
+        }
+        out.write(("SELECT " + algo + "\n").getBytes(StandardCharsets.US_ASCII));
+        out.flush();
+        line = in.readLine();
+        if (line == null || !line.startsWith("OK")) {
+            throw new IOException("SELECT rechazado: " + line);
+        }
+    }
+
+    // Conexión que lleva varias tramas seguidas (negocia una sola vez, con el algoritmo
+    // de la primera trama). Sin HELLO cada trama usa su propia conexión, como antes,
+    // porque no se leen las respuestas del receptor.
+    public static class Session implements AutoCloseable {
+        private final String host;
+        private final int port;
+        private Closeable conn;
+        private DatagramSocket udpSock;
+        private OutputStream out;
+        private BufferedReader in;
+        private String selected;
+        private PrintStream frames;
+
+        public Session(String host, int port) {
+            this.host = host;
+            this.port = port;
+            if (toStdout()) {
+                frames = System.out;
+                System.setOut(System.err);
+            }
+        }
+
+        // Abre la conexión de flujo (TCP o socket Unix).
+        private void open() throws IOException {
+            InputStream is;
+            String path = unixPath();
+            if (path != null) {
+                SocketChannel ch = SocketChannel.open(UnixDomainSocketAddress.of(path));
+                conn = ch;
+                out = Channels.newOutputStream(ch);
+                is = Channels.newInputStream(ch);
+            } else {
+                Socket sock = new Socket(host, port);
+                sock.setTcpNoDelay(true);
+                conn = sock;
+                out = sock.getOutputStream();
+                is = sock.getInputStream();
+            }
+            in = new BufferedReader(new InputStreamReader(is, StandardCharsets.UTF_8));
+        }
+
+        public Sent send(String algo, String param, String bits) throws IOException {
+            byte[] frame = binaryWire() ? encodeBinary(algo, param, bits) : encodeText(algo, param, bits);
+            if (udp()) {
+                return sendDatagram(frame);
+            }
+            if (frames != null) {
+                frames.write(frame);
+                frames.flush();
+                return new Sent(frame.length, null);
+            }
+            if (!useHello()) {
+                open();
+                out.write(frame);
+                out.flush();
+                close();
+                conn = null;
+                return new Sent(frame.length, null);
+            }
+            if (conn == null) {
+                open();
+                negotiate(out, in, algo);
+                selected = algo;
+            } else if (!algo.equals(selected)) {
+                throw new IOException("La sesión negoció " + selected + ", no " + algo);
+            }
+            out.write(frame);
+            out.flush();
+            return new Sent(frame.length, in.readLine());
+        }
+
+        private Sent sendDatagram(byte[] frame) throws IOException {
+            if (udpSock == null) {
+                udpSock = new DatagramSocket();
+                udpSock.connect(new InetSocketAddress(host, port));
+                udpSock.setSoTimeout(replyTimeoutMs());
+            }
+            udpSock.send(new DatagramPacket(frame, frame.length));
+            byte[] buf = new byte[512];
+            DatagramPacket reply = new DatagramPacket(buf, buf.length);
+            try {
+                udpSock.receive(reply);
+            } catch (SocketTimeoutException e) {
+                return new Sent(frame.length, null); // trama o respuesta perdida
+            }
+            return new Sent(frame.length, new String(buf, 0, reply.getLength(), StandardCharsets.UTF_8).trim());
+        }
+
+        @Override
+        public void close() throws IOException {
+            if (conn != null) conn.close();
+            if (udpSock != null) udpSock.close();
+        }
+    }
+
+    // Una trama en su propia conexión.
+    public static Sent send(String host, int port, String algo, String param, String bits) throws IOException {
+        try (Session session = new Session(host, port)) {
+            return session.send(algo, param, bits);
+        }
+    }
+}
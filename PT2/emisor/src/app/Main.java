@@ -1,7 +1,9 @@
 package app;
 
 import java.io.*;
-import java.net.Socket;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.Paths;
 import java.util.Random;
 
 import correction.Hamming;
@@ -25,6 +27,16 @@ public class Main {
         return out.toString();
     }
 
+    // Bytes arbitrarios (texto UTF-8 o contenido de un archivo) a bits
+    private static String bytesToBits(byte[] data) {
+        StringBuilder out = new StringBuilder(data.length * 8);
+        for (byte b : data) {
+            String bits = String.format("%8s", Integer.toBinaryString(b & 0xFF)).replace(' ', '0');
+            out.append(bits);
+        }
+        return out.toString();
+    }
+
     // ==== RUIDO ====
     private static String applyNoise(String bits, double p) {
         Random rnd = new Random();
@@ -37,38 +49,38 @@ public class Main {
         return sb.toString();
     }
 
-    // ==== TRANSMISIÓN (cliente TCP) ====
-    private static void sendToReceiver(String host, int port, String algo, String param, String bits) throws IOException {
-        try (Socket sock = new Socket(host, port);
-             PrintWriter out = new PrintWriter(new OutputStreamWriter(sock.getOutputStream()), true)) {
-            out.println("ALGO=" + algo);
-            out.println("PARAM=" + param);
-            out.println("BITS=" + bits);
-        }
-    }
-
     public static void main(String[] args) throws Exception {
         System.out.println("=== EMISOR (Parte 2) ===");
 
         // APLICACIÓN
-        String text = readLine("Texto a enviar: ");
+        String text = readLine("Texto a enviar (o @ruta para enviar un archivo como bytes): ");
         System.out.println("Algoritmos: 1) Hamming  2) CRC-32 (puro)");
         String choice = readLine("Selecciona algoritmo [1/2]: ");
 
-        // PRESENTACIÓN
-        String dataBits = asciiToBits(text);
+        // PRESENTACIÓN: el receptor interpreta la carga según PARAM content=
+        String dataBits, content;
+        if (text.startsWith("@")) {
+            dataBits = bytesToBits(Files.readAllBytes(Paths.get(text.substring(1))));
+            content = "bytes";
+        } else if (text.chars().allMatch(c -> c < 128)) {
+            dataBits = asciiToBits(text);
+            content = "ascii";
+        } else {
+            dataBits = bytesToBits(text.getBytes(StandardCharsets.UTF_8));
+            content = "utf8";
+        }
 
         String algo, param, frameBits; // salida de ENLACE
         if ("1".equals(choice)) {
             algo = "HAMMING";
             int n = Integer.parseInt(readLine("Hamming: n del código (p.ej., 7): "));
             Hamming.EncodeResult res = Hamming.encodeStream(dataBits, n);
-            param = "n=" + n + ";pad=" + res.paddingZeros;
+            param = "n=" + n + ";pad=" + res.paddingZeros + ";content=" + content;
             frameBits = res.encodedBits;
         } else if ("2".equals(choice)) {
             algo = "CRC32";
             CRC32.Result r = CRC32.computePure(dataBits);
-            param = "mode=PURE";
+            param = "mode=PURE;content=" + content;
             frameBits = r.codeword;
         } else {
             System.err.println("Opción inválida");
@@ -91,7 +103,9 @@ public class Main {
             if (pStr != null && !pStr.isBlank()) port = Integer.parseInt(pStr.trim());
         } catch (Exception ignore) {}
 
-        sendToReceiver(host, port, algo, param, noisy);
-        System.out.println("Trama enviada. Longitud: " + noisy.length() + " bits");
+        // TRANSMISIÓN (texto por defecto, binario con -Dwire=bin)
+        Link.Sent sent = Link.send(host, port, algo, param, noisy);
+        System.out.println("Trama enviada. Longitud: " + noisy.length() + " bits (" + sent.bytes + " bytes en el cable)");
+        if (sent.reply != null) System.out.println("Respuesta del receptor: " + sent.reply);
     }
 }
\ No newline at end of file
@@ -17,6 +17,11 @@ RECEIVER_CWD = "receptor/target/debug"
 # Emisor Java headless (usa app.Bench)
 EMITTER_CLASSPATH = "emisor/out"
 EMITTER_CLASS     = "app.Bench"
+# Formato en el cable: "text" (ALGO=/PARAM=/BITS=, 1 byte por bit) o "bin" (compacto)
+WIRE              = "text"
+# True: todas las pruebas de una combinación viajan por una sola conexión (un único
+# app.Bench con los mensajes por stdin). False: un proceso y un socket por prueba.
+PERSISTENT        = True
 
 # Parrilla de parámetros
 BERS           = [0.0, 0.01, 0.02]
@@ -38,7 +43,11 @@ RE_H_CORR_CNT  = re.compile(r"\(\s*\d+\s*,\s*\d+\s*\)")  # cuenta tuplas (bloque
 RE_CRC_OK      = re.compile(r"CRC válido\.\s*Mensaje:\s*([^\n]+)", re.I)
 RE_DROP        = re.compile(r"(CRC inválido|descartado|no corregibles)", re.I)
 RE_ALGO_LINE   = re.compile(r"^ALGO=")
+RE_LOG_LINE    = re.compile(r"^\d{4}-\d{2}-\d{2}T\S+Z (ERROR|WARN|INFO|DEBUG)\b")  # registro del receptor
 RE_TX_BITS     = re.compile(r"bits=(\d+)")
+RE_TX_BYTES    = re.compile(r"bytes_tx=(\d+)")
+RE_TX_PARAM    = re.compile(r"^PARAM=(.*)$", re.M)
+RE_TX_FRAME    = re.compile(r"^TRAMA=([01]*)$", re.M)
 
 def parse_line_to_event(s: str):
     """
@@ -51,6 +60,9 @@ def parse_line_to_event(s: str):
     s = s.strip()
     if RE_ALGO_LINE.search(s):
         return {"type": "ALGO"}
+    # Registro: "2026-...Z INFO  peer=... Conexión cerrada: N tramas (valido .., descartado ..)"
+    if RE_LOG_LINE.search(s):
+        return None
 
     # Hamming OK
     m = RE_H_OK.search(s)
@@ -121,33 +133,54 @@ def random_message(n_bytes: int) -> str:
     # Mensaje solo con mayúsculas ASCII para fácil visualización
     return ''.join(random.choices(string.ascii_uppercase, k=n_bytes))
 
+def emitter_cmd(algo: str, msg: str, ber: float, n_hamming: int | None):
+    java = ["java", f"-Dwire={WIRE}", "-cp", EMITTER_CLASSPATH, EMITTER_CLASS]
+    if algo == "HAMMING":
+        return java + [msg, "1", str(n_hamming), str(ber), HOST, PORT]
+    if algo == "CRC32":
+        return java + [msg, "2", str(ber), HOST, PORT]
+    raise ValueError("Algoritmo desconocido")
+
+def parse_emitter_block(em_out: str):
+    """Salida de app.Bench para una trama -> (bits_tx:int, bytes_tx:int, param:str, trama:str)"""
+    m = RE_TX_BITS.search(em_out or "")
+    bits_tx = int(m.group(1)) if m else 0
+    mb = RE_TX_BYTES.search(em_out or "")
+    bytes_tx = int(mb.group(1)) if mb else 0
+    mp = RE_TX_PARAM.search(em_out or "")
+    mf = RE_TX_FRAME.search(em_out or "")
+    return bits_tx, bytes_tx, (mp.group(1) if mp else ""), (mf.group(1) if mf else "")
+
 def run_one_trial(algo: str, msg: str, ber: float, n_hamming: int | None):
     """
-    Ejecuta 1 envío usando app.Bench, devuelve (em_out_text, bits_tx:int)
+    Ejecuta 1 envío usando app.Bench, devuelve (em_out_text, bits_tx:int, bytes_tx:int, param:str, trama:str)
     """
-    if algo == "HAMMING":
-        em_cmd = [
-            "java", "-cp", EMITTER_CLASSPATH, EMITTER_CLASS,
-            msg, "1", str(n_hamming), str(ber), HOST, PORT
-        ]
-    elif algo == "CRC32":
-        em_cmd = [
-            "java", "-cp", EMITTER_CLASSPATH, EMITTER_CLASS,
-            msg, "2", str(ber), HOST, PORT
-        ]
-    else:
-        raise ValueError("Algoritmo desconocido")
-
     em = subprocess.Popen(
-        em_cmd,
+        emitter_cmd(algo, msg, ber, n_hamming),
         stdout=subprocess.PIPE,
         stderr=subprocess.STDOUT,
         text=True
     )
     em_out, _ = em.communicate()  # bloquea hasta terminar de enviar
-    m = RE_TX_BITS.search(em_out or "")
-    bits_tx = int(m.group(1)) if m else 0
-    return em_out, bits_tx
+    return (em_out, *parse_emitter_block(em_out))
+
+def run_trials_persistent(algo: str, msgs: list[str], ber: float, n_hamming: int | None):
+    """
+    Envía todos los mensajes por una sola conexión; devuelve un
+    (bits_tx, bytes_tx, param, trama) por mensaje, en orden.
+    """
+    em = subprocess.run(
+        emitter_cmd(algo, "-", ber, n_hamming),
+        input="\n".join(msgs) + "\n",
+        stdout=subprocess.PIPE,
+        stderr=subprocess.STDOUT,
+        text=True
+    )
+    # Cada trama empieza con la línea "OK <algo> -> bits=..."
+    blocks = [b for b in re.split(r"^(?=OK )", em.stdout or "", flags=re.M) if b.startswith("OK ")]
+    if len(blocks) != len(msgs):
+        raise RuntimeError(f"El emisor envió {len(blocks)} de {len(msgs)} tramas:\n{em.stdout}")
+    return [parse_emitter_block(b) for b in blocks]
 
 def run_batch(receptor_proc, events, lock, algo: str, ber: float, msg_bytes: int, n_hamming: int | None, trials: int, out_csv: Path):
     out_csv.parent.mkdir(parents=True, exist_ok=True)
@@ -157,12 +190,19 @@ def run_batch(receptor_proc, events, lock, algo: str, ber: float, msg_bytes: int
             "trial_idx", "mensaje_original", "mensaje_recibido", "valido",
             "errores_corregidos", "errores_corregidos_count",
             "algoritmo", "hamming_n", "ber", "msg_bytes",
-            "bits_tx", "useful_bits_delivered"
+            "bits_tx", "useful_bits_delivered",
+            "param", "trama",  # permiten reprocesar con `receptor --batch`
+            "wire", "bytes_tx"  # formato en el cable y bytes realmente enviados
         ])
 
-        for t in range(1, trials+1):
-            original = random_message(msg_bytes)
-            em_out, bits_tx = run_one_trial(algo, original, ber, n_hamming)
+        originals = [random_message(msg_bytes) for _ in range(trials)]
+        sent = run_trials_persistent(algo, originals, ber, n_hamming) if PERSISTENT else None
+
+        for t, original in enumerate(originals, start=1):
+            if sent is not None:
+                bits_tx, bytes_tx, param, trama = sent[t - 1]
+            else:
+                _, bits_tx, bytes_tx, param, trama = run_one_trial(algo, original, ber, n_hamming)
 
             # Espera el RESULT correspondiente
             res = wait_next_result_blocking(events, lock)
@@ -178,7 +218,9 @@ def run_batch(receptor_proc, events, lock, algo: str, ber: float, msg_bytes: int
                 corr, corr_cnt,
                 algo, (n_hamming if n_hamming is not None else ""),
                 ber, msg_bytes,
-                bits_tx, useful_bits
+                bits_tx, useful_bits,
+                param, trama,
+                WIRE, bytes_tx
             ])
 
             if t % PRINT_EVERY == 0 or t == trials:
@@ -0,0 +1,434 @@
+// Modo batch: decodifica un archivo de tramas capturadas sin pasar por TCP.
+//
+// Formatos de entrada (se ignoran líneas vacías y las que empiezan con '#'):
+// - Registro por línea:  ALGO/PARAM/BITS     (p.ej. HAMMING/n=7;pad=3/0110011...)
+// - Bits crudos por línea, usando el algoritmo y PARAM por defecto (--algo/--param).
+// - CSV con encabezado al estilo de `resultados/` (columna `algoritmo` obligatoria y
+//   la trama en `trama` o `bits`; opcionales `param`, `hamming_n` y `mensaje_original`).
+// - Con --framing, el archivo es un canal crudo con las tramas entramadas una tras
+//   otra (ver framing/); cada trama extraída se decodifica con --algo/--param y la
+//   columna `linea` pasa a ser la posición de la trama en el canal.
+//
+// Salida: un CSV con el resultado de cada trama y un resumen por estado.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+
+use crate::frame::{self, DecodeOptions, Status};
+use crate::framing::sync::SyncConfig;
+use crate::framing::{self, DeframeStats, Framing};
+
+// Valores por defecto para líneas de solo bits y opciones de decodificación.
+#[derive(Debug, Default)]
+pub struct BatchOptions {
+    pub default_algo: Option<String>,
+    pub default_param: String,
+    pub decode: DecodeOptions,
+    pub framing: Option<Framing>,
+    pub sync: SyncConfig, // preámbulo, palabra y tolerancia de --framing sync
+    pub max_frame_bits: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub line: usize, // línea del archivo (1-based)
+    pub algo: String,
+    pub param: String,
+    pub bits: String,
+    pub expected: Option<String>, // mensaje original, si el CSV lo trae
+}
+
+// Registro válido, o (línea, motivo) si no se pudo interpretar.
+pub type ParsedRecord = Result<Record, (usize, String)>;
+
+#[derive(Debug, Default)]
+pub struct BatchSummary {
+    pub frames: usize,
+    pub by_status: HashMap<&'static str, usize>,
+    pub by_algo: HashMap<String, usize>,
+    pub corrected_bits: usize,
+    pub mismatches: usize, // mensaje entregado distinto del original (solo CSV)
+    pub channel: Option<(Framing, DeframeStats)>,
+}
+
+impl BatchSummary {
+    pub fn render(&self) -> String {
+        let mut out = format!("Tramas procesadas: {}\n", self.frames);
+        for st in [Status::Valid, Status::Corrected, Status::Partial, Status::Uncorrectable, Status::Detected, Status::Error] {
+            out.push_str(&format!("  {:<14} {}\n", st.as_str(), self.by_status.get(st.as_str()).unwrap_or(&0)));
+        }
+        let mut algos: Vec<_> = self.by_algo.iter().collect();
+        algos.sort();
+        for (algo, count) in algos {
+            out.push_str(&format!("  [{}] {}\n", algo, count));
+        }
+        out.push_str(&format!("Bits corregidos: {}\n", self.corrected_bits));
+        if self.mismatches > 0 {
+            out.push_str(&format!("Mensajes entregados distintos del original: {}\n", self.mismatches));
+        }
+        if let Some((f, stats)) = &self.channel {
+            out.push_str(&format!("Canal {} ({}): {}\n", f.as_str(), f.unit(), stats));
+        }
+        out
+    }
+}
+
+// Separa "ALGO/PARAM/BITS"; si la línea son solo bits usa los valores por defecto.
+fn parse_record_line(line: &str, default_algo: Option<&str>, default_param: &str) -> Result<(String, String, String), String> {
+    let parts: Vec<&str> = line.splitn(3, '/').collect();
+    match parts.as_slice() {
+        [algo, param, bits] => Ok((algo.trim().to_ascii_uppercase(), param.trim().to_string(), bits.trim().to_string())),
+        [bits] if bits.chars().all(|c| c == '0' || c == '1') => match default_algo {
+            Some(a) => Ok((a.to_ascii_uppercase(), default_param.to_string(), bits.to_string())),
+            None => Err("trama sin algoritmo (use ALGO/PARAM/BITS o --algo)".into()),
+        },
+        _ => Err(format!("registro mal formado: {}", line)),
+    }
+}
+
+// Campos de una fila CSV (RFC 4180, la inversa de csv_field): un campo entre
+// comillas puede tener comas y comillas dobladas (""). Las filas se leen de a una,
+// así que un campo con un salto de línea queda como comilla sin cerrar.
+fn csv_split(row: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false; // dentro de las comillas
+    let mut was_quoted = false; // el campo empezó con comillas
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if !was_quoted && field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+                was_quoted = true;
+            }
+            '"' => return Err(format!("comilla en medio del campo {}", fields.len() + 1)),
+            _ if quoted => field.push(c),
+            ',' => {
+                fields.push(if was_quoted { std::mem::take(&mut field) } else { field.trim().to_string() });
+                field.clear();
+                was_quoted = false;
+            }
+            _ if was_quoted && !c.is_whitespace() => {
+                return Err(format!("texto después de la comilla de cierre en el campo {}", fields.len() + 1));
+            }
+            _ if was_quoted => {}
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("comilla sin cerrar en el campo {}", fields.len() + 1));
+    }
+    fields.push(if was_quoted { field } else { field.trim().to_string() });
+    Ok(fields)
+}
+
+fn parse_csv(lines: &[(usize, &str)]) -> Result<Vec<ParsedRecord>, String> {
+    let (_, header) = lines[0];
+    let cols = csv_split(header).map_err(|e| format!("encabezado CSV mal formado: {}", e))?;
+    let idx = |name: &str| cols.iter().position(|c| c == name);
+    let algo_i = idx("algoritmo").ok_or("CSV sin columna 'algoritmo'")?;
+    let bits_i = idx("trama").or_else(|| idx("bits")).ok_or(
+        "CSV sin columna 'trama' ni 'bits' (un CSV de resultados generado por una versión de pruebas.py \
+         anterior a esa columna no guarda la trama y no se puede decodificar)",
+    )?;
+    let (param_i, n_i, expected_i) = (idx("param"), idx("hamming_n"), idx("mensaje_original"));
+
+    let records = lines[1..]
+        .iter()
+        .map(|&(line, row)| {
+            let cells = csv_split(row).map_err(|e| (line, format!("fila CSV mal formada: {}", e)))?;
+            let get = |i: Option<usize>| i.and_then(|i| cells.get(i)).cloned();
+            let algo = get(Some(algo_i)).unwrap_or_default().to_ascii_uppercase();
+            let bits = get(Some(bits_i)).ok_or((line, "fila sin trama".to_string()))?;
+            let param = match (get(param_i), get(n_i)) {
+                (Some(p), _) if !p.is_empty() => p,
+                (_, Some(n)) if !n.is_empty() => format!("n={}", n),
+                _ => String::new(),
+            };
+            Ok(Record { line, algo, param, bits, expected: get(expected_i) })
+        })
+        .collect();
+    Ok(records)
+}
+
+// Interpreta el contenido completo del archivo de entrada.
+pub fn parse_input(content: &str, default_algo: Option<&str>, default_param: &str) -> Result<Vec<ParsedRecord>, String> {
+    let lines: Vec<(usize, &str)> = content
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+    if lines.is_empty() {
+        return Ok(Vec::new());
+    }
+    if lines[0].1.contains(',') && lines[0].1.split(',').any(|c| c.trim().trim_matches('"') == "algoritmo") {
+        return parse_csv(&lines);
+    }
+    Ok(lines
+        .iter()
+        .map(|&(line, l)| {
+            parse_record_line(l, default_algo, default_param)
+                .map(|(algo, param, bits)| Record { line, algo, param, bits, expected: None })
+                .map_err(|e| (line, e))
+        })
+        .collect())
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// Decodifica todas las tramas de `input` y escribe el CSV de resultados en `out`.
+pub fn run_batch<W: Write>(content: &str, opts: &BatchOptions, out: &mut W) -> io::Result<BatchSummary> {
+    let records = parse_input(content, opts.default_algo.as_deref(), &opts.default_param)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    write_results(records, opts, out)
+}
+
+// Extrae las tramas de un canal crudo y las decodifica todas con el algoritmo por
+// defecto; las que el entramado descartó quedan como filas con resultado error.
+pub fn run_framed<W: Write>(channel: &[u8], framing: Framing, opts: &BatchOptions, out: &mut W) -> io::Result<BatchSummary> {
+    let invalid = |e: String| io::Error::new(io::ErrorKind::InvalidData, e);
+    let algo = opts.default_algo.as_deref().ok_or_else(|| invalid("--framing necesita --algo".into()))?;
+    let (frames, stats) = framing::deframe(framing, channel, opts.max_frame_bits, &opts.sync).map_err(invalid)?;
+    let records = frames
+        .into_iter()
+        .map(|f| {
+            f.map(|f| Record {
+                line: f.offset,
+                algo: algo.to_ascii_uppercase(),
+                param: opts.default_param.clone(),
+                bits: f.bits,
+                expected: None,
+            })
+        })
+        .collect();
+    let mut summary = write_results(records, opts, out)?;
+    summary.channel = Some((framing, stats));
+    Ok(summary)
+}
+
+fn write_results<W: Write>(records: Vec<ParsedRecord>, opts: &BatchOptions, out: &mut W) -> io::Result<BatchSummary> {
+    writeln!(out, "frame_idx,linea,algoritmo,param,bits_rx,resultado,correcciones,mensaje,mensaje_original,coincide,detalle")?;
+
+    let mut summary = BatchSummary::default();
+    for (i, rec) in records.into_iter().enumerate() {
+        summary.frames += 1;
+        let (line, algo, param, bits_len, st, corrections, message, expected, detail) = match rec {
+            Ok(r) => {
+                let res = frame::process_frame(&r.algo, &r.param, &r.bits, &opts.decode);
+                let corrections: Vec<String> = res.corrected_positions.iter().map(|(b, p)| format!("{}:{}", b + 1, p)).collect();
+                summary.corrected_bits += res.corrected_positions.len();
+                *summary.by_algo.entry(r.algo.clone()).or_insert(0) += 1;
+                (r.line, r.algo, r.param, r.bits.len(), res.status, corrections.join(" "), res.message, r.expected, res.summary)
+            }
+            Err((line, e)) => (line, String::new(), String::new(), 0, Status::Error, String::new(), None, None, e),
+        };
+        *summary.by_status.entry(st.as_str()).or_insert(0) += 1;
+        let matches = match (&expected, &message) {
+            (Some(exp), Some(msg)) => (exp == msg).to_string(),
+            (Some(_), None) => "false".to_string(),
+            _ => String::new(),
+        };
+        if matches == "false" {
+            summary.mismatches += 1;
+        }
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{}",
+            i + 1, line, csv_field(&algo), csv_field(&param), bits_len, st.as_str(), corrections,
+            csv_field(message.as_deref().unwrap_or("")), csv_field(expected.as_deref().unwrap_or("")),
+            matches, csv_field(&detail)
+        )?;
+    }
+    Ok(summary)
+}
+
+// Punto de entrada desde main: lee `input`, escribe resultados en `output` (o stdout).
+pub fn run_file(input: &str, output: Option<&str>, opts: &BatchOptions) -> io::Result<BatchSummary> {
+    let content = fs::read(input)?;
+    let run = |mut out: &mut dyn Write| match opts.framing {
+        Some(framing) => run_framed(&content, framing, opts, &mut out),
+        None => {
+            let text = std::str::from_utf8(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            run_batch(text, opts, &mut out)
+        }
+    };
+    match output {
+        Some(path) => {
+            let mut f = io::BufWriter::new(fs::File::create(path)?);
+            let summary = run(&mut f)?;
+            f.flush()?;
+            Ok(summary)
+        }
+        None => run(&mut io::stdout().lock()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correction::hamming::encode_stream;
+    use crate::detection::crc32::append_crc32_poly;
+    use crate::framing::{bits_to_string, hdlc, parse_bit_channel, sync};
+
+    fn opts_por_defecto(algo: &str, param: &str) -> BatchOptions {
+        BatchOptions { default_algo: Some(algo.into()), default_param: param.into(), ..Default::default() }
+    }
+
+    #[test]
+    fn batch_registros_y_bits_crudos() {
+        let h = encode_stream("01000001", 7).unwrap(); // 'A'
+        let c = append_crc32_poly("01000010").unwrap(); // 'B'
+        let input = format!("# captura\nHAMMING/n=7;pad=0/{}\n\nCRC32/mode=PURE/{}\n{}\n", h, c, h);
+        let mut out = Vec::new();
+        let summary = run_batch(&input, &opts_por_defecto("hamming", "n=7"), &mut out).expect("batch");
+        assert_eq!(summary.frames, 3);
+        assert_eq!(summary.by_status.get("valido"), Some(&3));
+        let csv = String::from_utf8(out).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(2).unwrap().contains(",valido,,B,"));
+    }
+
+    #[test]
+    fn batch_formato_csv_resultados() {
+        let h = encode_stream("01000001", 7).unwrap();
+        let mut bad = h.clone().into_bytes();
+        bad[2] ^= 1; // un error en el primer bloque
+        let bad = String::from_utf8(bad).unwrap();
+        let input = format!(
+            "trial_idx,mensaje_original,algoritmo,hamming_n,trama\n1,A,HAMMING,7,{}\n2,Z,HAMMING,7,{}\n",
+            h, bad
+        );
+        let mut out = Vec::new();
+        let summary = run_batch(&input, &BatchOptions::default(), &mut out).expect("batch");
+        assert_eq!(summary.by_status.get("valido"), Some(&1));
+        assert_eq!(summary.by_status.get("corregido"), Some(&1));
+        assert_eq!(summary.mismatches, 1, "la fila 2 entrega 'A' y esperaba 'Z'");
+
+        // Un CSV de una versión anterior de pruebas.py no tiene la trama
+        let old = "trial_idx,mensaje_original,mensaje_recibido,valido,algoritmo,hamming_n\n1,G,G,True,CRC32,\n";
+        assert!(parse_input(old, None, "").unwrap_err().contains("no se puede decodificar"));
+    }
+
+    #[test]
+    fn batch_csv_con_campos_entre_comillas() {
+        assert_eq!(csv_split(r#"1, "Hola, ""mundo""" ,HAMMING,"#).unwrap(), ["1", "Hola, \"mundo\"", "HAMMING", ""]);
+        assert!(csv_split(r#"1,"sin cerrar"#).is_err());
+        assert!(csv_split(r#"1,"a"b"#).is_err());
+        assert!(csv_split(r#"1,a"b"#).is_err());
+
+        let h = encode_stream("01000001", 7).unwrap(); // 'A'
+        let input = format!(
+            "trial_idx,\"mensaje_original\",algoritmo,param,trama\n\
+             1,\"A\",HAMMING,\"n=7;pad=0\",{}\n\
+             2,\"A, con coma\",HAMMING,n=7,{}\n\
+             3,\"roto,HAMMING,n=7,{}\n",
+            h, h, h
+        );
+        let recs = parse_input(&input, None, "").unwrap();
+        assert_eq!(recs[0].as_ref().unwrap().param, "n=7;pad=0");
+        // La coma entre comillas no corre las columnas
+        assert_eq!(recs[1].as_ref().unwrap().algo, "HAMMING");
+        assert_eq!(recs[1].as_ref().unwrap().expected.as_deref(), Some("A, con coma"));
+        assert!(recs[2].as_ref().unwrap_err().1.contains("comilla sin cerrar"));
+
+        let mut out = Vec::new();
+        let summary = run_batch(&input, &BatchOptions::default(), &mut out).expect("batch");
+        assert_eq!(summary.by_status.get("valido"), Some(&2));
+        assert_eq!(summary.by_status.get("error"), Some(&1));
+        assert_eq!(summary.mismatches, 1);
+    }
+
+    #[test]
+    fn batch_lineas_mal_formadas() {
+        let recs = parse_input("0101\nHAMMING/xyz\n", None, "").unwrap();
+        assert!(recs.iter().all(|r| r.is_err()));
+        assert!(parse_input("a,b\n1,2\n", None, "").unwrap().iter().all(|r| r.is_err()));
+    }
+
+    #[test]
+    fn batch_entrega_parcial() {
+        let h = encode_stream("0100000101000010", 12).unwrap(); // "AB", 2 bloques (12,8)
+        let mut bad = h.into_bytes();
+        bad[4] ^= 1; // posiciones 5 y 8 del bloque 1 -> síndrome 13, no corregible
+        bad[7] ^= 1;
+        let input = format!("HAMMING/n=12;k=8/{}\n", String::from_utf8(bad).unwrap());
+
+        let mut out = Vec::new();
+        let summary = run_batch(&input, &BatchOptions::default(), &mut out).expect("batch");
+        assert_eq!(summary.by_status.get("no_corregible"), Some(&1));
+
+        let mut opts = BatchOptions::default();
+        opts.decode.partial = true;
+        let mut out = Vec::new();
+        let summary = run_batch(&input, &opts, &mut out).expect("batch");
+        assert_eq!(summary.by_status.get("parcial"), Some(&1));
+        assert!(String::from_utf8(out).unwrap().contains(",parcial,,?B,"));
+    }
+
+    #[test]
+    fn batch_canal_hdlc() {
+        let a = encode_stream("01000001", 7).unwrap(); // 'A'
+        let b = encode_stream("01000010", 7).unwrap(); // 'B'
+        let mut b_err = b.clone().into_bytes();
+        b_err[5] ^= 1;
+        let channel = hdlc::encode(&[&a, std::str::from_utf8(&b_err).unwrap(), &a]);
+        // Un error en la última bandera corta la tercera trama
+        let mut channel = channel.into_bytes();
+        let last = channel.len() - 4;
+        channel[last] ^= 1;
+        let channel = format!("# canal\n{}\n", String::from_utf8(channel).unwrap());
+
+        let mut opts = opts_por_defecto("hamming", "n=7");
+        opts.max_frame_bits = 1024;
+        let mut out = Vec::new();
+        let summary = run_framed(channel.as_bytes(), Framing::Hdlc, &opts, &mut out).expect("canal");
+        assert_eq!(summary.frames, 3);
+        assert_eq!(summary.by_status.get("valido"), Some(&1));
+        assert_eq!(summary.by_status.get("corregido"), Some(&1));
+        assert_eq!(summary.by_status.get("error"), Some(&1));
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.lines().nth(1).unwrap().starts_with("1,8,HAMMING,n=7,14,valido,"));
+        let (_, stats) = summary.channel.as_ref().unwrap();
+        assert_eq!((stats.frames, stats.discarded), (2, 1));
+        assert!(summary.render().contains("Canal hdlc (bits): "));
+
+        opts.default_algo = None;
+        assert!(run_framed(channel.as_bytes(), Framing::Hdlc, &opts, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn batch_canal_sync() {
+        let a = parse_bit_channel(encode_stream("01000001", 7).unwrap().as_bytes()).unwrap();
+        let mut b = parse_bit_channel(encode_stream("01000010", 7).unwrap().as_bytes()).unwrap();
+        b[3] ^= 1;
+        let mut opts = opts_por_defecto("hamming", "n=7");
+        opts.max_frame_bits = 1024;
+        let mut bits = [&[1, 1, 0][..], &sync::encode_frame(&a, &opts.sync).unwrap(), &sync::encode_frame(&b, &opts.sync).unwrap()].concat();
+        bits[3 + 5] ^= 1; // un error tolerado en el preámbulo de la primera trama
+        let channel = bits_to_string(&bits);
+
+        let mut out = Vec::new();
+        let summary = run_framed(channel.as_bytes(), Framing::Sync, &opts, &mut out).expect("canal");
+        assert_eq!(summary.by_status.get("valido"), Some(&1));
+        assert_eq!(summary.by_status.get("corregido"), Some(&1));
+        assert!(String::from_utf8(out).unwrap().lines().nth(1).unwrap().starts_with("1,75,HAMMING,n=7,14,valido,"));
+        assert!(summary.render().contains("Canal sync (bits): "));
+    }
+}
@@ -0,0 +1,271 @@
+// Captura de tramas en pcapng (--capture ARCHIVO) para revisar una sesión con
+// Wireshark u otras herramientas estándar. Cada trama recibida es un paquete
+// (Enhanced Packet Block) con los bytes exactos leídos del cable y, como comentarios
+// clave=valor, el emisor, el número de trama, el algoritmo negociado, ALGO, PARAM,
+// el resultado y las posiciones corregidas. Los comentarios de la sección guardan la
+// configuración que afecta la decodificación, para reproducir la sesión (--replay).
+//
+// El tipo de enlace es LINKTYPE_USER0 (147), reservado para usos privados: en
+// Wireshark se le asigna un disector en Preferencias > Protocols > DLT_USER
+// (por ejemplo data-text-lines para tramas de texto).
+//
+// PcapngWriter no depende del servidor: cualquier otro programa del enlace (un
+// simulador de canal, un proxy) puede escribir capturas con el mismo formato.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const LINKTYPE_USER0: u16 = 147;
+
+const SHB: u32 = 0x0A0D_0D0A;
+const IDB: u32 = 0x0000_0001;
+const EPB: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const OPT_ENDOFOPT: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const SHB_USERAPPL: u16 = 4;
+const IF_NAME: u16 = 2;
+
+// Opción TLV con el valor rellenado a múltiplo de 4 bytes.
+fn push_option(out: &mut Vec<u8>, code: u16, value: &[u8]) {
+    out.extend_from_slice(&code.to_le_bytes());
+    out.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    out.extend_from_slice(value);
+    out.resize(out.len().next_multiple_of(4), 0);
+}
+
+fn end_options(out: &mut Vec<u8>) {
+    push_option(out, OPT_ENDOFOPT, &[]);
+}
+
+// Bloque completo: tipo, largo total, cuerpo y el largo repetido al final.
+fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+    let total = (12 + body.len()) as u32;
+    let mut out = Vec::with_capacity(total as usize);
+    out.extend_from_slice(&block_type.to_le_bytes());
+    out.extend_from_slice(&total.to_le_bytes());
+    out.extend_from_slice(body);
+    out.extend_from_slice(&total.to_le_bytes());
+    out
+}
+
+pub struct PcapngWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    // Escribe el encabezado de sección y una única interfaz (la dirección de escucha).
+    pub fn new(mut out: W, application: &str, interface: &str, comments: &[String]) -> io::Result<PcapngWriter<W>> {
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes()); // versión 1.0
+        shb.extend_from_slice(&0u16.to_le_bytes());
+        shb.extend_from_slice(&(-1i64).to_le_bytes()); // largo de sección desconocido
+        push_option(&mut shb, SHB_USERAPPL, application.as_bytes());
+        for c in comments {
+            push_option(&mut shb, OPT_COMMENT, c.as_bytes());
+        }
+        end_options(&mut shb);
+        out.write_all(&block(SHB, &shb))?;
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&LINKTYPE_USER0.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&0u32.to_le_bytes()); // sin límite de captura
+        push_option(&mut idb, IF_NAME, interface.as_bytes());
+        end_options(&mut idb);
+        out.write_all(&block(IDB, &idb))?;
+        out.flush()?;
+        Ok(PcapngWriter { out })
+    }
+
+    // Un paquete con marca de tiempo en microsegundos (la resolución por defecto).
+    pub fn write_packet(&mut self, ts: SystemTime, data: &[u8], comments: &[String]) -> io::Result<()> {
+        let micros = ts.duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+        let mut epb = Vec::with_capacity(data.len() + 64);
+        epb.extend_from_slice(&0u32.to_le_bytes()); // interfaz 0
+        epb.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(micros as u32).to_le_bytes());
+        epb.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(data);
+        epb.resize(epb.len().next_multiple_of(4), 0);
+        for c in comments {
+            // Una opción no puede pasar de 65535 bytes
+            let bytes = c.as_bytes();
+            push_option(&mut epb, OPT_COMMENT, &bytes[..bytes.len().min(u16::MAX as usize)]);
+        }
+        end_options(&mut epb);
+        self.out.write_all(&block(EPB, &epb))?;
+        // Cada paquete queda en disco: la captura se puede abrir con el receptor andando
+        self.out.flush()
+    }
+}
+
+// Archivo de captura compartido por las conexiones del servidor.
+pub struct Capture {
+    writer: Mutex<PcapngWriter<BufWriter<File>>>,
+}
+
+impl Capture {
+    pub fn create(path: &str, interface: &str, settings: &[String]) -> io::Result<Capture> {
+        let file = File::create(path).map_err(|e| io::Error::new(e.kind(), format!("No se pudo crear {}: {}", path, e)))?;
+        let writer = PcapngWriter::new(BufWriter::new(file), "receptor (Parte 2)", interface, settings)?;
+        Ok(Capture { writer: Mutex::new(writer) })
+    }
+
+    pub fn record(&self, data: &[u8], comments: &[String]) -> io::Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writer.write_packet(SystemTime::now(), data, comments)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub micros: u64, // marca de tiempo, en microsegundos desde 1970
+    pub data: Vec<u8>,
+    pub comments: Vec<String>,
+}
+
+impl Packet {
+    // Valor de un comentario clave=valor.
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.comments.iter().find_map(|c| c.strip_prefix(key)?.strip_prefix('='))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Recording {
+    pub comments: Vec<String>, // de la sección
+    pub packets: Vec<Packet>,
+}
+
+fn bad(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("captura inválida: {}", msg))
+}
+
+// Comentarios (opt_comment) de la lista de opciones de un bloque.
+fn comments(mut opts: &[u8]) -> Vec<String> {
+    let mut out = Vec::new();
+    while opts.len() >= 4 {
+        let code = u16::from_le_bytes([opts[0], opts[1]]);
+        let len = u16::from_le_bytes([opts[2], opts[3]]) as usize;
+        if code == OPT_ENDOFOPT || opts.len() < 4 + len {
+            break;
+        }
+        if code == OPT_COMMENT {
+            out.push(String::from_utf8_lossy(&opts[4..4 + len]).into_owned());
+        }
+        opts = &opts[(4 + len).next_multiple_of(4).min(opts.len())..];
+    }
+    out
+}
+
+// Lee una captura escrita por PcapngWriter (little-endian, resolución en microsegundos);
+// los bloques de otros tipos se saltean.
+pub fn read<R: Read>(mut r: R) -> io::Result<Recording> {
+    let mut bytes = Vec::new();
+    r.read_to_end(&mut bytes)?;
+    let mut rec = Recording::default();
+    let mut rest = &bytes[..];
+    let u32_at = |b: &[u8], i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+    while !rest.is_empty() {
+        if rest.len() < 12 {
+            return Err(bad("bloque truncado"));
+        }
+        let (kind, len) = (u32_at(rest, 0), u32_at(rest, 4) as usize);
+        if len < 12 || len % 4 != 0 || len > rest.len() {
+            return Err(bad("largo de bloque incorrecto"));
+        }
+        let body = &rest[8..len - 4];
+        match kind {
+            SHB => {
+                if body.len() < 16 || u32_at(body, 0) != BYTE_ORDER_MAGIC {
+                    return Err(bad("solo se leen secciones little-endian"));
+                }
+                rec.comments = comments(&body[16..]);
+            }
+            EPB => {
+                if body.len() < 20 {
+                    return Err(bad("paquete truncado"));
+                }
+                let micros = (u32_at(body, 4) as u64) << 32 | u32_at(body, 8) as u64;
+                let caplen = u32_at(body, 12) as usize;
+                let data = body.get(20..20 + caplen).ok_or_else(|| bad("paquete truncado"))?;
+                let opts = body.get((20 + caplen).next_multiple_of(4)..).unwrap_or_default();
+                rec.packets.push(Packet { micros, data: data.to_vec(), comments: comments(opts) });
+            }
+            _ => {}
+        }
+        rest = &rest[len..];
+    }
+    Ok(rec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn u32_at(b: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes(b[i..i + 4].try_into().unwrap())
+    }
+
+    // (tipo, cuerpo) de cada bloque, verificando que ambos largos coincidan.
+    fn blocks(mut b: &[u8]) -> Vec<(u32, Vec<u8>)> {
+        let mut out = Vec::new();
+        while !b.is_empty() {
+            let len = u32_at(b, 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(u32_at(b, len - 4) as usize, len);
+            out.push((u32_at(b, 0), b[8..len - 4].to_vec()));
+            b = &b[len..];
+        }
+        out
+    }
+
+    #[test]
+    fn bloques_pcapng() {
+        let mut buf = Vec::new();
+        let mut w = PcapngWriter::new(&mut buf, "prueba", "tcp://127.0.0.1:9000", &[]).unwrap();
+        let ts = UNIX_EPOCH + Duration::from_micros(0x1_0000_0002);
+        let comments = vec!["algo=HAMMING".to_string(), "resultado=corregido".to_string()];
+        w.write_packet(ts, b"ALGO=HAMMING\n", &comments).unwrap();
+
+        let b = blocks(&buf);
+        assert_eq!(b.iter().map(|(t, _)| *t).collect::<Vec<_>>(), vec![SHB, IDB, EPB]);
+        assert_eq!(u32_at(&b[0].1, 0), BYTE_ORDER_MAGIC);
+        assert_eq!(u16::from_le_bytes([b[1].1[0], b[1].1[1]]), LINKTYPE_USER0);
+
+        let epb = &b[2].1;
+        assert_eq!((u32_at(epb, 4), u32_at(epb, 8)), (1, 2)); // marca de tiempo alta y baja
+        assert_eq!((u32_at(epb, 12), u32_at(epb, 16)), (13, 13));
+        assert_eq!(&epb[20..33], b"ALGO=HAMMING\n");
+        // Datos rellenados a 16 bytes, luego las opciones: comentario de 12 bytes
+        assert_eq!(&epb[36..40], &[1, 0, 12, 0]);
+        assert_eq!(&epb[40..52], b"algo=HAMMING");
+        assert_eq!(&epb[epb.len() - 4..], &[0, 0, 0, 0]); // opt_endofopt
+    }
+
+    #[test]
+    fn captura_ida_y_vuelta() {
+        let mut buf = Vec::new();
+        let settings = vec!["codecs=HAMMING".to_string(), "params.HAMMING=n=15;pad=0".to_string()];
+        let mut w = PcapngWriter::new(&mut buf, "prueba", "udp://0.0.0.0:9001", &settings).unwrap();
+        let ts = UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456);
+        w.write_packet(ts, b"\xB7\x01\x02", &["peer=a:1".to_string(), "trama=4".to_string()]).unwrap();
+        w.write_packet(ts, b"ALGO=CRC32\r\n", &[]).unwrap();
+
+        let rec = read(&buf[..]).unwrap();
+        assert_eq!(rec.comments, settings);
+        assert_eq!(rec.packets.len(), 2);
+        assert_eq!(rec.packets[0].micros, 1_700_000_000_123_456);
+        assert_eq!(rec.packets[0].data, b"\xB7\x01\x02");
+        assert_eq!((rec.packets[0].meta("trama"), rec.packets[0].meta("peer"), rec.packets[0].meta("tram")), (Some("4"), Some("a:1"), None));
+        assert_eq!((rec.packets[1].data.as_slice(), rec.packets[1].comments.len()), (&b"ALGO=CRC32\r\n"[..], 0));
+
+        assert!(read(&buf[..buf.len() - 2]).is_err());
+    }
+}
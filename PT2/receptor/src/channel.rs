@@ -0,0 +1,132 @@
+// Simulador de canal para los análisis sin red (`--sync-sim`, `--line-sim`): un
+// generador pseudoaleatorio con semilla, así una misma corrida se puede repetir, el
+// ruido binario simétrico que aplica el emisor Java (cada bit cambia con probabilidad
+// BER) y la codificación Hamming/CRC-32 del emisor.
+
+use crate::correction::hamming::{encode_stream, parity_bits_count};
+use crate::detection::crc32::append_crc32_poly;
+
+// Parámetros comunes de las simulaciones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimOptions {
+    pub ber: Vec<f64>,     // una fila del reporte por cada BER
+    pub seed: u64,
+    pub frame_bits: usize, // bits de código por trama
+    pub msg_bytes: usize,  // bytes por mensaje cuando se codifica con Hamming o CRC-32
+}
+
+impl Default for SimOptions {
+    fn default() -> Self {
+        // 112 bits: un mensaje de 8 bytes con Hamming(7,4)
+        SimOptions { ber: vec![0.0, 0.001, 0.01, 0.02, 0.05], seed: 1, frame_bits: 112, msg_bytes: 8 }
+    }
+}
+
+impl SimOptions {
+    // Lista de BER separadas por comas, p.ej. "0,0.01,0.02".
+    pub fn parse_ber(s: &str) -> Result<Vec<f64>, String> {
+        s.split(',')
+            .map(|v| match v.trim().parse::<f64>() {
+                Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+                _ => Err(format!("BER inválida: {} (debe estar entre 0 y 1)", v.trim())),
+            })
+            .collect()
+    }
+}
+
+// xorshift64*: suficiente para simular ruido y no agrega dependencias.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // El estado no puede ser 0
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Uniforme en [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniforme en [0, n).
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn bits(&mut self, n: usize) -> Vec<u8> {
+        (0..n).map(|_| (self.next_u64() >> 63) as u8).collect()
+    }
+}
+
+// Cambia cada bit con probabilidad `ber`; devuelve cuántos cambió.
+pub fn flip_bits(bits: &mut [u8], ber: f64, rng: &mut Rng) -> usize {
+    let mut flips = 0;
+    for b in bits.iter_mut() {
+        if rng.next_f64() < ber {
+            *b ^= 1;
+            flips += 1;
+        }
+    }
+    flips
+}
+
+// Lo que hace el emisor con un mensaje: devuelve PARAM y los bits del código. Con
+// Hamming los datos se completan con ceros hasta un múltiplo de los bits de datos del
+// bloque (pad). `content=bytes` para comparar los bytes entregados con los enviados.
+pub fn encode_message(algo: &str, n: usize, data: &[u8]) -> Result<(String, String), String> {
+    let bits: String = data.iter().map(|b| format!("{:08b}", b)).collect();
+    match algo {
+        "HAMMING" => {
+            let m = n.checked_sub(parity_bits_count(n)).filter(|&m| n >= 3 && m > 0).ok_or(format!("n={} inválido", n))?;
+            let pad = (m - bits.len() % m) % m;
+            let padded = format!("{}{}", bits, "0".repeat(pad));
+            Ok((format!("n={};pad={};content=bytes", n, pad), encode_stream(&padded, n)?))
+        }
+        "CRC32" => Ok(("mode=PURE;content=bytes".to_string(), append_crc32_poly(&bits)?)),
+        other => Err(format!("Algoritmo no soportado: {}", other)),
+    }
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruido_repetible_y_con_la_ber_pedida() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        assert_eq!(a.bits(64), b.bits(64));
+
+        let mut bits = vec![0u8; 100_000];
+        let flips = flip_bits(&mut bits, 0.01, &mut a);
+        assert_eq!(flips, bits.iter().filter(|&&b| b == 1).count());
+        assert!((800..1200).contains(&flips), "{} cambios", flips);
+        assert_eq!(flip_bits(&mut bits, 0.0, &mut a), 0);
+
+        assert_eq!(SimOptions::parse_ber("0, 0.01").unwrap(), [0.0, 0.01]);
+        assert!(SimOptions::parse_ber("1.5").is_err());
+    }
+
+    #[test]
+    fn mensajes_codificados_como_el_emisor() {
+        use crate::frame::{process_frame, DecodeOptions, Status};
+        for (algo, n) in [("HAMMING", 7), ("HAMMING", 15), ("CRC32", 0)] {
+            let (param, bits) = encode_message(algo, n, b"Hola").unwrap();
+            let out = process_frame(algo, &param, &bits, &DecodeOptions::default());
+            assert_eq!(out.status, Status::Valid, "{} {}", algo, param);
+            assert_eq!(out.payload.as_deref(), Some(&b"Hola"[..]));
+        }
+        assert_eq!(encode_message("HAMMING", 15, b"Hola").unwrap().0, "n=15;pad=1;content=bytes");
+        assert!(encode_message("HAMMING", 2, b"x").is_err());
+    }
+}
@@ -0,0 +1,302 @@
+// Configuración del receptor: valores por defecto, archivo TOML (--config) y banderas
+// de la línea de comandos, en ese orden de prioridad creciente. Archivo de ejemplo:
+//
+//   listen = "tcp://0.0.0.0:9000"
+//   workers = 4
+//   read_timeout = 30            # segundos, 0 = sin límite
+//   max_frame_bits = 1048576
+//   codecs = ["HAMMING", "CRC32"]
+//   output = "text"              # text | json
+//   log_level = "info"           # error | warn | info | debug
+//   log_format = "text"          # text | json
+//   # log_file = "receptor.log"  # por defecto, stderr
+//   partial = false
+//   utf8_strict = false
+//   recv_dir = "recibidos"
+//   # payload_dir = "cargas"
+//   # metrics = "127.0.0.1:9100" # GET /metrics en formato Prometheus
+//   # capture = "sesion.pcapng"  # cada trama recibida, para Wireshark
+//
+//   [params]                     # PARAM por defecto; lo que trae la trama tiene prioridad
+//   HAMMING = "n=7"
+//   CRC32 = "mode=PURE"
+//
+// Cada bandera larga corresponde a la clave con '-' en lugar de '_'
+// (--read-timeout = read_timeout), y --default-param ALGO:PARAM a params.ALGO.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::frame::DecodeOptions;
+use crate::logging::LogFormat;
+use crate::protocol::ALGORITHMS;
+use crate::toml::{self, Value};
+use crate::transport::Listen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,  // conexiones y resultado de cada trama (por defecto)
+    Debug, // además, los bits completos de cada trama
+}
+
+impl LogLevel {
+    pub fn parse(s: &str) -> Result<LogLevel, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            other => Err(format!("nivel de log desconocido: {} (use error, warn, info o debug)", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+// Formato del resultado de cada trama en stdout. Con json cada trama es un objeto en
+// una línea y los demás mensajes van a stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub listen: Listen,
+    pub workers: usize,
+    pub read_timeout: Option<Duration>, // None = esperar indefinidamente
+    pub max_frame_bits: usize,
+    pub codecs: Vec<&'static str>,      // subconjunto de ALGORITHMS
+    pub default_params: HashMap<&'static str, String>,
+    pub output: OutputFormat,
+    pub log_level: LogLevel,
+    pub log_format: LogFormat,
+    pub log_file: Option<String>, // None = stderr
+    pub decode: DecodeOptions,
+    pub payload_dir: Option<String>,
+    pub recv_dir: String,
+    pub metrics: Option<String>, // dirección del endpoint HTTP de métricas
+    pub capture: Option<String>, // archivo pcapng
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            listen: Listen::Tcp("0.0.0.0:9000".into()),
+            workers: 4,
+            read_timeout: Some(Duration::from_secs(30)),
+            max_frame_bits: 1 << 20,
+            codecs: ALGORITHMS.to_vec(),
+            default_params: HashMap::new(),
+            output: OutputFormat::Text,
+            log_level: LogLevel::Info,
+            log_format: LogFormat::Text,
+            log_file: None,
+            decode: DecodeOptions::default(),
+            payload_dir: None,
+            recv_dir: "recibidos".into(),
+            metrics: None,
+            capture: None,
+        }
+    }
+}
+
+fn algorithm(name: &str) -> Result<&'static str, String> {
+    let upper = name.trim().to_ascii_uppercase();
+    ALGORITHMS
+        .iter()
+        .find(|a| **a == upper)
+        .copied()
+        .ok_or_else(|| format!("algoritmo desconocido: {} (soportados: {})", name.trim(), ALGORITHMS.join(",")))
+}
+
+// Los valores de la línea de comandos llegan como strings: se aceptan en ambos casos.
+fn as_str(v: &Value) -> Result<&str, String> {
+    match v {
+        Value::Str(s) => Ok(s),
+        other => Err(format!("se esperaba string, se recibió {}", other.type_name())),
+    }
+}
+
+fn as_u64(v: &Value) -> Result<u64, String> {
+    match v {
+        Value::Int(n) => u64::try_from(*n).map_err(|_| format!("se esperaba un entero no negativo: {}", n)),
+        Value::Str(s) => s.parse().map_err(|_| format!("se esperaba un entero no negativo: {}", s)),
+        other => Err(format!("se esperaba entero, se recibió {}", other.type_name())),
+    }
+}
+
+fn as_bool(v: &Value) -> Result<bool, String> {
+    match v {
+        Value::Bool(b) => Ok(*b),
+        Value::Str(s) if s == "true" || s == "false" => Ok(s == "true"),
+        other => Err(format!("se esperaba booleano, se recibió {}", other.type_name())),
+    }
+}
+
+// Arreglo de strings, o una lista separada por comas desde la línea de comandos.
+fn as_list(v: &Value) -> Result<Vec<String>, String> {
+    match v {
+        Value::Array(items) => items.iter().map(|i| as_str(i).map(String::from)).collect(),
+        Value::Str(s) => Ok(s.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect()),
+        other => Err(format!("se esperaba arreglo, se recibió {}", other.type_name())),
+    }
+}
+
+impl Config {
+    pub fn load(&mut self, path: &str) -> Result<(), String> {
+        let src = std::fs::read_to_string(path).map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        for (key, value) in toml::parse(&src).map_err(|e| format!("{}: {}", path, e))? {
+            self.set(&key, &value).map_err(|e| format!("{}: {}", path, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn set(&mut self, key: &str, value: &Value) -> Result<(), String> {
+        let invalid = |e: String| format!("{}: {}", key, e);
+        match key {
+            "listen" => self.listen = Listen::parse(as_str(value).map_err(invalid)?)?,
+            "workers" => match as_u64(value).map_err(invalid)? {
+                0 => return Err(invalid("debe ser mayor que 0".into())),
+                n => self.workers = n as usize,
+            },
+            "read_timeout" => {
+                let secs = as_u64(value).map_err(invalid)?;
+                self.read_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+            }
+            "max_frame_bits" => match as_u64(value).map_err(invalid)? {
+                0 => return Err(invalid("debe ser mayor que 0".into())),
+                n => self.max_frame_bits = n as usize,
+            },
+            "codecs" => {
+                let names = as_list(value).map_err(invalid)?;
+                let codecs = names.iter().map(|n| algorithm(n)).collect::<Result<Vec<_>, _>>().map_err(invalid)?;
+                if codecs.is_empty() {
+                    return Err(invalid("hace falta al menos un algoritmo".into()));
+                }
+                self.codecs = codecs;
+            }
+            "output" => {
+                self.output = match as_str(value).map_err(invalid)? {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    other => return Err(invalid(format!("formato desconocido: {} (use text o json)", other))),
+                }
+            }
+            "log_level" => self.log_level = LogLevel::parse(as_str(value).map_err(invalid)?).map_err(invalid)?,
+            "log_format" => {
+                self.log_format = match as_str(value).map_err(invalid)? {
+                    "text" => LogFormat::Text,
+                    "json" => LogFormat::Json,
+                    other => return Err(invalid(format!("formato desconocido: {} (use text o json)", other))),
+                }
+            }
+            "log_file" => self.log_file = Some(as_str(value).map_err(invalid)?.to_string()),
+            "partial" => self.decode.partial = as_bool(value).map_err(invalid)?,
+            "utf8_strict" => self.decode.strict_utf8 = as_bool(value).map_err(invalid)?,
+            "payload_dir" => self.payload_dir = Some(as_str(value).map_err(invalid)?.to_string()),
+            "recv_dir" => self.recv_dir = as_str(value).map_err(invalid)?.to_string(),
+            "metrics" => self.metrics = Some(as_str(value).map_err(invalid)?.to_string()),
+            "capture" => self.capture = Some(as_str(value).map_err(invalid)?.to_string()),
+            _ => match key.strip_prefix("params.") {
+                Some(algo) => {
+                    let algo = algorithm(algo).map_err(invalid)?;
+                    self.default_params.insert(algo, as_str(value).map_err(invalid)?.to_string());
+                }
+                None => return Err(format!("clave desconocida: {}", key)),
+            },
+        }
+        Ok(())
+    }
+
+    // Lo que cambia el resultado de una trama, como líneas clave=valor: se guarda en las
+    // capturas para que --replay decodifique igual que el receptor que las grabó.
+    pub fn recorded_settings(&self) -> Vec<String> {
+        let listen = match &self.listen {
+            Listen::Stdio => "-".to_string(),
+            other => other.to_string(),
+        };
+        let mut out = vec![
+            format!("listen={}", listen),
+            format!("codecs={}", self.codecs.join(",")),
+            format!("max_frame_bits={}", self.max_frame_bits),
+            format!("partial={}", self.decode.partial),
+            format!("utf8_strict={}", self.decode.strict_utf8),
+        ];
+        let mut params: Vec<_> = self.default_params.iter().collect();
+        params.sort();
+        out.extend(params.into_iter().map(|(algo, p)| format!("params.{}={}", algo, p)));
+        out
+    }
+
+    pub fn apply_recorded(&mut self, settings: &[String]) -> Result<(), String> {
+        for line in settings {
+            let (key, value) = line.split_once('=').ok_or(format!("configuración grabada inválida: {}", line))?;
+            self.set(key, &Value::Str(value.to_string()))?;
+        }
+        Ok(())
+    }
+
+    // PARAM de la trama sobre los valores por defecto de su algoritmo.
+    pub fn effective_param(&self, algo: &str, param: &str) -> String {
+        match self.default_params.get(algo) {
+            Some(defaults) if !param.trim().is_empty() => format!("{};{}", defaults, param),
+            Some(defaults) => defaults.clone(),
+            None => param.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::parse_param_map;
+
+    #[test]
+    fn archivo_y_banderas() {
+        let path = std::env::temp_dir().join(format!("receptor_config_{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "listen = \"udp://127.0.0.1:9001\"\nread_timeout = 0\ncodecs = [\"crc32\"]\noutput = \"json\"\n[params]\nhamming = \"n=15;pad=0\"\n",
+        )
+        .unwrap();
+        let mut cfg = Config::default();
+        cfg.load(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(cfg.listen, Listen::Udp("127.0.0.1:9001".into()));
+        assert_eq!((cfg.read_timeout, cfg.codecs.clone(), cfg.output), (None, vec!["CRC32"], OutputFormat::Json));
+
+        // La línea de comandos pisa al archivo
+        cfg.set("codecs", &Value::Str("HAMMING,CRC32".into())).unwrap();
+        cfg.set("log_level", &Value::Str("warn".into())).unwrap();
+        assert_eq!((cfg.codecs.len(), cfg.log_level), (2, LogLevel::Warn));
+
+        let param = cfg.effective_param("HAMMING", "pad=3");
+        assert_eq!(parse_param_map(&param).get("n").map(String::as_str), Some("15"));
+        assert_eq!(parse_param_map(&param).get("pad").map(String::as_str), Some("3"));
+        assert_eq!(cfg.effective_param("CRC32", "mode=PURE"), "mode=PURE");
+
+        assert_eq!(cfg.set("workers", &Value::Int(0)).unwrap_err(), "workers: debe ser mayor que 0");
+        assert!(cfg.set("codecs", &Value::Str("PARITY".into())).is_err());
+        assert_eq!(cfg.set("puerto", &Value::Int(1)).unwrap_err(), "clave desconocida: puerto");
+
+        // Lo grabado en una captura reconstruye la misma configuración de decodificación
+        cfg.set("partial", &Value::Bool(true)).unwrap();
+        let settings = cfg.recorded_settings();
+        assert!(settings.contains(&"params.HAMMING=n=15;pad=0".to_string()));
+        let mut replay = Config::default();
+        replay.apply_recorded(&settings).unwrap();
+        assert_eq!(replay.recorded_settings(), settings);
+        assert_eq!((replay.listen, replay.decode.partial), (Listen::Udp("127.0.0.1:9001".into()), true));
+    }
+}
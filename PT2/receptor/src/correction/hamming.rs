@@ -1,3 +1,114 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+// Mayor n aceptado y mayor r (16 bits de paridad).
+pub const MAX_N: usize = (1 << 16) - 1;
+const MAX_R: usize = 16;
+
+// Parámetros (n, k) de un código de Hamming SEC, posiblemente acortado.
+// Con r = n - k bits de paridad en las posiciones 1, 2, 4, ..., 2^(r-1), el código
+// es el Hamming (2^r - 1, 2^r - 1 - r) al que se le quitaron posiciones de datos:
+// quedan las k primeras (3, 5, 6, 7, 9, ...) y se descartan las más altas. Hace
+// falta 2^r >= n + 1, es decir, r >= parity_bits_count(n). Con el r mínimo (el del
+// emisor cuando PARAM no trae k) el bloque ocupa las posiciones 1..=n, como en
+// (12, 8) o (10, 6); con más paridad, p.ej. (7, 3) con r = 4, ocupa 1, 2, 3, 4, 5,
+// 6 y 8 (ver positions()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HammingCode {
+    pub n: usize,
+    pub k: usize,
+    pub r: usize,
+}
+
+impl HammingCode {
+    pub fn new(n: usize, k: usize) -> Result<HammingCode, HammingError> {
+        let min = HammingCode::from_n(n)?;
+        if k == 0 || k > min.k {
+            return Err(HammingError::InvalidParams(format!(
+                "({}, {}) no es un código de Hamming: con n={} se necesitan al menos r={} bits de paridad (1 <= k <= {})",
+                n, k, n, min.r, min.k
+            )));
+        }
+        if n - k > MAX_R {
+            return Err(HammingError::InvalidParams(format!(
+                "({}, {}): r={} bits de paridad, demasiados (máximo {})",
+                n, k, n - k, MAX_R
+            )));
+        }
+        Ok(HammingCode { n, k, r: n - k })
+    }
+
+    // Infiere k = n - r con el r mínimo tal que 2^r >= n + 1.
+    pub fn from_n(n: usize) -> Result<HammingCode, HammingError> {
+        if n < 3 {
+            return Err(HammingError::InvalidParams(format!("n={} demasiado pequeño (mínimo 3)", n)));
+        }
+        // n llega del emisor: un valor enorme desbordaría 2^r
+        if n > MAX_N {
+            return Err(HammingError::InvalidParams(format!("n={} demasiado grande (máximo {})", n, MAX_N)));
+        }
+        let r = parity_bits_count(n);
+        Ok(HammingCode { n, k: n - r, r })
+    }
+
+    // Posición en el Hamming (2^r - 1) de cada bit del bloque, en orden: las r de
+    // paridad y las k primeras de datos.
+    pub fn positions(&self) -> Vec<usize> {
+        let data = (1usize..).filter(|&p| !is_power_of_two(p)).take(self.k);
+        let mut positions: Vec<usize> = (0..self.r).map(|i| 1 << i).chain(data).collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    // Síndromes de las posiciones quitadas al acortar: ningún error simple los
+    // produce, así que indican >= 2 errores en el bloque (detectados pero no
+    // corregibles). Vacío si el código no está acortado.
+    pub fn uncorrectable_syndromes(&self) -> Vec<RangeInclusive<usize>> {
+        let mut ranges = Vec::new();
+        let mut next = 1;
+        for p in self.positions().into_iter().chain([1 << self.r]) {
+            if p > next {
+                ranges.push(next..=p - 1);
+            }
+            next = p + 1;
+        }
+        ranges
+    }
+}
+
+// "7, 9..=15"
+fn format_ranges(ranges: &[RangeInclusive<usize>]) -> String {
+    let parts: Vec<String> = ranges
+        .iter()
+        .map(|r| if r.start() == r.end() { r.start().to_string() } else { format!("{}..={}", r.start(), r.end()) })
+        .collect();
+    parts.join(", ")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HammingError {
+    InvalidParams(String), // (n, k) imposible
+    InvalidInput(String),  // caracteres no binarios o longitud no múltiplo de n
+    // Síndrome fuera del código acortado: errores detectados, no corregibles
+    Uncorrectable { block: usize, syndrome: usize, code: HammingCode },
+}
+
+impl fmt::Display for HammingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HammingError::InvalidParams(e) | HammingError::InvalidInput(e) => write!(f, "{}", e),
+            HammingError::Uncorrectable { block, syndrome, code } => {
+                write!(
+                    f,
+                    "Bloque {}: síndrome {} (no corregibles: {}) no corresponde a ninguna posición del código \
+                     ({}, {}) acortado; errores detectados (>= 2) no corregibles",
+                    block + 1, syndrome, format_ranges(&code.uncorrectable_syndromes()), code.n, code.k
+                )
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct HammingStreamResult {
     pub data_bits: String,                    // concatenación de todos los bloques (solo datos)
@@ -11,7 +122,7 @@ fn is_power_of_two(x: usize) -> bool {
 
 // Calcula r tal que 2^r >= n + 1 (n = longitud del código).
 // Este r corresponde al número de bits de paridad de un código de Hamming SEC (sin paridad global).
-fn parity_bits_count(n: usize) -> usize {
+pub(crate) fn parity_bits_count(n: usize) -> usize {
     let mut r = 0usize;
     while (1usize << r) < (n + 1) {
         r += 1;
@@ -19,68 +130,79 @@ fn parity_bits_count(n: usize) -> usize {
     r
 }
 
-// Decodifica un bloque Hamming de longitud n (n = m + r).
-// Retorna (datos, posición corregida) o error si el bloque es inconsistente.
-fn decode_block(block: &[u8]) -> Result<(Vec<u8>, Option<usize>), String> {
-    let n = block.len();
-    if n < 3 {
-        return Err("n demasiado pequeño".into());
-    }
-    let r = parity_bits_count(n);
-
+// Decodifica un bloque Hamming; `positions` es HammingCode::positions (una por bit).
+// Retorna (datos, posición corregida 1-based en el bloque), o Err(síndrome) si el
+// síndrome cae en HammingCode::uncorrectable_syndromes (solo en códigos acortados).
+fn decode_block(block: &[u8], positions: &[usize]) -> Result<(Vec<u8>, Option<usize>), usize> {
     // Calcular síndrome
     // NOTA: El síndrome en códigos de Hamming es una secuencia de bits que se utiliza
     // para identificar y corregir errores en la transmisión de datos. La interpretación
     //  del síndrome revela la posición del bit erróneo, permitiendo su corrección. 
+    let syndrome = syndrome(block, positions);
+
+    // Si síndrome != 0, corregir el bit que ocupa esa posición (si está en el bloque)
+    let mut corrected_pos: Option<usize> = None;
+    let mut corrected_block = block.to_vec();
+    if syndrome != 0 {
+        match positions.iter().position(|&pos| pos == syndrome) {
+            Some(i) => {
+                corrected_block[i] ^= 1;
+                corrected_pos = Some(i + 1);
+            }
+            None => return Err(syndrome),
+        }
+    }
+
+    // Extraer solo los bits de datos (omitir posiciones potencia de dos)
+    let data = corrected_block
+        .iter()
+        .zip(positions)
+        .filter(|(_, &pos)| !is_power_of_two(pos))
+        .map(|(&b, _)| b)
+        .collect();
+
+    Ok((data, corrected_pos))
+}
+
+// Cada bit de paridad p = 2^i (1-based) cubre las posiciones con el bit i en 1; el
+// síndrome junta las paridades que fallan.
+fn syndrome(block: &[u8], positions: &[usize]) -> usize {
+    let r = positions.iter().filter(|&&pos| is_power_of_two(pos)).count();
     let mut syndrome: usize = 0;
     for i in 0..r {
-        // posición de paridad p = 2^i (1-based)
         let p = 1usize << i;
         let mut parity = 0u8;
-        for pos in 1..=n {
+        for (bit, pos) in block.iter().zip(positions) {
             if (pos & p) != 0 {
-                parity ^= block[pos - 1];
+                parity ^= bit;
             }
         }
         if parity == 1 {
             syndrome |= p;
         }
     }
-
-    // Si síndrome != 0, corregir ese bit (si está dentro de rango)
-    let mut corrected_pos: Option<usize> = None;
-    let mut corrected_block = block.to_vec();
-    if syndrome != 0 {
-        if syndrome >= 1 && syndrome <= n {
-            corrected_block[syndrome - 1] ^= 1;
-            corrected_pos = Some(syndrome);
-        } else {
-            return Err(format!("Síndrome {} fuera de rango para n={}", syndrome, n));
-        }
-    }
-
-    // Extraer solo los bits de datos (omitir posiciones potencia de dos)
-    let mut data = Vec::new();
-    for pos in 1..=n {
-        if !is_power_of_two(pos) {
-            data.push(corrected_block[pos - 1]);
-        }
-    }
-
-    Ok((data, corrected_pos))
+    syndrome
 }
 
-// Decodifica una secuencia concatenada de bloques Hamming, cada uno de longitud n.
-// Retorna todos los datos concatenados y las posiciones corregidas por bloque.
-pub fn decode_stream(bits_str: &str, n: usize) -> Result<HammingStreamResult, String> {
+// Valida la trama y la convierte a bits, exigiendo longitud múltiplo de n.
+fn parse_stream(bits_str: &str, n: usize) -> Result<Vec<u8>, HammingError> {
     if !bits_str.chars().all(|c| c == '0' || c == '1') {
-        return Err("Solo se aceptan '0' y '1'".to_string());
+        return Err(HammingError::InvalidInput("Solo se aceptan '0' y '1'".to_string()));
     }
     let bits: Vec<u8> = bits_str.chars().map(|c| if c == '1' {1} else {0}).collect();
-    if bits.len() % n != 0 {
-        return Err(format!("La longitud de la trama ({}) no es múltiplo de n={}.", bits.len(), n));
+    if !bits.len().is_multiple_of(n) {
+        return Err(HammingError::InvalidInput(format!("La longitud de la trama ({}) no es múltiplo de n={}.", bits.len(), n)));
     }
+    Ok(bits)
+}
+
+// Decodifica una secuencia concatenada de bloques del código (n, k) dado.
+// Retorna todos los datos concatenados y las posiciones corregidas por bloque.
+pub fn decode_stream_code(bits_str: &str, code: &HammingCode) -> Result<HammingStreamResult, HammingError> {
+    let n = code.n;
+    let bits = parse_stream(bits_str, n)?;
     let num_blocks = bits.len() / n;
+    let positions = code.positions();
 
     let mut all_data = Vec::<u8>::new();
     let mut corrected_positions = Vec::<(usize, usize)>::new();
@@ -89,15 +211,15 @@ pub fn decode_stream(bits_str: &str, n: usize) -> Result<HammingStreamResult, St
         let start = b * n;
         let end = start + n;
         let block = &bits[start..end];
-        match decode_block(block) {
+        match decode_block(block, &positions) {
             Ok((data, corrected)) => {
                 if let Some(pos) = corrected {
                     corrected_positions.push((b, pos));
                 }
                 all_data.extend_from_slice(&data);
             }
-            Err(e) => {
-                return Err(format!("Bloque {} inválido: {}", b + 1, e));
+            Err(syndrome) => {
+                return Err(HammingError::Uncorrectable { block: b, syndrome, code: *code });
             }
         }
     }
@@ -106,15 +228,145 @@ pub fn decode_stream(bits_str: &str, n: usize) -> Result<HammingStreamResult, St
     Ok(HammingStreamResult { data_bits, corrected_positions })
 }
 
-// --------------------------------- Tests --------------------------------- 
-// === Helpers de emisor para pruebas Hamming ===
+// Estado de cada bloque en la decodificación parcial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockStatus {
+    Clean,
+    Corrected(usize),     // posición corregida (1-based)
+    Uncorrectable(usize), // síndrome fuera del código acortado
+}
 
+// Resultado de decode_stream_partial: los bloques no corregibles no descartan la
+// trama, sus bits de datos quedan como borrados (None).
+#[derive(Debug)]
+pub struct PartialStreamResult {
+    pub blocks: Vec<BlockStatus>,
+    pub data: Vec<Option<u8>>,
+}
 
+impl PartialStreamResult {
+    // Índices (0-based) de los bloques no corregibles.
+    pub fn uncorrectable_blocks(&self) -> Vec<usize> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, st)| matches!(st, BlockStatus::Uncorrectable(_)))
+            .map(|(b, _)| b)
+            .collect()
+    }
+
+    // Mismo formato que HammingStreamResult.
+    pub fn corrected_positions(&self) -> Vec<(usize, usize)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(b, st)| match st {
+                BlockStatus::Corrected(pos) => Some((b, *pos)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// Como decode_stream_code, pero sigue después de un bloque no corregible. Solo
+// falla si la entrada es inválida (caracteres o longitud).
+pub fn decode_stream_partial(bits_str: &str, code: &HammingCode) -> Result<PartialStreamResult, HammingError> {
+    let bits = parse_stream(bits_str, code.n)?;
+    let mut blocks = Vec::new();
+    let mut data = Vec::new();
+    let positions = code.positions();
+    for block in bits.chunks(code.n) {
+        match decode_block(block, &positions) {
+            Ok((d, corrected)) => {
+                blocks.push(match corrected {
+                    Some(pos) => BlockStatus::Corrected(pos),
+                    None => BlockStatus::Clean,
+                });
+                data.extend(d.into_iter().map(Some));
+            }
+            Err(syndrome) => {
+                blocks.push(BlockStatus::Uncorrectable(syndrome));
+                data.extend(std::iter::repeat_n(None, code.k));
+            }
+        }
+    }
+    Ok(PartialStreamResult { blocks, data })
+}
+
+// === Emisor (pruebas y simulaciones del canal) ===
+
+// Codifica un bloque de datos (k bits) en un bloque del código de `positions`.
+fn encode_block(data: &[u8], positions: &[usize]) -> Result<Vec<u8>, String> {
+    let k = positions.iter().filter(|&&pos| !is_power_of_two(pos)).count();
+    if data.len() != k {
+        return Err(format!("El bloque de datos debe tener k={} bits, recibido {}", k, data.len()));
+    }
+    // Colocar bits: paridad en potencias de dos, datos en el resto
+    let mut block = vec![0u8; positions.len()];
+    let mut di = 0usize;
+    for (i, &pos) in positions.iter().enumerate() {
+        if !is_power_of_two(pos) {
+            block[i] = data[di];
+            di += 1;
+        }
+    }
+    // Cada bit de paridad anula su bit del síndrome (solo él tiene ese bit en su posición)
+    let syndrome = syndrome(&block, positions);
+    for (i, &pos) in positions.iter().enumerate() {
+        if is_power_of_two(pos) && syndrome & pos != 0 {
+            block[i] = 1;
+        }
+    }
+    Ok(block)
+}
+
+// Codifica una secuencia de datos en bloques Hamming de longitud n (k inferido).
+pub(crate) fn encode_stream(data_bits: &str, n: usize) -> Result<String, String> {
+    encode_stream_code(data_bits, &HammingCode::from_n(n).map_err(|e| e.to_string())?)
+}
+
+// Codifica una secuencia de datos en bloques del código (n, k) dado.
+pub(crate) fn encode_stream_code(data_bits: &str, code: &HammingCode) -> Result<String, String> {
+    if !data_bits.chars().all(|c| c=='0' || c=='1') {
+        return Err("Solo se aceptan '0' y '1'".into());
+    }
+    let bits: Vec<u8> = data_bits.chars().map(|c| if c=='1' {1} else {0}).collect();
+    if !bits.len().is_multiple_of(code.k) {
+        return Err(format!("La longitud de datos ({}) debe ser múltiplo de k={} para ({}, {})", bits.len(), code.k, code.n, code.k));
+    }
+    let positions = code.positions();
+    let mut out = Vec::<u8>::new();
+    for chunk in bits.chunks(code.k) {
+        let block = encode_block(chunk, &positions)?;
+        out.extend_from_slice(&block);
+    }
+    let s: String = out.into_iter().map(|b| if b==1 {'1'} else {'0'}).collect();
+    Ok(s)
+}
+
+// --------------------------------- Tests ---------------------------------
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Bloques de longitud n con k inferido, como los manda el emisor sin k en PARAM.
+    fn decode_stream(bits_str: &str, n: usize) -> Result<HammingStreamResult, HammingError> {
+        decode_stream_code(bits_str, &HammingCode::from_n(n)?)
+    }
+
+    // Datos como texto con `marker` en cada bit borrado (p.ej. "10?1").
+    fn data_bits_marked(res: &PartialStreamResult, marker: char) -> String {
+        res.data
+            .iter()
+            .map(|b| match b {
+                Some(1) => '1',
+                Some(_) => '0',
+                None => marker,
+            })
+            .collect()
+    }
+
     fn flip_bit(s: String, idx: usize) -> String {
         let mut v: Vec<u8> = s.chars().map(|c| if c=='1' {1} else {0}).collect();
         if idx < v.len() { v[idx] ^= 1; }
@@ -175,4 +427,77 @@ mod tests {
             assert_eq!(res.data_bits, data, "datos corregidos deben coincidir");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn hamming_acortado_parametros() {
+        let code = HammingCode::new(12, 8).expect("(12,8)");
+        assert_eq!(code.uncorrectable_syndromes(), [13..=15]);
+        assert!(HammingCode::new(15, 11).unwrap().uncorrectable_syndromes().is_empty());
+        assert!(matches!(HammingCode::new(12, 9), Err(HammingError::InvalidParams(_))));
+        // Con más paridad que la mínima: (7, 3) es el (15, 11) sin los datos 7, 9..=15
+        let code = HammingCode::new(7, 3).expect("(7,3)");
+        assert_eq!(code.r, 4);
+        assert_eq!(code.positions(), [1, 2, 3, 4, 5, 6, 8]);
+        assert_eq!(code.uncorrectable_syndromes(), [7..=7, 9..=15]);
+        assert!(matches!(HammingCode::new(7, 0), Err(HammingError::InvalidParams(_))));
+        assert!(matches!(HammingCode::new(40, 20), Err(HammingError::InvalidParams(_))));
+        assert!(matches!(HammingCode::from_n(2), Err(HammingError::InvalidParams(_))));
+        assert!(matches!(HammingCode::from_n(MAX_N + 1), Err(HammingError::InvalidParams(_))));
+        assert_eq!(HammingCode::from_n(MAX_N).unwrap().r, 16);
+    }
+
+    #[test]
+    fn hamming_acortado_ida_y_vuelta_con_k_explicito() {
+        let data = "110100111000101101100011";
+        for (n, k) in [(12, 8), (10, 6), (7, 3), (12, 6)] {
+            let code = HammingCode::new(n, k).unwrap();
+            let codeword = encode_stream_code(data, &code).expect("emisor hamming");
+            assert_eq!(codeword.len(), data.len() / k * n);
+            let res = decode_stream_code(&codeword, &code).expect("decodificar");
+            assert!(res.corrected_positions.is_empty(), "({}, {})", n, k);
+            assert_eq!(res.data_bits, data);
+            // Un error simple en cualquier posición del último bloque se corrige
+            let last = codeword.len() - n;
+            for pos in 0..n {
+                let res = decode_stream_code(&flip_bit(codeword.clone(), last + pos), &code).expect("corregir");
+                assert_eq!(res.corrected_positions, [(data.len() / k - 1, pos + 1)], "({}, {}) bit {}", n, k, pos + 1);
+                assert_eq!(res.data_bits, data);
+            }
+        }
+        // Con r = 4 en (7, 3), dos errores que suman la posición quitada 7 se detectan
+        let code = HammingCode::new(7, 3).unwrap();
+        let tampered = flip_bit(flip_bit(encode_stream_code("101", &code).unwrap(), 0), 5);
+        assert!(matches!(decode_stream_code(&tampered, &code), Err(HammingError::Uncorrectable { syndrome: 7, .. })));
+    }
+
+    #[test]
+    fn hamming_acortado_dos_errores_detectados() {
+        let code = HammingCode::new(12, 8).unwrap();
+        let codeword = encode_stream("1011001011110000", 12).expect("emisor hamming");
+        // Posiciones 5 y 8 del bloque 2 -> síndrome 5 ^ 8 = 13 > n
+        let tampered = flip_bit(flip_bit(codeword, 12 + 4), 12 + 7);
+        match decode_stream_code(&tampered, &code) {
+            Err(HammingError::Uncorrectable { block, syndrome, .. }) => {
+                assert_eq!(block, 1);
+                assert_eq!(syndrome, 13);
+            }
+            other => panic!("se esperaba no corregible, se obtuvo {:?}", other),
+        }
+    }
+
+    #[test]
+    fn hamming_entrega_parcial_con_borrados() {
+        let code = HammingCode::new(12, 8).unwrap();
+        let codeword = encode_stream("101100101111000000000001", 12).expect("emisor hamming");
+        // Bloque 2 con síndrome 13 (no corregible) y bloque 3 con un error corregible
+        let tampered = flip_bit(flip_bit(codeword, 12 + 4), 12 + 7);
+        let tampered = flip_bit(tampered, 24 + 2);
+        assert!(decode_stream_code(&tampered, &code).is_err());
+        let res = decode_stream_partial(&tampered, &code).expect("parcial");
+        assert_eq!(res.blocks, vec![BlockStatus::Clean, BlockStatus::Uncorrectable(13), BlockStatus::Corrected(3)]);
+        assert_eq!(res.uncorrectable_blocks(), vec![1]);
+        assert_eq!(res.corrected_positions(), vec![(2, 3)]);
+        assert!(res.data.iter().any(|b| b.is_none()));
+        assert_eq!(data_bits_marked(&res, '?'), "10110010????????00000001");
+    }
+}
@@ -73,8 +73,41 @@ pub fn verify_crc32_poly(received_bits: &str) -> Result<CrcVerify, String> {
     }
 }
 
+// CRC-32 estándar (IEEE 802.3 / zlib: reflejado, init y xorout 0xFFFFFFFF) sobre bytes.
+// Es el que calcula java.util.zip.CRC32; se usa como checksum de archivo completo en
+// las transferencias, no para verificar tramas.
+pub fn crc32_ieee(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// === Emisor (pruebas y simulaciones del canal) ===
+pub(crate) fn append_crc32_poly(msg_bits_str: &str) -> Result<String, String> {
+    let mut msg = parse_bits(msg_bits_str)?;
+    // Asegurar que el mensaje tenga al menos 1 bit para que verificación (>=33) sea válida
+    if msg.is_empty() {
+        return Err("El mensaje para CRC no puede ser vacío".into());
+    }
+    // Padding: agregar 32 ceros (grado del polinomio)
+    msg.extend(std::iter::repeat_n(0u8, 32));
+    let divisor = crc32_poly_bits();
+    let remainder = mod2_divide(msg.clone(), &divisor); // 32 bits
+    if remainder.len() != 32 {
+        return Err(format!("Resto inesperado de longitud {}", remainder.len()));
+    }
+    // Formar codeword: mensaje original + resto
+    let mut original_msg = parse_bits(msg_bits_str)?;
+    original_msg.extend_from_slice(&remainder);
+    Ok(bits_to_string(&original_msg))
+}
+
 // --------------------------------- Tests ---------------------------------
-// === Helpers para pruebas (emulan al emisor) ===
 
 #[cfg(test)]
 mod tests {
@@ -136,4 +169,10 @@ mod tests {
             assert!(!v.valid, "debería ser inválido por 2+ errores");
         }
     }
+
+    #[test]
+    fn crc32_ieee_valor_de_referencia() {
+        assert_eq!(crc32_ieee(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32_ieee(b""), 0);
+    }
 }
@@ -0,0 +1,369 @@
+// Procesamiento de una trama (ALGO, PARAM, BITS) independiente de cómo llegó
+// (conexión TCP o archivo en modo batch).
+
+use std::collections::HashMap;
+
+use crate::correction;
+use crate::correction::hamming::{HammingCode, HammingError, PartialStreamResult};
+use crate::detection;
+use crate::transfer::TransferHeader;
+
+// Claves de PARAM cuyo valor es un entero no negativo (Hamming y transferencias).
+const NUMERIC_KEYS: [&str; 6] = ["n", "k", "pad", "seq", "total", "size"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Valid,         // sin errores
+    Corrected,     // Hamming corrigió al menos un bit
+    Uncorrectable, // Hamming detectó errores que no puede corregir
+    Detected,      // CRC-32 inválido: trama descartada
+    Partial,       // Hamming con bloques no corregibles, entregado con borrados
+    Error,         // trama mal formada o algoritmo no soportado
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Valid => "valido",
+            Status::Corrected => "corregido",
+            Status::Uncorrectable => "no_corregible",
+            Status::Detected => "descartado",
+            Status::Partial => "parcial",
+            Status::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FrameOutcome {
+    pub status: Status,
+    pub corrected_positions: Vec<(usize, usize)>, // (índice de bloque, posición 1-based)
+    pub message: Option<String>,                  // mensaje decodificado, si se pudo
+    pub untrusted_bytes: Vec<usize>,              // bytes del mensaje con bits borrados (entrega parcial)
+    pub payload: Option<Vec<u8>>,                 // bytes crudos entregados (content=bytes)
+    pub summary: String,                          // línea que imprime el receptor
+}
+
+// Opciones del receptor que afectan la decodificación de cada trama.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecodeOptions {
+    pub partial: bool,     // Hamming: entregar los bloques buenos aunque otros no sean corregibles
+    pub strict_utf8: bool, // content=utf8: descartar en vez de reemplazar secuencias inválidas
+}
+
+// Carácter que reemplaza a un byte no confiable en la entrega parcial.
+pub const ERASURE_MARKER: char = '?';
+
+// Máximo de bytes que se muestran en el volcado hexadecimal de la línea resumen.
+const HEX_PREVIEW: usize = 32;
+
+// Interpretación de la carga útil, indicada por el emisor con PARAM=...;content=...
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentKind {
+    #[default]
+    Latin1, // un char por byte (comportamiento original, sin content=)
+    Ascii,  // texto de 7 bits: un byte >= 0x80 es un error
+    Utf8,   // texto UTF-8
+    Bytes, // binario arbitrario: volcado hex y, opcionalmente, archivo
+}
+
+impl ContentKind {
+    pub fn parse(s: &str) -> Result<ContentKind, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "ascii" => Ok(ContentKind::Ascii),
+            "latin1" | "latin-1" => Ok(ContentKind::Latin1),
+            "utf8" | "utf-8" => Ok(ContentKind::Utf8),
+            "bytes" | "bin" => Ok(ContentKind::Bytes),
+            other => Err(format!("content desconocido: {} (use utf8, bytes, ascii o latin1)", other)),
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ContentKind::Latin1 => "Latin-1",
+            ContentKind::Ascii => "ASCII",
+            ContentKind::Utf8 => "UTF-8",
+            ContentKind::Bytes => "bytes",
+        }
+    }
+}
+
+pub fn bits_to_bytes(bits: &str) -> Result<Vec<u8>, String> {
+    if !bits.len().is_multiple_of(8) { return Err(format!("Longitud no múltiplo de 8: {}", bits.len())); }
+    let mut out = Vec::with_capacity(bits.len() / 8);
+    for chunk in bits.as_bytes().chunks(8) {
+        let s = std::str::from_utf8(chunk).map_err(|_| "utf8".to_string())?;
+        out.push(u8::from_str_radix(s, 2).map_err(|_| format!("byte inválido: {}", s))?);
+    }
+    Ok(out)
+}
+
+// Volcado "48 6f 6c 61 (4 bytes)"; los bytes no confiables (None) salen como "??".
+pub fn hex_dump(bytes: &[Option<u8>]) -> String {
+    let mut parts: Vec<String> = bytes
+        .iter()
+        .take(HEX_PREVIEW)
+        .map(|b| b.map(|v| format!("{:02x}", v)).unwrap_or_else(|| "??".into()))
+        .collect();
+    if bytes.len() > HEX_PREVIEW { parts.push("…".into()); }
+    format!("{} ({} bytes)", parts.join(" "), bytes.len())
+}
+
+// Texto que se muestra como "Mensaje:" según el tipo de contenido.
+pub fn render_payload(bytes: &[u8], kind: ContentKind, opts: &DecodeOptions) -> Result<String, String> {
+    match kind {
+        ContentKind::Latin1 => Ok(bytes.iter().map(|&v| v as char).collect()),
+        ContentKind::Ascii => match bytes.iter().position(|b| !b.is_ascii()) {
+            Some(i) => Err(format!("byte {:02x} no ASCII en la posición {}", bytes[i], i)),
+            None => Ok(bytes.iter().map(|&v| v as char).collect()),
+        },
+        ContentKind::Utf8 if opts.strict_utf8 => String::from_utf8(bytes.to_vec())
+            .map_err(|e| format!("UTF-8 inválido a partir del byte {}", e.utf8_error().valid_up_to())),
+        ContentKind::Utf8 => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        ContentKind::Bytes => Ok(hex_dump(&bytes.iter().copied().map(Some).collect::<Vec<_>>())),
+    }
+}
+
+// Datos ya sin redundancia -> (mensaje mostrado, bytes crudos si content=bytes).
+fn deliver(data_bits: &str, kind: ContentKind, opts: &DecodeOptions) -> Result<(String, Option<Vec<u8>>), String> {
+    let bytes = bits_to_bytes(data_bits)?;
+    let text = render_payload(&bytes, kind, opts)?;
+    Ok((text, (kind == ContentKind::Bytes).then_some(bytes)))
+}
+
+pub fn parse_param_map(s: &str) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for part in s.split(';') {
+        let p = part.trim();
+        if p.is_empty() { continue; }
+        if let Some((k,v)) = p.split_once('=') { map.insert(k.to_string(), v.to_string()); }
+    }
+    map
+}
+
+fn outcome(status: Status, message: Option<String>, summary: String) -> FrameOutcome {
+    FrameOutcome { status, corrected_positions: Vec::new(), message, untrusted_bytes: Vec::new(), payload: None, summary }
+}
+
+// Entrega parcial: cada byte con algún bit borrado se reemplaza por ERASURE_MARKER
+// (o "??" en el volcado hex de content=bytes).
+fn partial_outcome(res: PartialStreamResult, pad: usize, kind: ContentKind, opts: &DecodeOptions) -> FrameOutcome {
+    let mut data = res.data.clone();
+    if pad > 0 && pad <= data.len() { data.truncate(data.len() - pad); }
+    let bad_blocks = res.uncorrectable_blocks();
+    if !data.len().is_multiple_of(8) {
+        return outcome(Status::Uncorrectable, None, format!(
+            "Hamming: errores no corregibles en bloques {:?}; longitud no múltiplo de 8: {}", bad_blocks, data.len()
+        ));
+    }
+    let bytes: Vec<Option<u8>> = data
+        .chunks(8)
+        .map(|chunk| chunk.iter().try_fold(0u8, |acc, b| b.map(|v| (acc << 1) | v)))
+        .collect();
+    let untrusted: Vec<usize> = (0..bytes.len()).filter(|&i| bytes[i].is_none()).collect();
+    let text = if kind == ContentKind::Bytes {
+        hex_dump(&bytes)
+    } else {
+        let filled: Vec<u8> = bytes.iter().map(|b| b.unwrap_or(ERASURE_MARKER as u8)).collect();
+        match render_payload(&filled, kind, opts) {
+            Ok(t) => t,
+            Err(e) => return outcome(Status::Uncorrectable, None, format!(
+                "Hamming: errores no corregibles en bloques {:?}; no se pudo decodificar {}: {}", bad_blocks, kind.as_str(), e
+            )),
+        }
+    };
+    let mut out = outcome(Status::Partial, Some(text.clone()), format!(
+        "Hamming: entrega parcial, bloques no corregibles {:?}, bytes no confiables {:?}. Mensaje: {}",
+        bad_blocks, untrusted, text
+    ));
+    out.corrected_positions = res.corrected_positions();
+    out.untrusted_bytes = untrusted;
+    out
+}
+
+// PARAM contra las restricciones del algoritmo, antes de decodificar: entradas
+// clave=valor, enteros donde corresponde, un código de Hamming posible con pad < k,
+// mode=PURE en CRC-32, content conocido y, en un fragmento de archivo, su encabezado.
+pub fn validate_param(algo: &str, param_str: &str) -> Result<(), String> {
+    for part in param_str.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+        match part.split_once('=') {
+            Some((k, _)) if !k.trim().is_empty() => {}
+            _ => return Err(format!("entrada sin clave=valor: {}", part)),
+        }
+    }
+    let params = parse_param_map(param_str);
+    for key in NUMERIC_KEYS {
+        if let Some(v) = params.get(key) {
+            v.parse::<usize>().map_err(|_| format!("{}={} no es un entero no negativo", key, v))?;
+        }
+    }
+    let num = |key: &str| params.get(key).and_then(|v| v.parse::<usize>().ok());
+    match algo {
+        "HAMMING" => {
+            let n = num("n").unwrap_or(7);
+            let code = match num("k") {
+                Some(k) => HammingCode::new(n, k),
+                None => HammingCode::from_n(n),
+            }
+            .map_err(|e| e.to_string())?;
+            let pad = num("pad").unwrap_or(0);
+            if pad >= code.k {
+                return Err(format!("pad={} debe ser menor que k={}", pad, code.k));
+            }
+        }
+        "CRC32" => {
+            if let Some(mode) = params.get("mode").filter(|m| *m != "PURE") {
+                return Err(format!("mode={} no soportado (solo PURE)", mode));
+            }
+        }
+        _ => {}
+    }
+    if let Some(c) = params.get("content") {
+        ContentKind::parse(c)?;
+    }
+    if let Some(Err(e)) = TransferHeader::from_params(&params) {
+        return Err(format!("transferencia: {}", e));
+    }
+    Ok(())
+}
+
+pub fn process_frame(algo: &str, param_str: &str, bits: &str, opts: &DecodeOptions) -> FrameOutcome {
+    if let Err(e) = validate_param(algo, param_str) {
+        return outcome(Status::Error, None, format!("PARAM inválido, descartado. {}", e));
+    }
+    let params = parse_param_map(param_str);
+    let kind = params.get("content").and_then(|c| ContentKind::parse(c).ok()).unwrap_or_default();
+    match algo {
+        "CRC32" => {
+            match detection::crc32::verify_crc32_poly(bits) {
+                Ok(ok) if ok.valid => {
+                    let msg_bits = ok.original_message.unwrap_or_default();
+                    match deliver(&msg_bits, kind, opts) {
+                        Ok((s, payload)) => {
+                            let mut out = outcome(Status::Valid, Some(s.clone()), format!("CRC válido. Mensaje: {}", s));
+                            out.payload = payload;
+                            out
+                        }
+                        Err(e) => outcome(Status::Valid, None, format!("CRC válido, pero no se pudo decodificar {}: {}", kind.as_str(), e)),
+                    }
+                }
+                Ok(_) => outcome(Status::Detected, None, "CRC inválido: mensaje descartado".to_string()),
+                Err(e) => outcome(Status::Error, None, format!("Error CRC: {}", e)),
+            }
+        }
+        "HAMMING" => {
+            let n: usize = params.get("n").and_then(|v| v.parse().ok()).unwrap_or(7);
+            let pad: usize = params.get("pad").and_then(|v| v.parse().ok()).unwrap_or(0);
+            // k es opcional: si viene (p.ej. "n=12;k=8" o "n=7;k=3") fija r = n - k
+            let code = match params.get("k").and_then(|v| v.parse().ok()) {
+                Some(k) => HammingCode::new(n, k),
+                None => HammingCode::from_n(n),
+            };
+            let code = match code {
+                Ok(c) => c,
+                Err(e) => return outcome(Status::Error, None, format!("Hamming: parámetros inválidos, descartado. {}", e)),
+            };
+            match correction::hamming::decode_stream_code(bits, &code) {
+                Ok(res) => {
+                    let mut data = res.data_bits;
+                    if pad > data.len() {
+                        return outcome(Status::Error, None, format!("PARAM inválido, descartado. pad={} mayor que los {} bits de datos", pad, data.len()));
+                    }
+                    data.truncate(data.len() - pad);
+                    let status = if res.corrected_positions.is_empty() { Status::Valid } else { Status::Corrected };
+                    let mut out = match deliver(&data, kind, opts) {
+                        Ok((s, payload)) => {
+                            let summary = if res.corrected_positions.is_empty() {
+                                format!("Hamming: sin errores. Mensaje: {}", s)
+                            } else {
+                                format!("Hamming: errores corregidos en {:?}. Mensaje: {}", res.corrected_positions, s)
+                            };
+                            let mut out = outcome(status, Some(s), summary);
+                            out.payload = payload;
+                            out
+                        }
+                        Err(e) => outcome(status, None, format!("Hamming ok, pero no se pudo decodificar {}: {}", kind.as_str(), e)),
+                    };
+                    out.corrected_positions = res.corrected_positions;
+                    out
+                }
+                Err(HammingError::Uncorrectable { .. }) if opts.partial => {
+                    match correction::hamming::decode_stream_partial(bits, &code) {
+                        Ok(res) => partial_outcome(res, pad, kind, opts),
+                        Err(e) => outcome(Status::Error, None, format!("Hamming: trama inválida, descartado. {}", e)),
+                    }
+                }
+                Err(e @ HammingError::Uncorrectable { .. }) => {
+                    outcome(Status::Uncorrectable, None, format!("Hamming: errores no corregibles. {}", e))
+                }
+                Err(e) => outcome(Status::Error, None, format!("Hamming: trama inválida, descartado. {}", e)),
+            }
+        }
+        other => outcome(Status::Error, None, format!("Algoritmo no soportado: {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::crc32::append_crc32_poly;
+
+    fn to_bits(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:08b}", b)).collect()
+    }
+
+    #[test]
+    fn contenido_utf8_y_ascii() {
+        let frame = append_crc32_poly(&to_bits("año ✓".as_bytes())).unwrap();
+        let opts = DecodeOptions::default();
+        let utf8 = process_frame("CRC32", "mode=PURE;content=utf8", &frame, &opts);
+        assert_eq!(utf8.message.as_deref(), Some("año ✓"));
+        // Sin content= se mantiene un char por byte (mojibake para no-ASCII)
+        let legacy = process_frame("CRC32", "mode=PURE", &frame, &opts);
+        assert_eq!(legacy.message.as_deref(), Some("aÃ±o â\u{9c}\u{93}"));
+        assert_eq!(process_frame("CRC32", "mode=PURE;content=latin1", &frame, &opts).message, legacy.message);
+        // content=ascii no acepta bytes de 8 bits
+        let ascii = process_frame("CRC32", "mode=PURE;content=ascii", &frame, &opts);
+        assert_eq!(ascii.status, Status::Valid);
+        assert!(ascii.message.is_none());
+        assert!(ascii.summary.ends_with("no se pudo decodificar ASCII: byte c3 no ASCII en la posición 1"), "{}", ascii.summary);
+        let plain = append_crc32_poly(&to_bits(b"Hola")).unwrap();
+        assert_eq!(process_frame("CRC32", "mode=PURE;content=ascii", &plain, &opts).message.as_deref(), Some("Hola"));
+    }
+
+    #[test]
+    fn contenido_utf8_estricto_y_bytes() {
+        let raw = [0x00, 0xff, 0x41, 0x0a];
+        let frame = append_crc32_poly(&to_bits(&raw)).unwrap();
+        let lossy = process_frame("CRC32", "content=utf8", &frame, &DecodeOptions::default());
+        assert_eq!(lossy.message.as_deref(), Some("\u{0}\u{fffd}A\n"));
+        let strict = DecodeOptions { strict_utf8: true, ..Default::default() };
+        let res = process_frame("CRC32", "content=utf8", &frame, &strict);
+        assert!(res.message.is_none());
+        assert!(res.summary.contains("UTF-8 inválido a partir del byte 1"));
+
+        let bytes = process_frame("CRC32", "content=bytes", &frame, &DecodeOptions::default());
+        assert_eq!(bytes.payload.as_deref(), Some(&raw[..]));
+        assert_eq!(bytes.summary, "CRC válido. Mensaje: 00 ff 41 0a (4 bytes)");
+        let bad = process_frame("CRC32", "content=latin9", &frame, &DecodeOptions::default());
+        assert_eq!(bad.status, Status::Error);
+    }
+
+    #[test]
+    fn param_validado_contra_el_algoritmo() {
+        assert_eq!(validate_param("HAMMING", "n=7;pad=3;content=utf8"), Ok(()));
+        assert_eq!(validate_param("HAMMING", "n=siete").unwrap_err(), "n=siete no es un entero no negativo");
+        assert_eq!(validate_param("HAMMING", "n=7;pad=4").unwrap_err(), "pad=4 debe ser menor que k=4");
+        assert!(validate_param("HAMMING", "n=2").is_err());
+        assert!(validate_param("HAMMING", "n=18446744073709551615").is_err());
+        assert_eq!(validate_param("CRC32", "mode=CRC16").unwrap_err(), "mode=CRC16 no soportado (solo PURE)");
+        assert_eq!(validate_param("CRC32", "mode=PURE;basura").unwrap_err(), "entrada sin clave=valor: basura");
+        assert!(validate_param("CRC32", "file=a.bin;seq=3;total=2;size=1;filecrc=0").unwrap_err().starts_with("transferencia:"));
+
+        // pad válido para el código pero mayor que los datos de la trama
+        let one_block = correction::hamming::encode_stream("0100", 7).unwrap();
+        assert_eq!(process_frame("HAMMING", "n=7;pad=3", &one_block, &DecodeOptions::default()).status, Status::Valid);
+        let res = process_frame("HAMMING", "n=7;pad=3", "", &DecodeOptions::default());
+        assert_eq!(res.status, Status::Error);
+    }
+}
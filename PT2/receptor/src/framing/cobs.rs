@@ -0,0 +1,110 @@
+// COBS (Consistent Overhead Byte Stuffing): el contenido se parte en bloques que
+// terminan en un 0 (o al llegar a 254 bytes). Cada bloque se escribe como un byte
+// con su largo + 1, seguido de sus bytes sin el 0; así la trama no tiene ningún 0 y
+// el 0x00 queda libre como delimitador al final de cada trama:
+//
+//   contenido  11 22 00 33
+//   canal      03 11 22 02 33 00
+//
+// La sobrecarga es fija: un byte cada 254 (más el delimitador), sin importar los datos.
+// Un largo dañado descoloca los ceros de la trama, pero nunca el delimitador.
+
+pub const DELIMITER: u8 = 0x00;
+const MAX_BLOCK: u8 = 0xFF; // 254 bytes sin 0 al final
+
+pub fn decode(frame: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut i = 0;
+    while i < frame.len() {
+        let code = frame[i];
+        let end = i + code as usize;
+        if code == DELIMITER || end > frame.len() {
+            return Err(format!("bloque COBS de largo {} en el byte {}, fuera de la trama de {} bytes", code, i, frame.len()));
+        }
+        out.extend_from_slice(&frame[i + 1..end]);
+        i = end;
+        // El 0 del final del bloque; el último bloque no lo lleva
+        if code != MAX_BLOCK && i < frame.len() {
+            out.push(0);
+        }
+    }
+    Ok(out)
+}
+
+// Lo que hace el emisor con el contenido de una trama.
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![0];
+    let mut code_at = 0;
+    let mut code = 1u8;
+    for &b in payload {
+        if b != 0 {
+            out.push(b);
+            code += 1;
+        }
+        if b == 0 || code == MAX_BLOCK {
+            out[code_at] = code;
+            code_at = out.len();
+            out.push(0);
+            code = 1;
+        }
+    }
+    out[code_at] = code;
+    out.push(DELIMITER);
+    out
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framing::sync::SyncConfig;
+    use crate::framing::{deframe, pack_payload, Framing};
+
+    #[test]
+    fn bloques_sin_ceros() {
+        assert_eq!(encode(&[0x11, 0x22, 0x00, 0x33]), [0x03, 0x11, 0x22, 0x02, 0x33, 0x00]);
+        assert_eq!(encode(&[0x00]), [0x01, 0x01, 0x00]);
+        assert_eq!(encode(&[]), [0x01, 0x00]);
+        let long: Vec<u8> = (1..=254u8).chain([0, 7]).collect();
+        let channel = encode(&long);
+        assert_eq!((channel[0], channel[255], channel[256]), (0xFF, 0x01, 0x02));
+        for payload in [&long[..], &[0, 0, 0], &[5; 600]] {
+            let channel = encode(payload);
+            assert!(!channel[..channel.len() - 1].contains(&0));
+            assert_eq!(decode(&channel[..channel.len() - 1]).unwrap(), payload);
+        }
+        assert!(decode(&[0x05, 0x11]).is_err());
+    }
+
+    #[test]
+    fn delimitadores_dañados() {
+        let bits = ["1".repeat(100), "0".repeat(100), "0110".repeat(25)];
+        let encoded: Vec<Vec<u8>> = bits.iter().map(|b| encode(&pack_payload(b))).collect();
+        let channel = encoded.concat();
+        let (frames, stats) = deframe(Framing::Cobs, &channel, 1024, &SyncConfig::default()).unwrap();
+        assert_eq!(frames.iter().map(|f| f.as_ref().unwrap().bits.as_str()).collect::<Vec<_>>(), bits);
+        assert_eq!((stats.delimiters, stats.frames), (3, 3));
+
+        // Delimitador de la 1.ª trama dañado: se une con la 2.ª, que se pierde con ella
+        let mut bad = channel.clone();
+        bad[encoded[0].len() - 1] = 0x01;
+        let (frames, _) = deframe(Framing::Cobs, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].is_err());
+        assert_eq!(frames[1].as_ref().unwrap().bits, bits[2]);
+
+        // Un 0 de más en medio de la 2.ª trama la parte en dos; la 3.ª no se entera
+        let mut bad = channel.clone();
+        bad[encoded[0].len() + 3] = DELIMITER;
+        let (frames, stats) = deframe(Framing::Cobs, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert_eq!(frames.iter().map(|f| f.is_ok()).collect::<Vec<_>>(), [true, false, false, true]);
+        assert_eq!(stats.discarded, 2);
+
+        // Largo de bloque dañado dentro de la 3.ª trama
+        let mut bad = channel.clone();
+        bad[encoded[0].len() + encoded[1].len()] = 0x40;
+        let (frames, _) = deframe(Framing::Cobs, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert!(matches!(&frames[2], Err((_, e)) if e.contains("bloque COBS")));
+    }
+}
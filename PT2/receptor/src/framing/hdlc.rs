@@ -0,0 +1,215 @@
+// Entramado HDLC sobre un canal de bits. Cada trama va entre banderas 01111110 (0x7E)
+// y, dentro, después de cinco 1 seguidos el emisor inserta un 0 (bit stuffing), así la
+// bandera nunca aparece en los datos:
+//
+//   datos        0111111 0011111
+//   con relleno  01111101 00111110
+//   canal        01111110 0111110100111110 01111110 ...
+//
+// Una misma bandera puede cerrar una trama y abrir la siguiente; las banderas
+// seguidas (o que comparten el 0) son relleno entre tramas. Siete o más 1 seguidos
+// son una secuencia de aborto: la trama en curso se descarta y el receptor busca la
+// próxima bandera. Así, un error que rompe una bandera o el relleno solo afecta a las
+// tramas vecinas: la sincronización se recupera en la bandera siguiente.
+
+use super::{bits_to_string, DeframeStats, Deframed, Frame};
+
+pub const FLAG: u8 = 0x7E;
+
+// Quita el 0 que sigue a cada cinco 1. Seis 1 seguidos no pueden estar dentro de una
+// trama (serían parte de una bandera o un aborto), ni puede faltar el 0 del final.
+fn destuff(raw: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(raw.len());
+    let mut ones = 0;
+    for &b in raw {
+        if ones == 5 {
+            if b == 1 {
+                return Err("seis unos seguidos dentro de la trama".into());
+            }
+            ones = 0;
+            continue;
+        }
+        ones = if b == 1 { ones + 1 } else { 0 };
+        out.push(b);
+    }
+    if ones == 5 {
+        return Err("falta el 0 de relleno al final de la trama".into());
+    }
+    Ok(out)
+}
+
+// Recorre el canal bit a bit. Las banderas y los abortos se reconocen sobre los bits
+// crudos; el relleno se quita después, trama por trama.
+pub fn deframe(bits: &[u8], max_bits: usize) -> (Vec<Deframed>, DeframeStats) {
+    let mut frames = Vec::new();
+    let mut stats = DeframeStats { channel: bits.len(), ..DeframeStats::default() };
+    let mut window = 0u8;
+    let mut ones = 0;
+    let mut start: Option<usize> = None; // primer bit después de la bandera de apertura
+    let mut raw = Vec::new();
+
+    for (i, &b) in bits.iter().enumerate() {
+        window = (window << 1) | b;
+        ones = if b == 1 { ones + 1 } else { 0 };
+        match start {
+            Some(_) => raw.push(b),
+            None => stats.hunting += 1,
+        }
+        if ones == 7 {
+            if let Some(s) = start.take() {
+                // Solo 1 desde la bandera: canal en reposo, no una trama
+                if raw.len() > 7 {
+                    frames.push(Err((s, "trama abortada (7 unos seguidos)".to_string())));
+                    stats.discarded += 1;
+                }
+                raw.clear();
+            }
+            continue;
+        }
+        if i < 7 || window != FLAG {
+            continue;
+        }
+        stats.delimiters += 1;
+        match start {
+            Some(s) => {
+                raw.truncate(raw.len().saturating_sub(8));
+                if !raw.is_empty() {
+                    let frame = destuff(&raw).and_then(|content| {
+                        if content.len() > max_bits {
+                            Err(format!("trama de {} bits (máximo {})", content.len(), max_bits))
+                        } else {
+                            Ok(content)
+                        }
+                    });
+                    match frame {
+                        Ok(content) => {
+                            stats.frames += 1;
+                            stats.payload += content.len();
+                            frames.push(Ok(Frame { offset: s, bits: bits_to_string(&content) }));
+                        }
+                        Err(e) => {
+                            stats.discarded += 1;
+                            frames.push(Err((s, e)));
+                        }
+                    }
+                }
+            }
+            // Los 8 bits de la bandera se contaron como búsqueda
+            None => stats.hunting = stats.hunting.saturating_sub(8),
+        }
+        start = Some(i + 1);
+        raw.clear();
+    }
+    // Bits después de la última bandera: solo 1 es reposo; otra cosa es una trama cortada
+    if let Some(s) = start {
+        if raw.contains(&0) {
+            frames.push(Err((s, "trama sin bandera de cierre".to_string())));
+            stats.discarded += 1;
+        }
+    }
+    (frames, stats)
+}
+
+// Lo que hace el emisor: un 0 después de cada cinco 1.
+pub fn stuff(bits: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bits.len() + bits.len() / 5);
+    let mut ones = 0;
+    for &b in bits {
+        out.push(b);
+        ones = if b == 1 { ones + 1 } else { 0 };
+        if ones == 5 {
+            out.push(0);
+            ones = 0;
+        }
+    }
+    out
+}
+
+// Canal con cada trama entre banderas, compartiendo la bandera entre tramas seguidas.
+pub fn encode(frames: &[&str]) -> String {
+    let flag = "01111110";
+    let mut out = String::from(flag);
+    for f in frames {
+        let bits: Vec<u8> = f.chars().map(|c| if c == '1' { 1 } else { 0 }).collect();
+        out.push_str(&bits_to_string(&stuff(&bits)));
+        out.push_str(flag);
+    }
+    out
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framing::parse_bit_channel;
+
+    fn to_bits(s: &str) -> Vec<u8> {
+        parse_bit_channel(s.as_bytes()).unwrap()
+    }
+
+    fn ok_frames(res: &[Deframed]) -> Vec<&str> {
+        res.iter().filter_map(|r| r.as_ref().ok()).map(|f| f.bits.as_str()).collect()
+    }
+
+    #[test]
+    fn relleno_de_bits() {
+        assert_eq!(bits_to_string(&stuff(&to_bits("01111110011111"))), "0111110100111110");
+        assert_eq!(bits_to_string(&stuff(&to_bits("11111111111"))), "1111101111101");
+        // El relleno se quita y ninguna bandera queda dentro de los datos
+        for data in ["0", "11111", "0111111001111110", "1111111111111111111"] {
+            let stuffed = stuff(&to_bits(data));
+            assert!(!bits_to_string(&stuffed).contains("111111"));
+            assert_eq!(bits_to_string(&destuff(&stuffed).unwrap()), data);
+        }
+        assert!(destuff(&to_bits("0111111")).is_err());
+        assert!(destuff(&to_bits("011111")).is_err());
+    }
+
+    #[test]
+    fn varias_tramas_concatenadas() {
+        let data = ["1011", "0111111001111110", "11111"];
+        // Basura antes de la primera bandera, banderas de relleno y reposo en 1 al final
+        let channel = format!("0010{}01111110{}1111111111", encode(&data[..2]), encode(&data[2..]));
+        let (res, stats) = deframe(&to_bits(&channel), 1024);
+        assert_eq!(ok_frames(&res), data);
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].as_ref().unwrap().offset, 12);
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.delimiters, 6);
+        assert_eq!(stats.hunting, 4 + 3, "basura inicial y los 1 de reposo después del aborto");
+        assert_eq!(stats.payload, 4 + 16 + 5);
+        assert_eq!(stats.discarded, 0);
+    }
+
+    #[test]
+    fn resincroniza_despues_de_errores() {
+        let data = ["10110011", "00001111", "11110000", "01010101"];
+        let channel = encode(&data);
+        let mut bits = to_bits(&channel);
+        // Un bit cambiado rompe la bandera entre la 1.ª y la 2.ª trama: quedan unidas
+        let flag2 = 8 + 8;
+        bits[flag2 + 3] = 0;
+        let (res, _) = deframe(&bits, 1024);
+        let got = ok_frames(&res);
+        assert_eq!(got.len(), 3);
+        assert!(got[0].len() > 16, "las dos primeras tramas llegan juntas");
+        assert_eq!(got[1..], data[2..]);
+
+        // Siete 1 en la 2.ª trama: se aborta y la 3.ª se recupera en la bandera siguiente
+        let mut bits = to_bits(&channel);
+        for b in &mut bits[25..32] {
+            *b = 1;
+        }
+        let (res, stats) = deframe(&bits, 1024);
+        assert!(matches!(&res[1], Err((24, e)) if e.contains("abortada")));
+        assert_eq!(ok_frames(&res), [data[0], data[2], data[3]]);
+        assert_eq!(stats.discarded, 1);
+
+        // Demasiado larga, o cortada al final del canal
+        let (res, _) = deframe(&to_bits(&encode(&data)), 7);
+        assert!(res.iter().all(|r| r.is_err()));
+        let (res, _) = deframe(&to_bits(&channel[..channel.len() - 10]), 1024);
+        assert!(matches!(res.last(), Some(Err((_, e))) if e.contains("cierre")));
+    }
+}
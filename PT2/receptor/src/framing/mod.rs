@@ -0,0 +1,402 @@
+// Entramado sobre un canal crudo: separa las tramas de un flujo continuo para que el
+// receptor no dependa de las líneas del protocolo de texto y pueda volver a
+// sincronizarse después de un error (ver `receptor --batch ... --framing`).
+//
+// HDLC y la sincronización por palabra (sync) trabajan sobre bits. PPP, SLIP y COBS
+// trabajan sobre bytes: cada trama lleva la cantidad de bits del código (u32
+// big-endian) y los bits empaquetados MSB primero, como el formato binario del cable
+// (wire.rs). El largo permite descartar las tramas que quedaron unidas o cortadas
+// por un delimitador dañado.
+
+pub mod cobs;
+pub mod hdlc;
+pub mod ppp;
+pub mod slip;
+pub mod sync;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::channel::{self, Rng};
+use crate::frame::{self, DecodeOptions, Status};
+use crate::wire;
+
+pub const ALL: [Framing; 5] = [Framing::Hdlc, Framing::Ppp, Framing::Slip, Framing::Cobs, Framing::Sync];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    Hdlc, // banderas 0x7E con bit stuffing, sobre bits ('0'/'1')
+    Ppp,  // banderas 0x7E con byte stuffing (escape 0x7D)
+    Slip, // END 0xC0 con escape 0xDB
+    Cobs, // bloques sin ceros, delimitados por 0x00
+    Sync, // preámbulo y palabra de sincronización con tolerancia, y campo de largo, sobre bits
+}
+
+impl Framing {
+    pub fn parse(s: &str) -> Result<Framing, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "hdlc" => Ok(Framing::Hdlc),
+            "ppp" => Ok(Framing::Ppp),
+            "slip" => Ok(Framing::Slip),
+            "cobs" => Ok(Framing::Cobs),
+            "sync" => Ok(Framing::Sync),
+            other => Err(format!("Entramado desconocido: {} (hdlc, ppp, slip, cobs o sync)", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Framing::Hdlc => "hdlc",
+            Framing::Ppp => "ppp",
+            Framing::Slip => "slip",
+            Framing::Cobs => "cobs",
+            Framing::Sync => "sync",
+        }
+    }
+
+    // Unidad del canal en la que se cuentan posiciones y tamaños.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Framing::Hdlc | Framing::Sync => "bits",
+            Framing::Ppp | Framing::Slip | Framing::Cobs => "bytes",
+        }
+    }
+}
+
+// Trama extraída del canal: posición donde empieza su contenido y bits del código
+// (Hamming o CRC) que transporta.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub offset: usize,
+    pub bits: String,
+}
+
+// Trama extraída, o (posición, motivo) si hubo que descartarla.
+pub type Deframed = Result<Frame, (usize, String)>;
+
+// Contabilidad del canal, en la unidad de cada entramado.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeframeStats {
+    pub channel: usize,    // largo total del canal
+    pub payload: usize,    // contenido entregado en tramas válidas (sin delimitadores ni relleno)
+    pub delimiters: usize, // banderas o delimitadores vistos
+    pub frames: usize,
+    pub discarded: usize,  // tramas descartadas (aborto, relleno inválido, demasiado largas)
+    pub hunting: usize,    // descartado buscando el comienzo de una trama
+}
+
+impl DeframeStats {
+    // Porcentaje del canal que no es contenido (delimitadores, relleno, basura).
+    pub fn overhead(&self) -> f64 {
+        if self.channel == 0 {
+            return 0.0;
+        }
+        100.0 * (self.channel - self.payload.min(self.channel)) as f64 / self.channel as f64
+    }
+}
+
+impl fmt::Display for DeframeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} en el canal, {} de contenido ({:.1}% de sobrecarga), {} delimitadores, {} tramas, \
+             {} descartadas, {} descartados buscando el comienzo",
+            self.channel, self.payload, self.overhead(), self.delimiters, self.frames, self.discarded, self.hunting
+        )
+    }
+}
+
+// Canal de bits en texto: '0' y '1', con espacios, saltos de línea y líneas de
+// comentario ('#') que se ignoran.
+pub fn parse_bit_channel(input: &[u8]) -> Result<Vec<u8>, String> {
+    let text = std::str::from_utf8(input).map_err(|_| "el canal de bits debe ser texto de '0' y '1'".to_string())?;
+    let mut bits = Vec::with_capacity(text.len());
+    for (i, line) in text.lines().enumerate() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        for c in line.chars().filter(|c| !c.is_whitespace()) {
+            match c {
+                '0' => bits.push(0),
+                '1' => bits.push(1),
+                other => return Err(format!("línea {}: carácter no binario {:?} en el canal", i + 1, other)),
+            }
+        }
+    }
+    Ok(bits)
+}
+
+pub(crate) fn bits_to_string(bits: &[u8]) -> String {
+    bits.iter().map(|&b| if b == 1 { '1' } else { '0' }).collect()
+}
+
+// Contenido de una trama de bytes para los bits de un código: el largo en bits y
+// los bits empaquetados.
+pub fn pack_payload(bits: &str) -> Vec<u8> {
+    let mut out = (bits.len() as u32).to_be_bytes().to_vec();
+    out.extend(wire::pack_bits(bits));
+    out
+}
+
+// Bits del código a partir del contenido de una trama de bytes.
+fn unpack_payload(bytes: &[u8], max_bits: usize) -> Result<String, String> {
+    let Some((len, data)) = bytes.split_first_chunk::<4>() else {
+        return Err(format!("trama de {} bytes, sin el largo en bits", bytes.len()));
+    };
+    let bit_len = u32::from_be_bytes(*len) as usize;
+    if bit_len > max_bits {
+        return Err(format!("trama de {} bits (máximo {})", bit_len, max_bits));
+    }
+    if data.len() != bit_len.div_ceil(8) {
+        return Err(format!("el largo indica {} bits y la trama trae {} bytes de datos", bit_len, data.len()));
+    }
+    Ok(wire::unpack_bits(data, bit_len))
+}
+
+// Separa el canal en cada `delim` y decodifica el contenido de cada trama. Con
+// `opening`, lo anterior al primer delimitador es basura (PPP); en SLIP y COBS ya es
+// una trama. Lo que queda después del último delimitador es una trama cortada.
+fn split_delimited(
+    input: &[u8],
+    delim: u8,
+    opening: bool,
+    max_bits: usize,
+    decode: fn(&[u8]) -> Result<Vec<u8>, String>,
+) -> (Vec<Deframed>, DeframeStats) {
+    let mut frames = Vec::new();
+    let mut stats = DeframeStats { channel: input.len(), ..DeframeStats::default() };
+    let mut synced = !opening;
+    let mut start = 0;
+    for (i, _) in input.iter().enumerate().filter(|(_, &b)| b == delim) {
+        stats.delimiters += 1;
+        if !synced {
+            stats.hunting += i;
+            synced = true;
+        } else if i > start {
+            let frame = decode(&input[start..i]).and_then(|content| Ok((content.len(), unpack_payload(&content, max_bits)?)));
+            match frame {
+                Ok((len, bits)) => {
+                    stats.frames += 1;
+                    stats.payload += len;
+                    frames.push(Ok(Frame { offset: start, bits }));
+                }
+                Err(e) => {
+                    stats.discarded += 1;
+                    frames.push(Err((start, e)));
+                }
+            }
+        }
+        start = i + 1;
+    }
+    if !synced {
+        stats.hunting = input.len();
+    } else if start < input.len() {
+        stats.discarded += 1;
+        frames.push(Err((start, "trama sin delimitador de cierre".to_string())));
+    }
+    (frames, stats)
+}
+
+// Extrae las tramas de `input` (el archivo completo del canal). Las tramas de más de
+// `max_bits` bits se descartan. `sync` solo se usa con Framing::Sync.
+pub fn deframe(
+    framing: Framing,
+    input: &[u8],
+    max_bits: usize,
+    sync: &sync::SyncConfig,
+) -> Result<(Vec<Deframed>, DeframeStats), String> {
+    match framing {
+        Framing::Hdlc => Ok(hdlc::deframe(&parse_bit_channel(input)?, max_bits)),
+        Framing::Sync => Ok(sync::deframe(&parse_bit_channel(input)?, sync, max_bits)),
+        Framing::Ppp => Ok(split_delimited(input, ppp::FLAG, true, max_bits, ppp::decode)),
+        Framing::Slip => Ok(split_delimited(input, slip::END, false, max_bits, slip::decode)),
+        Framing::Cobs => Ok(split_delimited(input, cobs::DELIMITER, false, max_bits, cobs::decode)),
+    }
+}
+
+// Lo que hace el emisor: las tramas (bits del código de cada una) una tras otra en
+// el formato de archivo que lee `deframe`.
+pub fn encode(framing: Framing, codes: &[String], sync: &sync::SyncConfig) -> Result<Vec<u8>, String> {
+    match framing {
+        Framing::Hdlc => Ok(hdlc::encode(&codes.iter().map(String::as_str).collect::<Vec<_>>()).into_bytes()),
+        Framing::Sync => {
+            let mut out = Vec::new();
+            for c in codes {
+                let code: Vec<u8> = c.bytes().map(|b| b - b'0').collect();
+                out.extend(sync::encode_frame(&code, sync)?.into_iter().map(|b| b'0' + b));
+            }
+            Ok(out)
+        }
+        Framing::Ppp => Ok(codes.iter().flat_map(|c| ppp::encode(&pack_payload(c))).collect()),
+        Framing::Slip => Ok(codes.iter().flat_map(|c| slip::encode(&pack_payload(c))).collect()),
+        Framing::Cobs => Ok(codes.iter().flat_map(|c| cobs::encode(&pack_payload(c))).collect()),
+    }
+}
+
+// Cambia cada bit del canal con probabilidad `ber`: los caracteres '0'/'1' de los
+// entramados de bits o cada bit de los bytes.
+fn add_noise(framing: Framing, channel: &mut [u8], ber: f64, rng: &mut Rng) -> usize {
+    if framing.unit() == "bits" {
+        let mut bits: Vec<u8> = channel.iter().map(|c| c - b'0').collect();
+        let flips = channel::flip_bits(&mut bits, ber, rng);
+        channel.iter_mut().zip(bits).for_each(|(c, b)| *c = b'0' + b);
+        return flips;
+    }
+    let mut flips = 0;
+    for byte in channel.iter_mut() {
+        for i in 0..8 {
+            if rng.next_f64() < ber {
+                *byte ^= 1 << i;
+                flips += 1;
+            }
+        }
+    }
+    flips
+}
+
+// Resultado de la simulación de un entramado para una BER.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FrameSimStats {
+    pub ber: f64,
+    pub frames: usize,       // mensajes enviados
+    pub channel: usize,      // largo del canal, en la unidad del entramado
+    pub payload: usize,      // contenido de las tramas enviadas, en la misma unidad
+    pub bit_errors: usize,   // bits cambiados por el canal
+    pub delivered: usize,    // tramas extraídas del canal
+    pub discarded: usize,    // tramas descartadas por el entramado
+    pub by_status: HashMap<&'static str, usize>,
+    pub intact: usize,       // mensajes entregados con los bytes enviados
+    pub wrong: usize,        // tramas entregadas con bytes que no se enviaron
+}
+
+impl FrameSimStats {
+    pub fn overhead(&self) -> f64 {
+        DeframeStats { channel: self.channel, payload: self.payload, ..DeframeStats::default() }.overhead()
+    }
+}
+
+impl fmt::Display for FrameSimStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |st: Status| self.by_status.get(st.as_str()).copied().unwrap_or(0);
+        write!(
+            f,
+            "BER {}: {} mensajes, {:.1}% de sobrecarga, {} bits cambiados, {} tramas extraídas, {} descartadas; \
+             valido {}, corregido {}, no_corregible {}, descartado {}, error {}; {} intactos, {} perdidos, entregadas mal {}",
+            self.ber, self.frames, self.overhead(), self.bit_errors, self.delivered, self.discarded, count(Status::Valid),
+            count(Status::Corrected), count(Status::Uncorrectable), count(Status::Detected), count(Status::Error), self.intact,
+            self.frames - self.intact, self.wrong
+        )
+    }
+}
+
+// Envía `frames` mensajes aleatorios de `msg_bytes` bytes codificados con `algo`
+// (Hamming de largo `n` o CRC-32), entramados uno tras otro en un mismo canal, pasa
+// el canal por el ruido y lo procesa como `--batch --framing`: extrae las tramas y
+// decodifica cada una.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate(
+    framing: Framing,
+    sync: &sync::SyncConfig,
+    algo: &str,
+    n: usize,
+    frames: usize,
+    msg_bytes: usize,
+    ber: f64,
+    rng: &mut Rng,
+) -> Result<FrameSimStats, String> {
+    let mut sent = HashMap::new();
+    let mut codes = Vec::with_capacity(frames);
+    let mut param = String::new();
+    for _ in 0..frames {
+        let data: Vec<u8> = (0..msg_bytes).map(|_| rng.next_u64() as u8).collect();
+        let (p, bits) = channel::encode_message(algo, n, &data)?;
+        *sent.entry(data).or_insert(0) += 1;
+        codes.push(bits);
+        param = p;
+    }
+    let mut channel = encode(framing, &codes, sync)?;
+    let (_, clean) = deframe(framing, &channel, usize::MAX, sync)?;
+    let mut stats = FrameSimStats { ber, frames, channel: clean.channel, payload: clean.payload, ..FrameSimStats::default() };
+    stats.bit_errors = add_noise(framing, &mut channel, ber, rng);
+
+    let max_bits = codes.iter().map(String::len).max().unwrap_or(0);
+    let (found, deframed) = deframe(framing, &channel, max_bits, sync)?;
+    stats.delivered = deframed.frames;
+    stats.discarded = deframed.discarded;
+    for f in found.iter().filter_map(|f| f.as_ref().ok()) {
+        let out = frame::process_frame(algo, &param, &f.bits, &DecodeOptions::default());
+        *stats.by_status.entry(out.status.as_str()).or_insert(0) += 1;
+        let Some(payload) = out.payload else { continue };
+        match sent.get_mut(&payload) {
+            Some(left) if *left > 0 => {
+                *left -= 1;
+                stats.intact += 1;
+            }
+            _ => stats.wrong += 1,
+        }
+    }
+    Ok(stats)
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contenido_con_largo_en_bits() {
+        let payload = pack_payload("1011001110");
+        assert_eq!(payload, [0, 0, 0, 10, 0b1011_0011, 0b1000_0000]);
+        assert_eq!(unpack_payload(&payload, 64).unwrap(), "1011001110");
+        assert!(unpack_payload(&payload[..5], 64).unwrap_err().contains("10 bits"));
+        assert!(unpack_payload(&payload, 8).is_err());
+        assert!(unpack_payload(&[0, 0], 64).is_err());
+    }
+
+    #[test]
+    fn sobrecarga_de_cada_entramado() {
+        // Los bytes especiales cuestan el doble en PPP y SLIP (4 cada 256 bytes, más las
+        // 2 banderas); COBS agrega a lo sumo un byte cada 254, sin importar el contenido.
+        let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let bits: String = bytes.iter().map(|b| format!("{:08b}", b)).collect();
+        let payload = pack_payload(&bits);
+        for (framing, channel, max_extra) in [
+            (Framing::Ppp, ppp::encode(&payload), 8 + 2),
+            (Framing::Slip, slip::encode(&payload), 8 + 2),
+            (Framing::Cobs, cobs::encode(&payload), payload.len().div_ceil(254) + 1),
+        ] {
+            // PPP y SLIP abren con un delimitador; COBS solo lo pone al final
+            let offset = if framing == Framing::Cobs { 0 } else { 1 };
+            let (frames, stats) = deframe(framing, &channel, usize::MAX, &sync::SyncConfig::default()).unwrap();
+            assert_eq!(frames, [Ok(Frame { offset, bits: bits.clone() })]);
+            assert_eq!((stats.channel, stats.payload), (channel.len(), payload.len()));
+            assert!(stats.channel - stats.payload <= max_extra, "{:?}: {}", framing, stats);
+            assert!(stats.overhead() < 1.0, "{:?}: {}", framing, stats);
+        }
+    }
+
+    #[test]
+    fn simulacion_de_cada_entramado() {
+        let sync = sync::SyncConfig::default();
+        for framing in ALL {
+            // Sin ruido llegan todos los mensajes; con ruido se pierden algunos, pero el
+            // receptor se resincroniza y recupera la mayoría
+            let stats = simulate(framing, &sync, "HAMMING", 7, 200, 8, 0.0, &mut Rng::new(5)).unwrap();
+            assert_eq!((stats.delivered, stats.intact, stats.wrong), (200, 200, 0), "{:?}: {}", framing, stats);
+            assert!(stats.overhead() > 0.0);
+            let stats = simulate(framing, &sync, "HAMMING", 7, 200, 8, 0.001, &mut Rng::new(5)).unwrap();
+            assert!(stats.bit_errors > 0 && stats.intact < 200, "{:?}: {}", framing, stats);
+            assert!(stats.intact > 150, "{:?}: {}", framing, stats);
+        }
+        // HDLC: una bandera por trama y el relleno; sync: patrón y dos copias del largo
+        let stats = simulate(Framing::Hdlc, &sync, "CRC32", 0, 10, 8, 0.0, &mut Rng::new(1)).unwrap();
+        assert!(stats.channel >= 8 + 10 * (96 + 8), "{}", stats);
+        assert_eq!(stats.payload, 10 * 96);
+        let stats = simulate(Framing::Sync, &sync, "CRC32", 0, 10, 8, 0.0, &mut Rng::new(1)).unwrap();
+        assert_eq!(stats.channel, 10 * (40 + 32 + 96));
+        // Los de bytes: 4 bytes de largo y 12 de código por trama, más los delimitadores
+        let stats = simulate(Framing::Cobs, &sync, "CRC32", 0, 10, 8, 0.0, &mut Rng::new(1)).unwrap();
+        assert_eq!((stats.channel, stats.payload), (10 * (16 + 2), 10 * 16));
+    }
+}
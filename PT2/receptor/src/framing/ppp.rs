@@ -0,0 +1,95 @@
+// Byte stuffing al estilo de PPP asíncrono (RFC 1662): cada trama va entre banderas
+// 0x7E y, dentro, la bandera y el escape se reemplazan por 0x7D seguido del byte con
+// el bit 5 invertido (XOR 0x20):
+//
+//   contenido  01 7E 02 7D 03
+//   canal      7E 01 7D 5E 02 7D 5D 03 7E
+//
+// No se escapan los caracteres de control (ACCM = 0). Lo anterior a la primera
+// bandera es basura, y un escape justo antes de una bandera (7D 7E) aborta la trama.
+
+pub const FLAG: u8 = 0x7E;
+pub const ESCAPE: u8 = 0x7D;
+const XOR: u8 = 0x20;
+
+pub fn decode(frame: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut bytes = frame.iter();
+    while let Some(&b) = bytes.next() {
+        match b {
+            ESCAPE => match bytes.next() {
+                Some(&e) => out.push(e ^ XOR),
+                None => return Err("trama abortada (escape antes de la bandera)".into()),
+            },
+            b => out.push(b),
+        }
+    }
+    Ok(out)
+}
+
+// Lo que hace el emisor con el contenido de una trama.
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![FLAG];
+    for &b in payload {
+        match b {
+            FLAG | ESCAPE => out.extend([ESCAPE, b ^ XOR]),
+            b => out.push(b),
+        }
+    }
+    out.push(FLAG);
+    out
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framing::sync::SyncConfig;
+    use crate::framing::{deframe, pack_payload, Framing};
+
+    #[test]
+    fn escape_de_bandera_y_escape() {
+        let channel = encode(&[0x01, 0x7E, 0x02, 0x7D, 0x03]);
+        assert_eq!(channel, [0x7E, 0x01, 0x7D, 0x5E, 0x02, 0x7D, 0x5D, 0x03, 0x7E]);
+        assert_eq!(decode(&channel[1..channel.len() - 1]).unwrap(), [0x01, 0x7E, 0x02, 0x7D, 0x03]);
+        assert!(decode(&[0x01, ESCAPE]).is_err());
+    }
+
+    #[test]
+    fn banderas_dañadas() {
+        // 126 bits: el largo (00 00 00 7E) necesita escape
+        let bits = ["1".repeat(126), "0".repeat(126), "10".repeat(63)];
+        let mut channel = vec![0x55, 0x13]; // basura antes de la primera bandera
+        for b in &bits {
+            channel.extend(encode(&pack_payload(b)));
+        }
+        let (frames, stats) = deframe(Framing::Ppp, &channel, 1024, &SyncConfig::default()).unwrap();
+        let ok: Vec<_> = frames.iter().filter_map(|f| f.as_ref().ok()).map(|f| f.bits.clone()).collect();
+        assert_eq!(ok, bits);
+        assert_eq!(stats.hunting, 2);
+
+        // Se pierde la bandera de cierre de la 1.ª trama: la cierra la de apertura de la
+        // 2.ª, con un byte de más, y el largo no coincide
+        let close = 2 + encode(&pack_payload(&bits[0])).len() - 1;
+        let mut bad = channel.clone();
+        bad[close] = 0x00;
+        let (frames, stats) = deframe(Framing::Ppp, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert!(matches!(&frames[0], Err((3, e)) if e.contains("largo")));
+        assert_eq!(frames[1..].iter().filter_map(|f| f.as_ref().ok()).map(|f| &f.bits).collect::<Vec<_>>(), [&bits[1], &bits[2]]);
+        assert_eq!((stats.frames, stats.discarded), (2, 1));
+
+        // Un byte que se convierte en bandera parte la 2.ª trama en dos
+        let mut bad = channel.clone();
+        bad[close + 8] = FLAG;
+        let (frames, _) = deframe(Framing::Ppp, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert_eq!(frames.iter().filter(|f| f.is_err()).count(), 2);
+        assert_eq!(frames.last().unwrap().as_ref().unwrap().bits, bits[2]);
+
+        // Escape antes de la bandera: la trama se aborta
+        let mut bad = channel.clone();
+        bad[close - 1] = ESCAPE;
+        let (frames, _) = deframe(Framing::Ppp, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert!(matches!(&frames[0], Err((_, e)) if e.contains("abortada")));
+    }
+}
@@ -0,0 +1,94 @@
+// SLIP (RFC 1055): cada trama termina con END (0xC0). Dentro, END y ESC (0xDB) se
+// reemplazan por ESC ESC_END (DB DC) y ESC ESC_ESC (DB DD). El emisor manda además un
+// END al principio, para cortar el ruido que haya quedado en la línea; las tramas
+// vacías entre dos END se ignoran.
+//
+//   contenido  01 C0 02 DB
+//   canal      C0 01 DB DC 02 DB DD C0
+
+pub const END: u8 = 0xC0;
+pub const ESC: u8 = 0xDB;
+pub const ESC_END: u8 = 0xDC;
+pub const ESC_ESC: u8 = 0xDD;
+
+pub fn decode(frame: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(frame.len());
+    let mut bytes = frame.iter();
+    while let Some(&b) = bytes.next() {
+        match b {
+            ESC => match bytes.next() {
+                Some(&ESC_END) => out.push(END),
+                Some(&ESC_ESC) => out.push(ESC),
+                Some(other) => return Err(format!("escape SLIP inválido: DB {:02X}", other)),
+                None => return Err("escape SLIP al final de la trama".into()),
+            },
+            b => out.push(b),
+        }
+    }
+    Ok(out)
+}
+
+// Lo que hace el emisor con el contenido de una trama.
+pub fn encode(payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![END];
+    for &b in payload {
+        match b {
+            END => out.extend([ESC, ESC_END]),
+            ESC => out.extend([ESC, ESC_ESC]),
+            b => out.push(b),
+        }
+    }
+    out.push(END);
+    out
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framing::sync::SyncConfig;
+    use crate::framing::{deframe, pack_payload, Framing};
+
+    #[test]
+    fn escape_de_end_y_esc() {
+        let channel = encode(&[0x01, END, 0x02, ESC]);
+        assert_eq!(channel, [0xC0, 0x01, 0xDB, 0xDC, 0x02, 0xDB, 0xDD, 0xC0]);
+        assert_eq!(decode(&channel[1..channel.len() - 1]).unwrap(), [0x01, END, 0x02, ESC]);
+        assert!(decode(&[ESC, 0x01]).is_err());
+        assert!(decode(&[0x01, ESC]).is_err());
+    }
+
+    #[test]
+    fn delimitadores_dañados() {
+        // 0xC0 y 0xDB en los datos de la 1.ª y 3.ª trama
+        let bits = ["11000000".repeat(4), "0".repeat(20), "11011011".repeat(3)];
+        let frames_bytes: Vec<Vec<u8>> = bits.iter().map(|b| encode(&pack_payload(b))).collect();
+        // Sin el END inicial en la 1.ª trama: lo anterior al primer END ya es una trama
+        let channel = [&frames_bytes[0][1..], &frames_bytes[1], &frames_bytes[2]].concat();
+        let (frames, stats) = deframe(Framing::Slip, &channel, 1024, &SyncConfig::default()).unwrap();
+        assert_eq!(frames.iter().map(|f| f.as_ref().unwrap().bits.as_str()).collect::<Vec<_>>(), bits);
+        assert_eq!((stats.hunting, stats.discarded), (0, 0));
+        assert_eq!(stats.delimiters, 5);
+
+        // END de cierre de la 1.ª trama dañado: se une con la 2.ª (con su END inicial) y
+        // el largo no coincide; la 3.ª llega bien
+        let close = frames_bytes[0].len() - 2;
+        let mut bad = channel.clone();
+        bad[close] = 0x00;
+        let (frames, _) = deframe(Framing::Slip, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert!(frames[0].is_err());
+        assert_eq!(frames.iter().filter_map(|f| f.as_ref().ok()).map(|f| &f.bits).collect::<Vec<_>>(), [&bits[1], &bits[2]]);
+
+        // ESC_END dañado: escape inválido, solo se pierde esa trama
+        let mut bad = channel.clone();
+        bad[5] = 0x00;
+        let (frames, _) = deframe(Framing::Slip, &bad, 1024, &SyncConfig::default()).unwrap();
+        assert!(matches!(&frames[0], Err((0, e)) if e.contains("escape")));
+        assert_eq!(frames.iter().filter(|f| f.is_ok()).count(), 2);
+
+        // Canal cortado antes del último END
+        let (frames, _) = deframe(Framing::Slip, &channel[..channel.len() - 1], 1024, &SyncConfig::default()).unwrap();
+        assert!(matches!(frames.last(), Some(Err((_, e))) if e.contains("cierre")));
+    }
+}
@@ -0,0 +1,323 @@
+// Adquisición de tramas en un flujo de bits continuo y ruidoso. Cada trama empieza
+// con un preámbulo y una palabra de sincronización, sigue el largo del código en 16
+// bits (MSB primero), el mismo largo complementado y después los bits del código:
+//
+//   canal   ...basura... | 10101010 | 00011010110011111111110000011101 | largo | !largo | código
+//                          preámbulo   palabra (ASM de CCSDS, 0x1ACFFC1D)   16      16
+//
+// El receptor desliza una ventana del largo de preámbulo + palabra y se engancha en la
+// primera posición cuya distancia de Hamming al patrón no supera la tolerancia, así un
+// patrón con algunos bits cambiados por el canal no hace perder la trama. Enganchado,
+// lee el largo, extrae la trama y vuelve a buscar justo después. Sin la copia
+// complementada, un solo bit cambiado en el largo haría saltear las tramas siguientes
+// (un error en el bit alto salta 32768 bits). Si las copias no coinciden, o el largo
+// es 0 o mayor que el máximo, el enganche se descarta y la búsqueda sigue un bit más
+// adelante: así se descartan también casi todos los enganches falsos, en datos que
+// por azar se parecen al patrón. La simulación (`--sync-sim`) cuenta los enganches
+// falsos y las tramas perdidas.
+
+use std::fmt;
+
+use super::{bits_to_string, DeframeStats, Deframed, Frame};
+use crate::channel::{self, Rng};
+
+pub const LENGTH_BITS: usize = 16; // cada copia del largo
+pub const DEFAULT_PREAMBLE: &str = "10101010";
+pub const DEFAULT_WORD: &str = "00011010110011111111110000011101";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncConfig {
+    pub preamble: Vec<u8>,
+    pub word: Vec<u8>,
+    pub tolerance: usize, // bits distintos aceptados en preámbulo + palabra
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        SyncConfig { preamble: parse_pattern(DEFAULT_PREAMBLE).unwrap(), word: parse_pattern(DEFAULT_WORD).unwrap(), tolerance: 2 }
+    }
+}
+
+impl SyncConfig {
+    pub fn pattern(&self) -> Vec<u8> {
+        [&self.preamble[..], &self.word[..]].concat()
+    }
+
+    // Con la mitad o más de los bits distintos el patrón coincidiría con cualquier cosa.
+    pub fn validate(&self) -> Result<(), String> {
+        let len = self.preamble.len() + self.word.len();
+        if self.word.is_empty() {
+            return Err("la palabra de sincronización no puede estar vacía".into());
+        }
+        if 2 * self.tolerance >= len {
+            return Err(format!("tolerancia de {} bits para un patrón de {} bits: debe ser menor que la mitad", self.tolerance, len));
+        }
+        Ok(())
+    }
+}
+
+// Patrón en binario ("1010...") o en hexadecimal ("0x1ACFFC1D", 4 bits por dígito).
+pub fn parse_pattern(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        return hex
+            .chars()
+            .map(|c| c.to_digit(16).ok_or_else(|| format!("dígito hexadecimal inválido {:?} en {}", c, s)))
+            .map(|d| d.map(|d| (0..4).rev().map(|i| ((d >> i) & 1) as u8).collect::<Vec<u8>>()))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|v| v.concat());
+    }
+    s.chars()
+        .map(|c| match c {
+            '0' => Ok(0),
+            '1' => Ok(1),
+            other => Err(format!("carácter no binario {:?} en el patrón {}", other, s)),
+        })
+        .collect()
+}
+
+fn distance(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).filter(|(x, y)| x != y).count()
+}
+
+// Busca, engancha y extrae. La posición de cada trama (buena o descartada) es la del
+// primer bit de su código.
+pub fn deframe(bits: &[u8], cfg: &SyncConfig, max_bits: usize) -> (Vec<Deframed>, DeframeStats) {
+    let mut frames = Vec::new();
+    let mut stats = DeframeStats { channel: bits.len(), ..DeframeStats::default() };
+    let pattern = cfg.pattern();
+    let header = pattern.len() + 2 * LENGTH_BITS;
+    let mut i = 0;
+    let mut hunt_from = 0;
+
+    while i + pattern.len() <= bits.len() {
+        if distance(&bits[i..i + pattern.len()], &pattern) > cfg.tolerance {
+            i += 1;
+            continue;
+        }
+        stats.delimiters += 1;
+        stats.hunting += i - hunt_from;
+        let start = i + header;
+        if start > bits.len() {
+            frames.push(Err((start, "trama cortada: falta el largo".to_string())));
+            stats.discarded += 1;
+            hunt_from = bits.len();
+            break;
+        }
+        let field = |from: usize| bits[from..from + LENGTH_BITS].iter().fold(0usize, |acc, &b| (acc << 1) | b as usize);
+        let len = field(start - 2 * LENGTH_BITS);
+        let reject = if len ^ field(start - LENGTH_BITS) != (1 << LENGTH_BITS) - 1 {
+            Some("largo dañado (no coincide con su complemento)".to_string())
+        } else if len == 0 {
+            Some("largo 0".to_string())
+        } else if len > max_bits {
+            Some(format!("trama de {} bits (máximo {})", len, max_bits))
+        } else {
+            None
+        };
+        if let Some(e) = reject {
+            // Largo dañado o enganche falso: se sigue buscando desde el bit siguiente
+            frames.push(Err((start, e)));
+            stats.discarded += 1;
+            hunt_from = i;
+            i += 1;
+            continue;
+        }
+        if start + len > bits.len() {
+            frames.push(Err((start, "trama cortada al final del canal".to_string())));
+            stats.discarded += 1;
+            hunt_from = bits.len();
+            break;
+        }
+        stats.frames += 1;
+        stats.payload += len;
+        frames.push(Ok(Frame { offset: start, bits: bits_to_string(&bits[start..start + len]) }));
+        i = start + len;
+        hunt_from = i;
+    }
+    stats.hunting += bits.len().saturating_sub(hunt_from);
+    (frames, stats)
+}
+
+// Lo que hace el emisor: preámbulo, palabra, largo, largo complementado y código. El
+// largo tiene que caber en LENGTH_BITS y no ser 0, que el receptor descarta.
+pub fn encode_frame(code: &[u8], cfg: &SyncConfig) -> Result<Vec<u8>, String> {
+    if code.is_empty() || code.len() >= 1 << LENGTH_BITS {
+        return Err(format!("trama de {} bits: con sync el largo va de 1 a {}", code.len(), (1 << LENGTH_BITS) - 1));
+    }
+    let mut out = cfg.pattern();
+    out.extend((0..LENGTH_BITS).rev().map(|i| ((code.len() >> i) & 1) as u8));
+    out.extend((0..LENGTH_BITS).rev().map(|i| (!(code.len() >> i) & 1) as u8));
+    out.extend_from_slice(code);
+    Ok(out)
+}
+
+// Resultado de la simulación para una BER.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SimStats {
+    pub ber: f64,
+    pub frames: usize,      // tramas enviadas
+    pub locked: usize,      // tramas verdaderas en las que el receptor se enganchó
+    pub delivered: usize,   // de esas, entregadas (un largo dañado engancha pero no entrega)
+    pub intact: usize,      // de las entregadas, con el código sin errores
+    pub missed: usize,      // tramas verdaderas sin enganche (patrón dañado, o salteadas)
+    pub false_locks: usize, // enganches fuera de una trama verdadera
+    pub false_frames: usize, // enganches falsos que además entregaron una trama
+    pub bit_errors: usize,  // bits cambiados por el canal
+}
+
+impl fmt::Display for SimStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pct = |n: usize| if self.frames == 0 { 0.0 } else { 100.0 * n as f64 / self.frames as f64 };
+        write!(
+            f,
+            "BER {}: {} tramas, {} enganchadas ({:.2}%), {} entregadas, {} intactas, {} perdidas ({:.2}%), \
+             {} enganches falsos ({} entregaron una trama), {} bits cambiados",
+            self.ber, self.frames, self.locked, pct(self.locked), self.delivered, self.intact, self.missed, pct(self.missed),
+            self.false_locks, self.false_frames, self.bit_errors
+        )
+    }
+}
+
+// Envía `frames` tramas aleatorias de `frame_bits` bits (también el máximo del
+// receptor), separadas por basura aleatoria (0 a 63 bits) para que haya dónde
+// engancharse mal, pasa el canal por el ruido y compara los enganches con las
+// posiciones verdaderas.
+pub fn simulate(cfg: &SyncConfig, frames: usize, frame_bits: usize, ber: f64, rng: &mut Rng) -> Result<SimStats, String> {
+    let mut channel = Vec::new();
+    let mut sent = Vec::with_capacity(frames); // (posición del código, código)
+    for _ in 0..frames {
+        let gap = rng.below(64);
+        channel.extend(rng.bits(gap));
+        let code = rng.bits(frame_bits);
+        sent.push((channel.len() + cfg.pattern().len() + 2 * LENGTH_BITS, bits_to_string(&code)));
+        channel.extend(encode_frame(&code, cfg)?);
+    }
+    let bit_errors = channel::flip_bits(&mut channel, ber, rng);
+    let (found, _) = deframe(&channel, cfg, frame_bits);
+
+    let mut stats = SimStats { ber, frames, bit_errors, ..SimStats::default() };
+    for f in &found {
+        let (offset, bits) = match f {
+            Ok(fr) => (fr.offset, Some(&fr.bits)),
+            Err((offset, _)) => (*offset, None),
+        };
+        match sent.binary_search_by_key(&offset, |(o, _)| *o) {
+            Ok(k) => {
+                stats.locked += 1;
+                stats.delivered += bits.is_some() as usize;
+                if bits == Some(&sent[k].1) {
+                    stats.intact += 1;
+                }
+            }
+            Err(_) => {
+                stats.false_locks += 1;
+                if bits.is_some() {
+                    stats.false_frames += 1;
+                }
+            }
+        }
+    }
+    stats.missed = frames - stats.locked;
+    Ok(stats)
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel_of(codes: &[&str], cfg: &SyncConfig) -> Vec<u8> {
+        let mut out = parse_pattern("0110").unwrap(); // basura antes de la primera trama
+        for c in codes {
+            out.extend(encode_frame(&parse_pattern(c).unwrap(), cfg).unwrap());
+        }
+        out
+    }
+
+    #[test]
+    fn patrones_binarios_y_hexadecimales() {
+        assert_eq!(parse_pattern("0x1ACFFC1D").unwrap(), parse_pattern(DEFAULT_WORD).unwrap());
+        assert_eq!(parse_pattern("0xA").unwrap(), [1, 0, 1, 0]);
+        assert!(parse_pattern("0x1G").is_err());
+        assert!(parse_pattern("0102").is_err());
+
+        let mut cfg = SyncConfig::default();
+        assert!(cfg.validate().is_ok());
+        cfg.tolerance = 20;
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn engancha_con_errores_en_el_patron() {
+        let cfg = SyncConfig::default();
+        let codes = ["1011001", "1111111111110000", "0"];
+        let mut bits = channel_of(&codes, &cfg);
+        // Dos bits cambiados en el preámbulo y la palabra de la segunda trama: se tolera
+        let second = 4 + 72 + 7;
+        bits[second + 1] ^= 1;
+        bits[second + 20] ^= 1;
+        let (res, stats) = deframe(&bits, &cfg, 1024);
+        let got: Vec<_> = res.iter().map(|r| r.as_ref().unwrap().bits.as_str()).collect();
+        assert_eq!(got, codes);
+        assert_eq!(res[0].as_ref().unwrap().offset, 4 + 72);
+        assert_eq!((stats.frames, stats.delimiters, stats.hunting, stats.discarded), (3, 3, 4, 0));
+        assert_eq!(stats.payload, 7 + 16 + 1);
+
+        // Con tolerancia 0 la segunda se pierde y la búsqueda sigue hasta la tercera
+        let strict = SyncConfig { tolerance: 0, ..cfg };
+        let (res, stats) = deframe(&bits, &strict, 1024);
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[1].as_ref().unwrap().bits, "0");
+        assert_eq!(stats.hunting, 4 + 72 + 16);
+    }
+
+    #[test]
+    fn largos_invalidos_y_canal_cortado() {
+        let cfg = SyncConfig::default();
+        let bits = channel_of(&["10110011", "0101"], &cfg);
+        // Largo mayor que el máximo: se descarta y se recupera la trama siguiente
+        let (res, stats) = deframe(&bits, &cfg, 4);
+        assert!(matches!(&res[0], Err((76, e)) if e.contains("máximo")));
+        assert_eq!(res[1].as_ref().unwrap().bits, "0101");
+        assert_eq!(stats.discarded, 1);
+
+        let (res, _) = deframe(&bits[..bits.len() - 2], &cfg, 1024);
+        assert!(matches!(res.last(), Some(Err((_, e))) if e.contains("final")));
+        let (res, _) = deframe(&bits[..4 + 40 + 8], &cfg, 1024);
+        assert!(matches!(&res[..], [Err((_, e))] if e.contains("largo")));
+
+        // Un bit cambiado en el largo: se pierde esa trama, no las siguientes
+        let mut bits = channel_of(&["10110011", "0101", "111"], &cfg);
+        bits[4 + 40 + 12] ^= 1;
+        let (res, stats) = deframe(&bits, &cfg, 1024);
+        assert!(matches!(&res[0], Err((76, e)) if e.contains("complemento")));
+        let got: Vec<_> = res.iter().filter_map(|r| r.as_ref().ok()).map(|f| f.bits.as_str()).collect();
+        assert_eq!(got, ["0101", "111"]);
+        assert_eq!((stats.frames, stats.discarded), (2, 1));
+
+        // El emisor no puede mandar un largo que no entra en 16 bits (ni 0)
+        assert_eq!(encode_frame(&vec![1; 65535], &cfg).unwrap().len(), 40 + 32 + 65535);
+        assert!(encode_frame(&vec![1; 65536], &cfg).is_err());
+        assert!(encode_frame(&[], &cfg).is_err());
+    }
+
+    #[test]
+    fn simulacion_cuenta_perdidas_y_enganches_falsos() {
+        let cfg = SyncConfig::default();
+        let clean = simulate(&cfg, 200, 112, 0.0, &mut Rng::new(1)).unwrap();
+        assert_eq!((clean.locked, clean.delivered, clean.intact, clean.missed), (200, 200, 200, 0));
+
+        let noisy = simulate(&cfg, 500, 112, 0.05, &mut Rng::new(1)).unwrap();
+        assert_eq!(noisy.locked + noisy.missed, 500);
+        assert!(noisy.missed > 0 && noisy.delivered < noisy.locked && noisy.intact < noisy.delivered, "{}", noisy);
+
+        // Una palabra corta con tolerancia se engancha en la basura entre tramas, pero el
+        // largo y su complemento descartan esos enganches sin perder tramas verdaderas
+        let weak = SyncConfig { preamble: Vec::new(), word: parse_pattern("1011").unwrap(), tolerance: 1 };
+        let stats = simulate(&weak, 200, 112, 0.0, &mut Rng::new(1)).unwrap();
+        assert!(stats.false_locks > 0, "{}", stats);
+        assert_eq!((stats.intact, stats.missed, stats.false_frames), (200, 0, 0));
+    }
+}
@@ -0,0 +1,62 @@
+// 4B/5B (FDDI, 100BASE-X): cada grupo de 4 bits viaja como uno de 16 símbolos de 5
+// bits elegidos para que nunca haya más de tres 0 seguidos (en la práctica se envía
+// con NRZI, que convierte los 1 en transiciones). De los 32 símbolos posibles, los
+// otros 16 son de control o inválidos: recibir uno es una violación y el grupo se
+// entrega como 0000. Un nivel cambiado puede convertir un símbolo en otro válido y
+// dañar hasta 4 bits de datos sin que se note.
+
+use super::Decoded;
+
+const CODES: [u8; 16] = [
+    0b11110, 0b01001, 0b10100, 0b10101, 0b01010, 0b01011, 0b01110, 0b01111,
+    0b10010, 0b10011, 0b10110, 0b10111, 0b11010, 0b11011, 0b11100, 0b11101,
+];
+
+// Grupos de 4 bits MSB primero; el último se completa con ceros.
+pub fn encode(bits: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bits.len().div_ceil(4) * 5);
+    for chunk in bits.chunks(4) {
+        let nibble = (0..4).fold(0u8, |acc, i| (acc << 1) | chunk.get(i).copied().unwrap_or(0));
+        let code = CODES[nibble as usize];
+        out.extend((0..5).rev().map(|i| (code >> i) & 1));
+    }
+    out
+}
+
+pub fn decode(signal: &[u8]) -> Decoded {
+    let mut out = Decoded::default();
+    for (i, chunk) in signal.chunks(5).enumerate() {
+        let code = chunk.iter().fold(0u8, |acc, &b| (acc << 1) | b);
+        let nibble = match CODES.iter().position(|&c| c == code) {
+            Some(n) if chunk.len() == 5 => n as u8,
+            _ => {
+                out.violations.push(i);
+                0
+            }
+        };
+        out.bits.extend((0..4).rev().map(|i| (nibble >> i) & 1));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabla_y_violaciones() {
+        assert_eq!(encode(&[0, 0, 0, 0, 1, 1, 1, 1]), [1, 1, 1, 1, 0, 1, 1, 1, 0, 1]);
+        // Nunca más de tres 0 seguidos, tampoco entre dos símbolos
+        for a in CODES {
+            for b in CODES {
+                let pair = format!("{:05b}{:05b}", a, b);
+                assert!(!pair.contains("0000"), "{}", pair);
+            }
+        }
+        // 00000 es inválido; 11111 (IDLE) es de control
+        let d = decode(&[0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 0, 1, 0, 1]);
+        assert_eq!(d.violations, [0, 1]);
+        assert_eq!(d.bits[8..], [0, 0, 1, 1]);
+        assert_eq!(decode(&[1, 1, 1]).violations, [0]);
+    }
+}
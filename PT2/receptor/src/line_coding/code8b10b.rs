@@ -0,0 +1,199 @@
+// 8b/10b (Widmer y Franaszek; Fibre Channel, PCIe 1/2, SATA): cada byte viaja como
+// un símbolo de 10 bits, 6 para los 5 bits bajos (EDCBA) y 4 para los 3 altos (HGF).
+// Los sub-bloques tienen tantos 1 como 0, o uno de más o de menos; para estos hay dos
+// variantes y el emisor elige según la disparidad acumulada (RD), que así se mantiene
+// en ±1 y la línea queda balanceada.
+//
+// El receptor sigue la RD y detecta dos tipos de error: sub-bloques que no existen
+// (violaciones) y sub-bloques válidos con la variante de la otra RD (errores de
+// disparidad). Un nivel cambiado no siempre se detecta en su símbolo: puede dar la
+// variante correcta de otro valor y la RD equivocada aparece recién en un símbolo
+// posterior, que se marca aunque esté sano. Solo se codifican caracteres de datos
+// (D.x.y); los de control (K28.5, etc.) se reciben como violaciones.
+
+use super::Decoded;
+
+// abcdei (a primero) para RD-, y para RD+
+const CODES_6B: [(u8, u8); 32] = [
+    (0b100111, 0b011000), (0b011101, 0b100010), (0b101101, 0b010010), (0b110001, 0b110001),
+    (0b110101, 0b001010), (0b101001, 0b101001), (0b011001, 0b011001), (0b111000, 0b000111),
+    (0b111001, 0b000110), (0b100101, 0b100101), (0b010101, 0b010101), (0b110100, 0b110100),
+    (0b001101, 0b001101), (0b101100, 0b101100), (0b011100, 0b011100), (0b010111, 0b101000),
+    (0b011011, 0b100100), (0b100011, 0b100011), (0b010011, 0b010011), (0b110010, 0b110010),
+    (0b001011, 0b001011), (0b101010, 0b101010), (0b011010, 0b011010), (0b111010, 0b000101),
+    (0b110011, 0b001100), (0b100110, 0b100110), (0b010110, 0b010110), (0b110110, 0b001001),
+    (0b001110, 0b001110), (0b101110, 0b010001), (0b011110, 0b100001), (0b101011, 0b010100),
+];
+
+// fghj para RD-, y para RD+
+const CODES_4B: [(u8, u8); 8] = [
+    (0b1011, 0b0100), (0b1001, 0b1001), (0b0101, 0b0101), (0b1100, 0b0011),
+    (0b1101, 0b0010), (0b1010, 0b1010), (0b0110, 0b0110), (0b1110, 0b0001),
+];
+
+// D.x.A7: reemplaza a D.x.P7 donde esta formaría cinco bits iguales con el sub-bloque
+// de 6 bits (x = 17, 18, 20 con RD-; x = 11, 13, 14 con RD+).
+const CODE_A7: (u8, u8) = (0b0111, 0b1000);
+
+fn uses_a7(x: usize, rd_positive: bool) -> bool {
+    if rd_positive {
+        matches!(x, 11 | 13 | 14)
+    } else {
+        matches!(x, 17 | 18 | 20)
+    }
+}
+
+fn variant(pair: (u8, u8), rd_positive: bool) -> u8 {
+    if rd_positive { pair.1 } else { pair.0 }
+}
+
+// La RD cambia solo con sub-bloques desbalanceados.
+fn next_rd(rd_positive: bool, code: u8, len: u32) -> bool {
+    let ones = code.count_ones();
+    match (2 * ones).cmp(&len) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => rd_positive,
+    }
+}
+
+fn push_bits(out: &mut Vec<u8>, code: u8, len: u32) {
+    out.extend((0..len).rev().map(|i| (code >> i) & 1));
+}
+
+// Bytes MSB primero; el último se completa con ceros. La RD arranca negativa.
+pub fn encode(bits: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bits.len().div_ceil(8) * 10);
+    let mut rd = false;
+    for chunk in bits.chunks(8) {
+        let byte = (0..8).fold(0usize, |acc, i| (acc << 1) | chunk.get(i).copied().unwrap_or(0) as usize);
+        let (x, y) = (byte & 0x1F, byte >> 5);
+        let c6 = variant(CODES_6B[x], rd);
+        rd = next_rd(rd, c6, 6);
+        let c4 = if y == 7 && uses_a7(x, rd) { variant(CODE_A7, rd) } else { variant(CODES_4B[y], rd) };
+        rd = next_rd(rd, c4, 4);
+        push_bits(&mut out, c6, 6);
+        push_bits(&mut out, c4, 4);
+    }
+    out
+}
+
+// Sub-bloque recibido: valor, y si vino con la variante de la otra RD.
+fn lookup(code: u8, rd_positive: bool, table: &[(u8, u8)], extra: Option<((u8, u8), usize)>) -> Option<(usize, bool)> {
+    let entries = table.iter().copied().enumerate().chain(extra.map(|(pair, v)| (v, pair)));
+    let mut wrong_rd = None;
+    for (v, pair) in entries {
+        if variant(pair, rd_positive) == code {
+            return Some((v, false));
+        }
+        if variant(pair, !rd_positive) == code {
+            wrong_rd = Some((v, true));
+        }
+    }
+    wrong_rd
+}
+
+pub fn decode(signal: &[u8]) -> Decoded {
+    let mut out = Decoded::default();
+    let mut rd = false;
+    for (i, sym) in signal.chunks(10).enumerate() {
+        if sym.len() < 10 {
+            out.violations.push(i);
+            out.bits.extend([0; 8]);
+            break;
+        }
+        let c6 = sym[..6].iter().fold(0u8, |acc, &b| (acc << 1) | b);
+        let c4 = sym[6..].iter().fold(0u8, |acc, &b| (acc << 1) | b);
+        let (mut invalid, mut disparity) = (false, false);
+
+        let x = match lookup(c6, rd, &CODES_6B, None) {
+            Some((x, wrong)) => {
+                disparity |= wrong;
+                x
+            }
+            None => {
+                invalid = true;
+                0
+            }
+        };
+        rd = next_rd(rd, c6, 6);
+        let y = match lookup(c4, rd, &CODES_4B, Some((CODE_A7, 7))) {
+            Some((y, wrong)) => {
+                disparity |= wrong;
+                y
+            }
+            None => {
+                invalid = true;
+                0
+            }
+        };
+        rd = next_rd(rd, c4, 4);
+
+        if invalid {
+            out.violations.push(i);
+        } else if disparity {
+            out.disparity_errors.push(i);
+        }
+        push_bits(&mut out.bits, ((y << 5) | x) as u8, 8);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn byte_bits(bytes: &[u8]) -> Vec<u8> {
+        bytes.iter().flat_map(|b| (0..8).rev().map(move |i| (b >> i) & 1)).collect()
+    }
+
+    fn symbols(signal: &[u8]) -> Vec<String> {
+        signal.chunks(10).map(|s| s.iter().map(|b| (b'0' + b) as char).collect()).collect()
+    }
+
+    #[test]
+    fn simbolos_conocidos() {
+        // D.0.3 con RD- (la deja positiva), D.0.0 con RD+, D.21.5 (neutro) y D.11.7 con
+        // RD+, que usa A7
+        let signal = encode(&byte_bits(&[0x60, 0x00, 0xB5, 0xEB]));
+        assert_eq!(symbols(&signal), ["1001110011", "0110001011", "1010101010", "1101001000"]);
+        assert_eq!(symbols(&encode(&byte_bits(&[0x00]))), ["1001110100"]);
+        // K28.5 es un carácter de control: no es un dato
+        let k28_5: Vec<u8> = "0011111010".bytes().map(|c| c - b'0').collect();
+        assert_eq!(decode(&k28_5).violations, [0]);
+    }
+
+    #[test]
+    fn disparidad_acotada_y_sin_rachas_largas() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let signal = encode(&byte_bits(&bytes));
+        let mut sum: i32 = -1;
+        for (i, sub) in signal.chunks(10).flat_map(|s| [&s[..6], &s[6..]]).enumerate() {
+            sum += sub.iter().map(|&b| if b == 1 { 1 } else { -1 }).sum::<i32>();
+            assert!(sum == 1 || sum == -1, "sub-bloque {}: disparidad {}", i, sum);
+        }
+        let text: String = signal.iter().map(|b| (b'0' + b) as char).collect();
+        assert!(!text.contains("000000") && !text.contains("111111"));
+        let d = decode(&signal);
+        assert_eq!(d.bits, byte_bits(&bytes));
+        assert!(d.violations.is_empty() && d.disparity_errors.is_empty());
+    }
+
+    #[test]
+    fn errores_de_disparidad() {
+        // D.3.0 es 110001 1011: con el sub-bloque cambiado a 110101 (D.4 con RD-) la RD
+        // pasa a positiva y el 1011 que sigue es la variante de RD-
+        let mut signal = encode(&byte_bits(&[0x03, 0x00]));
+        signal[3] ^= 1;
+        let d = decode(&signal);
+        assert_eq!(d.bits[..8], byte_bits(&[0x04])[..], "el error llega como otro valor válido");
+        assert!(d.violations.is_empty());
+        assert_eq!(d.disparity_errors, [0]);
+
+        let mut signal = encode(&byte_bits(&[0x00]));
+        signal[0] ^= 1; // 000111: D.7 con RD+, recibido con RD-
+        assert_eq!(decode(&signal).disparity_errors, [0]);
+        signal[2] ^= 1; // 001111 no existe en datos (es K.28)
+        assert_eq!(decode(&signal).violations, [0]);
+    }
+}
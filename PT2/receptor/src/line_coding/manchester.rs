@@ -0,0 +1,101 @@
+// Manchester: dos niveles por bit con una transición a mitad de bit, que lleva el
+// reloj. Las convenciones difieren en el sentido: IEEE 802.3 manda un 0 como
+// alto→bajo (10) y un 1 como bajo→alto (01); la de G. E. Thomas es la inversa. Un
+// par sin transición (00 u 11) es una violación: el bit se toma de la segunda mitad
+// (IEEE) o de la primera (Thomas), así que la mitad de esos errores igual se aciertan.
+//
+// Manchester diferencial también transiciona siempre a mitad de bit, y un 0 agrega
+// una transición al comienzo del bit (un 1 no). El bit depende de la mitad anterior,
+// como en NRZI, y un nivel cambiado entre dos bits puede dañar los dos.
+
+use super::Decoded;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Convention {
+    Ieee,
+    Thomas,
+}
+
+pub fn encode(bits: &[u8], conv: Convention) -> Vec<u8> {
+    let flip = (conv == Convention::Thomas) as u8;
+    bits.iter().flat_map(|&b| [(b ^ 1) ^ flip, b ^ flip]).collect()
+}
+
+pub fn decode(signal: &[u8], conv: Convention) -> Decoded {
+    let mut out = Decoded::default();
+    for (i, pair) in signal.chunks(2).enumerate() {
+        let [first, second] = *pair else {
+            out.violations.push(i); // media celda al final
+            out.bits.push(0);
+            break;
+        };
+        if first == second {
+            out.violations.push(i);
+        }
+        out.bits.push(match conv {
+            Convention::Ieee => second,
+            Convention::Thomas => first,
+        });
+    }
+    out
+}
+
+pub fn encode_differential(bits: &[u8]) -> Vec<u8> {
+    let mut level = 0; // nivel de la segunda mitad del bit anterior
+    let mut out = Vec::with_capacity(2 * bits.len());
+    for &b in bits {
+        let first = if b == 0 { level ^ 1 } else { level };
+        level = first ^ 1;
+        out.extend([first, level]);
+    }
+    out
+}
+
+pub fn decode_differential(signal: &[u8]) -> Decoded {
+    let mut out = Decoded::default();
+    let mut prev = 0;
+    for (i, pair) in signal.chunks(2).enumerate() {
+        let [first, second] = *pair else {
+            out.violations.push(i);
+            out.bits.push(0);
+            break;
+        };
+        if first == second {
+            out.violations.push(i);
+        }
+        out.bits.push((first == prev) as u8);
+        prev = second;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convenciones_ieee_y_thomas() {
+        assert_eq!(encode(&[0, 1, 1], Convention::Ieee), [1, 0, 0, 1, 0, 1]);
+        assert_eq!(encode(&[0, 1, 1], Convention::Thomas), [0, 1, 1, 0, 1, 0]);
+        let mut bad = encode(&[0, 1, 1], Convention::Ieee);
+        bad[2] ^= 1; // 01 -> 11: se detecta y la segunda mitad igual da el 1
+        let d = decode(&bad, Convention::Ieee);
+        assert_eq!((d.bits, d.violations), (vec![0, 1, 1], vec![1]));
+        assert_eq!(decode(&[1, 0, 1], Convention::Ieee).violations, [1]);
+    }
+
+    #[test]
+    fn diferencial_independiente_de_la_polaridad() {
+        let bits = [1, 0, 0, 1, 1, 0];
+        let signal = encode_differential(&bits);
+        assert_eq!(signal, [0, 1, 0, 1, 0, 1, 1, 0, 0, 1, 0, 1]);
+        let inverted: Vec<u8> = signal.iter().map(|l| l ^ 1).collect();
+        // Solo el primer bit depende del nivel inicial
+        assert_eq!(decode_differential(&inverted).bits[1..], bits[1..]);
+        let mut bad = signal.clone();
+        bad[3] ^= 1;
+        let d = decode_differential(&bad);
+        assert_eq!(d.violations, [1]);
+        assert_eq!(d.bits, [1, 0, 1, 1, 1, 0], "la segunda mitad dañada arrastra al bit siguiente");
+    }
+}
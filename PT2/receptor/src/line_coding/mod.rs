@@ -0,0 +1,288 @@
+// Codificación de línea: cómo viajan los bits del código (Hamming o CRC-32) como
+// niveles en el medio. Cada código tiene su codificador y su decodificador, y el
+// decodificador no falla: entrega siempre los bits y marca los símbolos imposibles
+// (violaciones) y, en 8b/10b, los errores de disparidad. La señal es un nivel por
+// elemento (0 = bajo, 1 = alto); en Manchester hay dos niveles por bit.
+//
+// Un bit cambiado en la línea no siempre es un bit cambiado en el código: en NRZI y
+// Manchester diferencial el bit depende del nivel anterior y el error se duplica, y en
+// 4B/5B y 8b/10b un símbolo dañado cambia hasta 4 u 8 bits de datos. `--line-sim`
+// mide esa multiplicación y lo que le hace a Hamming y CRC-32, opcionalmente con un
+// aleatorizador (scrambler.rs) entre el código y la línea.
+
+pub mod code4b5b;
+pub mod code8b10b;
+pub mod manchester;
+pub mod nrz;
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::channel::{self, Rng};
+use crate::frame::{self, DecodeOptions, Status};
+use crate::scrambler::Scrambler;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCode {
+    NrzL,             // nivel = bit
+    Nrzi,             // un 1 es una transición, un 0 mantiene el nivel
+    ManchesterIeee,   // 802.3: 0 = alto→bajo, 1 = bajo→alto
+    ManchesterThomas, // G. E. Thomas: 0 = bajo→alto, 1 = alto→bajo
+    DiffManchester,   // transición siempre a mitad de bit; un 0 agrega una al comienzo
+    FourBFiveB,       // 4 bits de datos en 5, nunca más de tres 0 seguidos
+    EightBTenB,       // 8 bits en 10 con disparidad acumulada acotada
+}
+
+pub const ALL: [LineCode; 7] = [
+    LineCode::NrzL,
+    LineCode::Nrzi,
+    LineCode::ManchesterIeee,
+    LineCode::ManchesterThomas,
+    LineCode::DiffManchester,
+    LineCode::FourBFiveB,
+    LineCode::EightBTenB,
+];
+
+impl LineCode {
+    pub fn parse(s: &str) -> Result<LineCode, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "nrz" | "nrz-l" | "nrzl" => Ok(LineCode::NrzL),
+            "nrzi" => Ok(LineCode::Nrzi),
+            "manchester" | "manchester-ieee" => Ok(LineCode::ManchesterIeee),
+            "manchester-thomas" => Ok(LineCode::ManchesterThomas),
+            "diff-manchester" => Ok(LineCode::DiffManchester),
+            "4b5b" => Ok(LineCode::FourBFiveB),
+            "8b10b" => Ok(LineCode::EightBTenB),
+            other => Err(format!(
+                "Codificación de línea desconocida: {} (nrz-l, nrzi, manchester-ieee, manchester-thomas, \
+                 diff-manchester, 4b5b u 8b10b)",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineCode::NrzL => "nrz-l",
+            LineCode::Nrzi => "nrzi",
+            LineCode::ManchesterIeee => "manchester-ieee",
+            LineCode::ManchesterThomas => "manchester-thomas",
+            LineCode::DiffManchester => "diff-manchester",
+            LineCode::FourBFiveB => "4b5b",
+            LineCode::EightBTenB => "8b10b",
+        }
+    }
+
+    // Los códigos de bloque completan con ceros hasta un múltiplo de 4 u 8 bits; el
+    // decodificador devuelve también ese relleno.
+    pub fn encode(&self, bits: &[u8]) -> Vec<u8> {
+        match self {
+            LineCode::NrzL => nrz::encode_nrzl(bits),
+            LineCode::Nrzi => nrz::encode_nrzi(bits),
+            LineCode::ManchesterIeee => manchester::encode(bits, manchester::Convention::Ieee),
+            LineCode::ManchesterThomas => manchester::encode(bits, manchester::Convention::Thomas),
+            LineCode::DiffManchester => manchester::encode_differential(bits),
+            LineCode::FourBFiveB => code4b5b::encode(bits),
+            LineCode::EightBTenB => code8b10b::encode(bits),
+        }
+    }
+
+    pub fn decode(&self, signal: &[u8]) -> Decoded {
+        match self {
+            LineCode::NrzL => nrz::decode_nrzl(signal),
+            LineCode::Nrzi => nrz::decode_nrzi(signal),
+            LineCode::ManchesterIeee => manchester::decode(signal, manchester::Convention::Ieee),
+            LineCode::ManchesterThomas => manchester::decode(signal, manchester::Convention::Thomas),
+            LineCode::DiffManchester => manchester::decode_differential(signal),
+            LineCode::FourBFiveB => code4b5b::decode(signal),
+            LineCode::EightBTenB => code8b10b::decode(signal),
+        }
+    }
+}
+
+// Bits recuperados de la señal. Las posiciones son índices de símbolo: bit en NRZ y
+// Manchester, grupo de 5 en 4B/5B, grupo de 10 en 8b/10b.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub bits: Vec<u8>,
+    pub violations: Vec<usize>,       // símbolos que el código no puede producir
+    pub disparity_errors: Vec<usize>, // 8b/10b: símbolo válido con la disparidad equivocada
+}
+
+// Resultado de la simulación para un código de línea y una BER.
+#[derive(Debug, Default, Clone)]
+pub struct LineSimStats {
+    pub ber: f64,
+    pub frames: usize,
+    pub line_bits: usize,        // niveles enviados por la línea
+    pub line_flips: usize,       // niveles cambiados por el canal
+    pub code_errors: usize,      // bits del código distintos después de decodificar la línea (y desaleatorizar)
+    pub violations: usize,       // símbolos imposibles vistos por el decodificador de línea
+    pub disparity_errors: usize,
+    pub flagged_frames: usize,   // tramas con alguna violación o error de disparidad
+    pub by_status: HashMap<&'static str, usize>,
+    pub wrong: usize,            // tramas entregadas con bytes distintos de los enviados
+}
+
+impl LineSimStats {
+    // Bits del código dañados por cada nivel cambiado en la línea.
+    pub fn multiplication(&self) -> f64 {
+        if self.line_flips == 0 {
+            return 0.0;
+        }
+        self.code_errors as f64 / self.line_flips as f64
+    }
+}
+
+impl fmt::Display for LineSimStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |st: Status| self.by_status.get(st.as_str()).copied().unwrap_or(0);
+        write!(
+            f,
+            "BER {}: {} tramas, {} niveles cambiados -> {} bits del código ({:.2}x), {} violaciones, {} errores de \
+             disparidad ({} tramas marcadas); valido {}, corregido {}, no_corregible {}, descartado {}, entregadas mal {}",
+            self.ber, self.frames, self.line_flips, self.code_errors, self.multiplication(), self.violations, self.disparity_errors,
+            self.flagged_frames, count(Status::Valid), count(Status::Corrected), count(Status::Uncorrectable),
+            count(Status::Detected), self.wrong
+        )
+    }
+}
+
+// Envía `frames` mensajes aleatorios de `msg_bytes` bytes codificados con `algo`
+// (Hamming de largo `n` o CRC-32), los pasa por el aleatorizador (si hay), el código
+// de línea y el ruido, y deshace cada etapa antes de decodificar la trama como lo
+// haría el receptor. El aleatorizador arranca en su semilla en cada trama.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate(
+    code: LineCode,
+    scrambler: Option<&Scrambler>,
+    algo: &str,
+    n: usize,
+    frames: usize,
+    msg_bytes: usize,
+    ber: f64,
+    rng: &mut Rng,
+) -> Result<LineSimStats, String> {
+    let mut stats = LineSimStats { ber, frames, ..LineSimStats::default() };
+    for _ in 0..frames {
+        let data: Vec<u8> = (0..msg_bytes).map(|_| rng.next_u64() as u8).collect();
+        let (param, bits) = channel::encode_message(algo, n, &data)?;
+        let code_bits: Vec<u8> = bits.bytes().map(|c| c - b'0').collect();
+        let scrambled = match scrambler {
+            Some(s) => s.scramble(&code_bits),
+            None => code_bits.clone(),
+        };
+        let mut signal = code.encode(&scrambled);
+        stats.line_bits += signal.len();
+        stats.line_flips += channel::flip_bits(&mut signal, ber, rng);
+
+        let decoded = code.decode(&signal);
+        let rx = &decoded.bits[..code_bits.len()];
+        let rx = &match scrambler {
+            Some(s) => s.descramble(rx),
+            None => rx.to_vec(),
+        };
+        stats.code_errors += rx.iter().zip(&code_bits).filter(|(a, b)| a != b).count();
+        stats.violations += decoded.violations.len();
+        stats.disparity_errors += decoded.disparity_errors.len();
+        if !decoded.violations.is_empty() || !decoded.disparity_errors.is_empty() {
+            stats.flagged_frames += 1;
+        }
+
+        let rx_bits: String = rx.iter().map(|&b| (b'0' + b) as char).collect();
+        let out = frame::process_frame(algo, &param, &rx_bits, &DecodeOptions::default());
+        *stats.by_status.entry(out.status.as_str()).or_insert(0) += 1;
+        if out.payload.as_ref().is_some_and(|p| *p != data) {
+            stats.wrong += 1;
+        }
+    }
+    Ok(stats)
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ida_y_vuelta_de_todos_los_codigos() {
+        let mut rng = Rng::new(3);
+        let bits = rng.bits(203);
+        for code in ALL {
+            assert_eq!(LineCode::parse(code.as_str()).unwrap(), code);
+            let signal = code.encode(&bits);
+            let decoded = code.decode(&signal);
+            assert_eq!(decoded.bits[..bits.len()], bits[..], "{}", code.as_str());
+            assert!(decoded.bits[bits.len()..].iter().all(|&b| b == 0), "{}: relleno", code.as_str());
+            assert!(decoded.violations.is_empty() && decoded.disparity_errors.is_empty(), "{}", code.as_str());
+        }
+        assert!(LineCode::parse("ami").is_err());
+    }
+
+    #[test]
+    fn un_nivel_cambiado_se_multiplica() {
+        let bits = Rng::new(5).bits(64);
+        // (código, bits del código dañados como máximo, siempre se detecta)
+        for (code, max_errors, detected) in [
+            (LineCode::NrzL, 1, false),
+            (LineCode::Nrzi, 2, false),
+            (LineCode::ManchesterIeee, 1, true),
+            (LineCode::DiffManchester, 2, true),
+            (LineCode::FourBFiveB, 4, false),
+            (LineCode::EightBTenB, 8, false),
+        ] {
+            let signal = code.encode(&bits);
+            let mut worst = 0;
+            for i in 0..signal.len() {
+                let mut bad = signal.clone();
+                bad[i] ^= 1;
+                let decoded = code.decode(&bad);
+                let errors = decoded.bits.iter().zip(&bits).filter(|(a, b)| a != b).count();
+                worst = worst.max(errors);
+                if detected {
+                    assert!(!decoded.violations.is_empty() || !decoded.disparity_errors.is_empty() || errors == 0,
+                        "{}: nivel {} sin detectar", code.as_str(), i);
+                }
+            }
+            assert!(worst >= 1 && worst <= max_errors, "{}: hasta {} bits", code.as_str(), worst);
+        }
+    }
+
+    #[test]
+    fn simulacion_por_las_capas() {
+        let stats = simulate(LineCode::Nrzi, None, "HAMMING", 7, 200, 8, 0.0, &mut Rng::new(1)).unwrap();
+        assert_eq!(stats.by_status.get("valido"), Some(&200));
+        assert_eq!(stats.line_bits, 200 * 112);
+
+        // En NRZI cada nivel cambiado daña dos bits seguidos, que suelen caer en el
+        // mismo bloque: Hamming(7,4) "corrige" un tercer bit y entrega mal la trama
+        let nrzl = simulate(LineCode::NrzL, None, "HAMMING", 7, 500, 8, 0.01, &mut Rng::new(1)).unwrap();
+        let nrzi = simulate(LineCode::Nrzi, None, "HAMMING", 7, 500, 8, 0.01, &mut Rng::new(1)).unwrap();
+        assert_eq!(nrzl.line_flips, nrzi.line_flips, "mismo ruido en ambas líneas");
+        assert!((nrzl.multiplication() - 1.0).abs() < 1e-9);
+        assert!(nrzi.multiplication() > 1.8, "{}", nrzi);
+        assert!(nrzi.wrong > 5 * nrzl.wrong, "{} / {}", nrzl, nrzi);
+
+        let crc = simulate(LineCode::EightBTenB, None, "CRC32", 0, 200, 8, 0.01, &mut Rng::new(1)).unwrap();
+        assert_eq!(crc.wrong, 0);
+        assert!(crc.flagged_frames > 0 && crc.by_status.contains_key("descartado"), "{}", crc);
+    }
+
+    #[test]
+    fn aleatorizador_antes_de_la_linea() {
+        // Un error aislado: el aditivo lo deja en 1 bit, x^43 lo duplica (salvo en los
+        // últimos 43 bits de la trama, de 112) y 1 + x^4 + x^7 lo triplica; los errores
+        // repetidos dejan de ser corregibles por Hamming
+        let run = |s: Option<&str>| {
+            let s = s.map(|name| Scrambler::parse(name).unwrap());
+            simulate(LineCode::NrzL, s.as_ref(), "HAMMING", 7, 500, 8, 0.005, &mut Rng::new(4)).unwrap()
+        };
+        let (plain, additive, x43, b) = (run(None), run(Some("80211a")), run(Some("x43")), run(Some("80211b")));
+        assert_eq!(additive.code_errors, plain.code_errors);
+        assert_eq!(additive.wrong, plain.wrong);
+        assert!(x43.multiplication() > 1.5 && x43.multiplication() <= 2.0, "{}", x43);
+        assert!(b.multiplication() > 2.8 && b.multiplication() <= 3.0, "{}", b);
+        assert!(plain.wrong < x43.wrong && x43.wrong < b.wrong, "{} / {} / {}", plain, x43, b);
+    }
+}
@@ -0,0 +1,54 @@
+// NRZ-L y NRZI: un nivel por bit. En NRZ-L el nivel es el bit; en NRZI un 1 cambia el
+// nivel y un 0 lo mantiene (la línea arranca en bajo), así que la polaridad del
+// cable no importa pero cada bit depende del nivel anterior: un nivel cambiado por el
+// canal daña ese bit y el siguiente. Ninguno de los dos puede detectar errores.
+
+use super::Decoded;
+
+pub fn encode_nrzl(bits: &[u8]) -> Vec<u8> {
+    bits.to_vec()
+}
+
+pub fn decode_nrzl(signal: &[u8]) -> Decoded {
+    Decoded { bits: signal.to_vec(), ..Decoded::default() }
+}
+
+pub fn encode_nrzi(bits: &[u8]) -> Vec<u8> {
+    let mut level = 0;
+    bits.iter()
+        .map(|&b| {
+            level ^= b;
+            level
+        })
+        .collect()
+}
+
+pub fn decode_nrzi(signal: &[u8]) -> Decoded {
+    let mut prev = 0;
+    let bits = signal
+        .iter()
+        .map(|&level| {
+            let b = level ^ prev;
+            prev = level;
+            b
+        })
+        .collect();
+    Decoded { bits, ..Decoded::default() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nrzi_transicion_en_cada_uno() {
+        assert_eq!(encode_nrzi(&[1, 0, 1, 1, 0, 0, 1]), [1, 1, 0, 1, 1, 1, 0]);
+        // Invertir toda la línea solo daña el primer bit
+        let inverted: Vec<u8> = encode_nrzi(&[1, 0, 1, 1]).iter().map(|l| l ^ 1).collect();
+        assert_eq!(decode_nrzi(&inverted).bits, [0, 0, 1, 1]);
+        // Un nivel cambiado daña dos bits
+        let mut signal = encode_nrzi(&[0, 0, 0, 0]);
+        signal[1] ^= 1;
+        assert_eq!(decode_nrzi(&signal).bits, [0, 1, 1, 0]);
+    }
+}
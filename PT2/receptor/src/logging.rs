@@ -0,0 +1,188 @@
+// Registro del servidor (conexiones, rechazos, errores, detalle de cada trama), aparte
+// del resultado de las tramas en stdout. Cada línea lleva hora UTC, nivel y, cuando
+// corresponde, el emisor, el número de trama y el algoritmo, para correlacionarla con
+// la salida y con otras líneas de la misma conexión:
+//
+//   2026-10-18T15:04:05.123Z WARN  peer=127.0.0.1:51234 algo=HAMMING Trama rechazada: ERR 422 ...
+//   {"ts":"2026-10-18T15:04:05.123Z","level":"warn","peer":"127.0.0.1:51234","algo":"HAMMING","msg":"Trama rechazada: ERR 422 ..."}
+//
+// Va a stderr o, con --log-file, al final de un archivo.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::LogLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+// Contexto de una línea; los campos vacíos no se escriben.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Ctx<'a> {
+    pub peer: Option<&'a str>,
+    pub frame: Option<usize>,
+    pub algo: Option<&'a str>,
+}
+
+impl<'a> Ctx<'a> {
+    pub fn peer(peer: &'a str) -> Ctx<'a> {
+        Ctx { peer: Some(peer), ..Ctx::default() }
+    }
+
+    pub fn frame(self, frame: usize) -> Ctx<'a> {
+        Ctx { frame: Some(frame), ..self }
+    }
+
+    pub fn algo(self, algo: &'a str) -> Ctx<'a> {
+        Ctx { algo: Some(algo), ..self }
+    }
+}
+
+pub struct Logger {
+    level: LogLevel,
+    format: LogFormat,
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+pub fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// Fecha civil de un día contado desde 1970-01-01 (algoritmo de H. Hinnant).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// RFC 3339 en UTC con milisegundos: 2026-10-18T15:04:05.123Z
+pub fn timestamp(t: SystemTime) -> String {
+    let since = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs() as i64;
+    let (y, mo, d) = civil_from_days(secs.div_euclid(86_400));
+    let rem = secs.rem_euclid(86_400);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        y, mo, d, rem / 3600, rem / 60 % 60, rem % 60, since.subsec_millis()
+    )
+}
+
+impl Logger {
+    pub fn new(level: LogLevel, format: LogFormat, sink: Box<dyn Write + Send>) -> Logger {
+        Logger { level, format, sink: Mutex::new(sink) }
+    }
+
+    // A stderr, o agregando al archivo si se indica uno.
+    pub fn open(level: LogLevel, format: LogFormat, path: Option<&str>) -> io::Result<Logger> {
+        let sink: Box<dyn Write + Send> = match path {
+            Some(p) => Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(p)
+                    .map_err(|e| io::Error::new(e.kind(), format!("No se pudo abrir el log {}: {}", p, e)))?,
+            ),
+            None => Box::new(io::stderr()),
+        };
+        Ok(Logger::new(level, format, sink))
+    }
+
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level <= self.level
+    }
+
+    pub fn log(&self, level: LogLevel, ctx: Ctx, msg: &str) {
+        if !self.enabled(level) {
+            return;
+        }
+        let line = self.line(&timestamp(SystemTime::now()), level, ctx, msg);
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        // Un log que no se puede escribir no detiene al receptor
+        let _ = writeln!(sink, "{}", line).and_then(|_| sink.flush());
+    }
+
+    fn line(&self, ts: &str, level: LogLevel, ctx: Ctx, msg: &str) -> String {
+        let frame = ctx.frame.map(|n| n.to_string());
+        let fields = [("peer", ctx.peer), ("frame", frame.as_deref()), ("algo", ctx.algo)];
+        match self.format {
+            LogFormat::Text => {
+                let mut out = format!("{} {:<5}", ts, level.as_str().to_ascii_uppercase());
+                for (key, value) in fields {
+                    if let Some(v) = value {
+                        out.push_str(&format!(" {}={}", key, v));
+                    }
+                }
+                format!("{} {}", out, msg)
+            }
+            LogFormat::Json => {
+                let mut out = format!("{{\"ts\":\"{}\",\"level\":\"{}\"", ts, level.as_str());
+                for (key, value) in fields {
+                    match (key, value) {
+                        (_, None) => {}
+                        ("frame", Some(v)) => out.push_str(&format!(",\"frame\":{}", v)),
+                        (k, Some(v)) => out.push_str(&format!(",\"{}\":{}", k, json_str(v))),
+                    }
+                }
+                format!("{},\"msg\":{}}}", out, json_str(msg))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn hora_utc() {
+        assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        let t = UNIX_EPOCH + Duration::from_millis(1_709_210_096_789); // año bisiesto
+        assert_eq!(timestamp(t), "2024-02-29T12:34:56.789Z");
+        assert_eq!(timestamp(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00.000Z");
+    }
+
+    #[test]
+    fn lineas_de_texto_y_json() {
+        let ctx = Ctx::peer("127.0.0.1:5000").frame(7).algo("HAMMING");
+        let text = Logger::new(LogLevel::Info, LogFormat::Text, Box::new(io::sink()));
+        assert_eq!(
+            text.line("T", LogLevel::Warn, ctx, "Trama rechazada"),
+            "T WARN  peer=127.0.0.1:5000 frame=7 algo=HAMMING Trama rechazada"
+        );
+        assert_eq!(text.line("T", LogLevel::Info, Ctx::default(), "Apagando"), "T INFO  Apagando");
+        assert!(text.enabled(LogLevel::Warn) && !text.enabled(LogLevel::Debug));
+
+        let json = Logger::new(LogLevel::Info, LogFormat::Json, Box::new(io::sink()));
+        assert_eq!(
+            json.line("T", LogLevel::Error, ctx, "dijo \"hola\""),
+            r#"{"ts":"T","level":"error","peer":"127.0.0.1:5000","frame":7,"algo":"HAMMING","msg":"dijo \"hola\""}"#
+        );
+    }
+}
@@ -1,118 +1,388 @@
 mod detection;
 mod correction;
+mod frame;
+mod batch;
+mod transfer;
+mod wire;
+mod protocol;
+mod stats;
+mod pool;
+mod shutdown;
+mod server;
+mod transport;
+mod toml;
+mod config;
+mod metrics;
+mod logging;
+mod capture;
+mod framing;
+mod channel;
+mod line_coding;
+mod scrambler;
 
-use std::io::{BufRead, BufReader};
-use std::net::{TcpListener};
+use std::net::{TcpListener, UdpSocket};
 use std::io::{self, Write};
+use std::sync::Arc;
 
+use batch::BatchOptions;
+use channel::{Rng, SimOptions};
+use config::{Config, LogLevel, OutputFormat};
+use framing::{sync, Framing};
+use line_coding::LineCode;
+use scrambler::Scrambler;
+use logging::Ctx;
+use server::Server;
+use toml::Value;
+use transport::Listen;
 
-fn bits_to_string_u8(bits: &str) -> Result<String, String> {
-    if bits.len() % 8 != 0 { return Err(format!("Longitud no múltiplo de 8: {}", bits.len())); }
-    let mut out = String::new();
-    for chunk in bits.as_bytes().chunks(8) {
-        let s = std::str::from_utf8(chunk).map_err(|_| "utf8".to_string())?;
-        let v = u8::from_str_radix(s, 2).map_err(|_| format!("byte inválido: {}", s))?;
-        out.push(v as char);
+const USAGE: &str = "Uso:
+  receptor [--config ARCHIVO.toml] [--listen DIRECCIÓN] [--codecs LISTA] [--default-param ALGO:PARAM]
+           [--output text|json] [--log-level NIVEL] [--log-format text|json] [--log-file ARCHIVO]
+           [--max-frame-bits N] [--partial] [--utf8-strict]
+           [--payload-dir DIR] [--recv-dir DIR] [--workers N] [--read-timeout SEG] [--metrics HOST:PUERTO]
+           [--capture ARCHIVO.pcapng]
+                                                        (servidor, por defecto TCP en 0.0.0.0:9000)
+  receptor --batch ENTRADA [--out RESULTADOS.csv] [--algo HAMMING|CRC32] [--param PARAM] [--partial] [--utf8-strict]
+                   [--framing hdlc|ppp|slip|cobs|sync] [--preamble BITS] [--sync-word BITS|0xHEX] [--sync-tolerance N]
+  receptor --sync-sim TRAMAS [--ber LISTA] [--seed N] [--frame-bits N] [--preamble ...] [--sync-word ...] [--sync-tolerance N]
+                                                        (enganches falsos y sincronizaciones perdidas con ruido)
+  receptor --line-sim TRAMAS [--line-code CÓDIGO|all] [--scrambler NOMBRE|all] [--algo HAMMING|CRC32]
+                     [--param n=N] [--ber LISTA] [--seed N] [--msg-bytes N]
+                                                        (errores de la línea y del aleatorizador en Hamming y CRC)
+  receptor --frame-sim TRAMAS [--framing hdlc|ppp|slip|cobs|sync] [--algo HAMMING|CRC32] [--param n=N] [--ber LISTA] [--seed N]
+                      [--msg-bytes N] [--preamble ...] [--sync-word ...] [--sync-tolerance N]
+                                                        (sobrecarga y mensajes recuperados de cada entramado)
+  receptor --replay CAPTURA.pcapng [--output text|json] [--log-level NIVEL] [--recv-dir DIR] ...
+                                                        (vuelve a decodificar una captura de --capture)
+
+  --config          archivo TOML con las mismas opciones (ver receptor.toml); las banderas lo pisan
+  --listen          tcp://HOST:PUERTO (o HOST:PUERTO), udp://HOST:PUERTO (una trama por datagrama),
+                    unix:///RUTA (socket Unix) o - (tramas por stdin hasta EOF, sin respuestas)
+  --codecs          algoritmos habilitados, separados por comas (por defecto: HAMMING,CRC32)
+  --default-param   PARAM por defecto de un algoritmo, p.ej. HAMMING:n=15 (la trama tiene prioridad)
+  --output          resultado de cada trama: text (por defecto) o json (una línea por trama;
+                    los demás mensajes van a stderr)
+  --log-level       error, warn, info (por defecto) o debug (muestra los bits completos)
+  --log-format      registro del servidor en text (por defecto) o json, con hora, emisor,
+                    número de trama y algoritmo
+  --log-file        agregar el registro a ARCHIVO en vez de stderr
+  --max-frame-bits  tramas más largas se rechazan con ERR 413 (por defecto: 1048576)
+  --partial         Hamming: entregar los bloques buenos aunque haya bloques no corregibles
+                    (los bytes afectados se marcan con '?')
+  --utf8-strict     content=utf8: descartar texto con secuencias UTF-8 inválidas (por defecto se
+                    reemplazan por U+FFFD)
+  --payload-dir     content=bytes: guardar la carga útil de cada trama en DIR/trama_N.bin
+  --recv-dir        directorio donde se arman los archivos recibidos con app.FileSend
+                    (por defecto: recibidos)
+  --workers         TCP/Unix: conexiones atendidas en paralelo (por defecto: 4)
+  --read-timeout    TCP/Unix: segundos sin datos antes de cerrar una conexión, y plazo para recibir
+                    cada trama completa (por defecto: 30, 0 = sin límite)
+  --metrics         servir métricas en formato Prometheus en http://HOST:PUERTO/metrics
+  --capture         escribir cada trama recibida en un pcapng (tipo de enlace USER0, con ALGO,
+                    PARAM, resultado y correcciones como comentarios) para abrirlo con Wireshark
+  --replay          decodificar de nuevo, sin red, las tramas de una captura hecha con --capture y
+                    con la configuración grabada en ella: la salida es la misma que en la sesión
+  --framing         batch: ENTRADA es un canal crudo con las tramas entramadas una tras otra;
+                    cada trama extraída se decodifica con --algo/--param. hdlc: banderas 0x7E
+                    con bit stuffing, en texto de '0'/'1'. ppp (escape 0x7D), slip (END 0xC0)
+                    y cobs (delimitador 0x00): archivo binario, cada trama con su largo en bits
+                    (u32) y los bits empaquetados. sync: preámbulo + palabra de sincronización
+                    buscados con tolerancia, largo en 16 bits y su complemento, y el código,
+                    en texto de '0'/'1'
+  --preamble        sync: preámbulo antes de la palabra (por defecto: 10101010; '' = sin preámbulo)
+  --sync-word       sync: palabra de sincronización (por defecto: 0x1ACFFC1D)
+  --sync-tolerance  sync: bits distintos aceptados en preámbulo + palabra (por defecto: 2)
+  --sync-sim        simular TRAMAS tramas aleatorias por un canal con ruido para cada BER de --ber
+                    (por defecto: 0,0.001,0.01,0.02,0.05) y contar enganches y pérdidas
+  --seed            semilla del simulador (por defecto: 1; la misma semilla repite la corrida)
+  --frame-bits      bits de código por trama simulada (por defecto: 112)
+  --line-sim        codificar TRAMAS mensajes aleatorios con --algo (por defecto HAMMING, n de --param),
+                    pasarlos por la codificación de línea y el ruido, y contar cuántos bits del
+                    código daña cada nivel cambiado y qué hacen Hamming y CRC-32 con eso
+  --frame-sim       codificar TRAMAS mensajes aleatorios con --algo, entramarlos con --framing (por
+                    defecto: todos) en un mismo canal, pasarlo por el ruido y extraer y decodificar
+                    las tramas como --batch --framing: cuenta la sobrecarga y los mensajes recuperados
+  --line-code       nrz-l, nrzi, manchester-ieee, manchester-thomas, diff-manchester, 4b5b, 8b10b
+                    o all (por defecto: all)
+  --msg-bytes       bytes por mensaje simulado (por defecto: 8)
+  --scrambler       line-sim: aleatorizar el código antes de la línea. Aditivos (por trama): 80211a
+                    (1+x^4+x^7), sonet (1+x^6+x^7); multiplicativos (autosincronizados): 80211b
+                    (1+x^4+x^7), x43 (1+x^43); o add:7,4[@SEMILLA_HEX] / mult:43. all: sin
+                    aleatorizador y cada uno de los anteriores
+
+El servidor termina de forma ordenada con Ctrl+C (SIGINT) o SIGTERM e imprime los totales.";
+
+#[derive(Debug)]
+struct Args {
+    batch: Option<String>,
+    replay: Option<String>,
+    sync_sim: Option<usize>,
+    line_sim: Option<usize>,
+    frame_sim: Option<usize>,
+    line_code: Option<LineCode>, // None: todos
+    scramblers: Vec<Option<Scrambler>>,
+    sim: SimOptions,
+    out: Option<String>,
+    batch_opts: BatchOptions,
+    config: Config,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        batch: None,
+        replay: None,
+        sync_sim: None,
+        line_sim: None,
+        frame_sim: None,
+        line_code: None,
+        scramblers: vec![None],
+        sim: SimOptions::default(),
+        out: None,
+        batch_opts: BatchOptions::default(),
+        config: Config::default(),
+    };
+    // El archivo va primero para que las banderas lo pisen sin importar el orden
+    if let Some(i) = args.iter().position(|a| a == "--config") {
+        let path = args.get(i + 1).ok_or("Falta valor para --config")?;
+        parsed.config.load(path)?;
     }
-    Ok(out)
+    let mut it = args.iter();
+    while let Some(a) = it.next() {
+        let mut value = || it.next().cloned().ok_or_else(|| format!("Falta valor para {}", a));
+        match a.as_str() {
+            "--config" => {
+                value()?;
+            }
+            "--batch" => parsed.batch = Some(value()?),
+            "--replay" => parsed.replay = Some(value()?),
+            "--out" => parsed.out = Some(value()?),
+            "--algo" => parsed.batch_opts.default_algo = Some(value()?),
+            "--param" => parsed.batch_opts.default_param = value()?,
+            "--framing" => parsed.batch_opts.framing = Some(Framing::parse(&value()?)?),
+            "--preamble" => parsed.batch_opts.sync.preamble = sync::parse_pattern(&value()?)?,
+            "--sync-word" => parsed.batch_opts.sync.word = sync::parse_pattern(&value()?)?,
+            "--sync-tolerance" => parsed.batch_opts.sync.tolerance = parse_number(a, &value()?)?,
+            "--sync-sim" => parsed.sync_sim = Some(parse_number(a, &value()?)?),
+            "--ber" => parsed.sim.ber = SimOptions::parse_ber(&value()?)?,
+            "--seed" => parsed.sim.seed = parse_number(a, &value()?)?,
+            "--frame-bits" => parsed.sim.frame_bits = parse_number(a, &value()?)?,
+            "--line-sim" => parsed.line_sim = Some(parse_number(a, &value()?)?),
+            "--frame-sim" => parsed.frame_sim = Some(parse_number(a, &value()?)?),
+            "--line-code" => {
+                let v = value()?;
+                parsed.line_code = if v == "all" { None } else { Some(LineCode::parse(&v)?) };
+            }
+            "--msg-bytes" => parsed.sim.msg_bytes = parse_number(a, &value()?)?,
+            "--scrambler" => {
+                let v = value()?;
+                parsed.scramblers = if v == "all" {
+                    std::iter::once(None).chain(Scrambler::presets().into_iter().map(Some)).collect()
+                } else {
+                    vec![Some(Scrambler::parse(&v)?)]
+                };
+            }
+            "--partial" | "--utf8-strict" => parsed.config.set(&a[2..].replace('-', "_"), &Value::Bool(true))?,
+            "--default-param" => {
+                let v = value()?;
+                let (algo, param) = v.split_once(':').ok_or(format!("Se esperaba ALGO:PARAM en --default-param: {}", v))?;
+                parsed.config.set(&format!("params.{}", algo), &Value::Str(param.to_string()))?;
+            }
+            "--listen" | "--workers" | "--read-timeout" | "--max-frame-bits" | "--codecs" | "--output" | "--log-level"
+            | "--log-format" | "--log-file" | "--payload-dir" | "--recv-dir" | "--metrics"
+            | "--capture" => parsed.config.set(&a[2..].replace('-', "_"), &Value::Str(value()?))?,
+            other => return Err(format!("Argumento inválido: {}", other)),
+        }
+    }
+    parsed.batch_opts.sync.validate()?;
+    if parsed.sim.frame_bits == 0 || parsed.sim.frame_bits >= 1 << sync::LENGTH_BITS {
+        return Err(format!("--frame-bits debe estar entre 1 y {}", (1 << sync::LENGTH_BITS) - 1));
+    }
+    parsed.batch_opts.decode = parsed.config.decode;
+    parsed.batch_opts.max_frame_bits = parsed.config.max_frame_bits;
+    Ok(parsed)
 }
 
-fn parse_param_map(s: &str) -> std::collections::HashMap<String, String> {
-    let mut map = std::collections::HashMap::new();
-    for part in s.split(';') {
-        let p = part.trim();
-        if p.is_empty() { continue; }
-        if let Some((k,v)) = p.split_once('=') { map.insert(k.to_string(), v.to_string()); }
+fn parse_number<T: std::str::FromStr>(flag: &str, v: &str) -> Result<T, String> {
+    v.parse().map_err(|_| format!("Valor inválido para {}: {}", flag, v))
+}
+
+// Simulación de sincronización: `receptor --sync-sim 10000 --ber 0,0.01,0.05`. Cada
+// BER usa un generador nuevo con la misma semilla.
+fn run_sync_sim(frames: usize, args: &Args) -> Result<(), String> {
+    let cfg = &args.batch_opts.sync;
+    println!(
+        "Sincronización: preámbulo de {} bits, palabra de {} bits, tolerancia {}, {} tramas de {} bits, semilla {}",
+        cfg.preamble.len(), cfg.word.len(), cfg.tolerance, frames, args.sim.frame_bits, args.sim.seed
+    );
+    for &ber in &args.sim.ber {
+        let stats = sync::simulate(cfg, frames, args.sim.frame_bits, ber, &mut Rng::new(args.sim.seed))?;
+        println!("  {}", stats);
     }
-    map
+    Ok(())
 }
 
-fn main() -> std::io::Result<()> {
-    let addr = "0.0.0.0:9000";
-    println!("=== RECEPTOR (Parte 2) – escuchando en {} ===", addr);
-    io::stdout().flush().unwrap();
-
-    let listener = TcpListener::bind(addr)?;
-
-    for stream in listener.incoming() {
-        let stream = stream?;
-        let peer = stream.peer_addr().ok();
-        println!("Conexión de {:?}", peer);
-        io::stdout().flush().unwrap();
-
-        let mut reader = BufReader::new(stream);
-
-        let mut line = String::new();
-        // ALGO=
-        line.clear(); reader.read_line(&mut line)?; let algo = line.trim_start_matches("ALGO=").trim().to_string();
-        // PARAM=
-        line.clear(); reader.read_line(&mut line)?; let param_str = line.trim_start_matches("PARAM=").trim().to_string();
-        // BITS=
-        line.clear(); reader.read_line(&mut line)?; let bits = line.trim_start_matches("BITS=").trim().to_string();
-
-        println!("ALGO={} | PARAM={} | bits={}… ({} bits)", algo, param_str, &bits.chars().take(32).collect::<String>(), bits.len());
-        io::stdout().flush().unwrap();
-
-
-        match algo.as_str() {
-            "CRC32" => {
-                match detection::crc32::verify_crc32_poly(&bits) {
-                    Ok(ok) if ok.valid => {
-                        let msg_bits = ok.original_message.unwrap();
-                        match bits_to_string_u8(&msg_bits) {
-                            Ok(s) => {
-                                println!("CRC válido. Mensaje: {}", s);
-                                io::stdout().flush().unwrap();
-                            }
-                            Err(e) => {
-                                println!("CRC válido, pero no se pudo decodificar ASCII: {}", e);
-                                io::stdout().flush().unwrap();
-                            },
-                        }
-                    }
-                    Ok(_) => {
-                        println!("CRC inválido: mensaje descartado");
-                        io::stdout().flush().unwrap();
-                    },
-                    Err(e) => {
-                        println!("Error CRC: {}", e);
-                        io::stdout().flush().unwrap();
-                    },
-                }
+// Código de los mensajes simulados: --algo (por defecto HAMMING) y n de --param.
+fn sim_code(args: &Args) -> Result<(String, usize, String), String> {
+    let algo = args.batch_opts.default_algo.as_deref().unwrap_or("HAMMING").to_ascii_uppercase();
+    let params = frame::parse_param_map(&args.batch_opts.default_param);
+    let n = params.get("n").map(|v| parse_number("n", v)).transpose()?.unwrap_or(7);
+    let name = if algo == "HAMMING" { format!("Hamming n={}", n) } else { algo.clone() };
+    Ok((algo, n, name))
+}
+
+// Simulación de codificación de línea: `receptor --line-sim 1000 --line-code nrzi`,
+// opcionalmente con `--scrambler`. Todas las filas usan la misma semilla.
+fn run_line_sim(frames: usize, args: &Args) -> Result<(), String> {
+    let (algo, n, code_name) = sim_code(args)?;
+    let codes = match args.line_code {
+        Some(code) => vec![code],
+        None => line_coding::ALL.to_vec(),
+    };
+    println!("Codificación de línea: {} tramas de {} bytes con {}, semilla {}", frames, args.sim.msg_bytes, code_name, args.sim.seed);
+    for code in codes {
+        for scrambler in &args.scramblers {
+            match scrambler {
+                Some(s) => println!("{} + {}, errores del canal × {}:", code.as_str(), s, s.error_spread()),
+                None => println!("{}:", code.as_str()),
             }
-            "HAMMING" => {
-                let params = parse_param_map(&param_str);
-                let n: usize = params.get("n").and_then(|v| v.parse().ok()).unwrap_or(7);
-                let pad: usize = params.get("pad").and_then(|v| v.parse().ok()).unwrap_or(0);
-                match correction::hamming::decode_stream(&bits, n) {
-                    Ok(res) => {
-                        let mut data = res.data_bits;
-                        if pad > 0 && pad <= data.len() { data.truncate(data.len() - pad); }
-                        match bits_to_string_u8(&data) {
-                            Ok(s) => {
-                                if res.corrected_positions.is_empty() {
-                                    println!("Hamming: sin errores. Mensaje: {}", s);
-                                    io::stdout().flush().unwrap();
-                                } else {
-                                    println!("Hamming: errores corregidos en {:?}. Mensaje: {}", res.corrected_positions, s);
-                                    io::stdout().flush().unwrap();
-                                }
-                            }
-                            Err(e) => {
-                                println!("Hamming ok, pero no se pudo decodificar ASCII: {}", e);
-                                io::stdout().flush().unwrap();
-                            },
-                        }
-                    }
-                    Err(e) => { 
-                        println!("Hamming: errores no corregibles. {}", e);
-                        io::stdout().flush().unwrap();
-                    }
-                }
+            for &ber in &args.sim.ber {
+                let mut rng = Rng::new(args.sim.seed);
+                let stats = line_coding::simulate(code, scrambler.as_ref(), &algo, n, frames, args.sim.msg_bytes, ber, &mut rng)?;
+                println!("  {}", stats);
             }
-            other => println!("Algoritmo no soportado: {}", other),
         }
     }
+    Ok(())
+}
 
+// Simulación de entramado: `receptor --frame-sim 1000 --framing hdlc`; sin --framing,
+// compara la sobrecarga de todos. Todas las filas usan la misma semilla.
+fn run_frame_sim(frames: usize, args: &Args) -> Result<(), String> {
+    let framings = match args.batch_opts.framing {
+        Some(framing) => vec![framing],
+        None => framing::ALL.to_vec(),
+    };
+    let (algo, n, code_name) = sim_code(args)?;
+    println!("Entramado: {} mensajes de {} bytes con {}, semilla {}", frames, args.sim.msg_bytes, code_name, args.sim.seed);
+    for framing in framings {
+        println!("{} ({}):", framing.as_str(), framing.unit());
+        for &ber in &args.sim.ber {
+            let mut rng = Rng::new(args.sim.seed);
+            let stats = framing::simulate(framing, &args.batch_opts.sync, &algo, n, frames, args.sim.msg_bytes, ber, &mut rng)?;
+            println!("  {}", stats);
+        }
+    }
     Ok(())
 }
+
+// Modo batch: `receptor --batch capturas.txt --out resultados.csv`
+fn run_batch_mode(input: &str, args: &Args) -> i32 {
+    match batch::run_file(input, args.out.as_deref(), &args.batch_opts) {
+        Ok(summary) => {
+            // Con resultados a stdout, el resumen va a stderr para no mezclar el CSV
+            if args.out.is_some() {
+                print!("{}", summary.render());
+            } else {
+                eprint!("{}", summary.render());
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Error en modo batch: {}", e);
+            1
+        }
+    }
+}
+
+// Reproducción: `receptor --replay sesion.pcapng`. La configuración de decodificación
+// es la grabada en la captura; las banderas eligen la salida y los directorios.
+fn run_replay(path: &str, mut cfg: Config) -> Result<(), String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("No se pudo abrir {}: {}", path, e))?;
+    let recording = capture::read(io::BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))?;
+    cfg.apply_recorded(&recording.comments)?;
+    let server = Server::new(cfg).map_err(|e| e.to_string())?;
+    match server.replay(&recording).map_err(|e| e.to_string())? {
+        0 => Ok(()),
+        n => Err(format!("{} paquetes con un resultado distinto del grabado", n)),
+    }
+}
+
+fn main() -> std::io::Result<()> {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&raw) {
+        Ok(a) => a,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(64);
+        }
+    };
+    if let Some(frames) = args.sync_sim {
+        if let Err(e) = run_sync_sim(frames, &args) {
+            eprintln!("Error en la simulación: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(frames) = args.line_sim {
+        if let Err(e) = run_line_sim(frames, &args) {
+            eprintln!("Error en la simulación: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(frames) = args.frame_sim {
+        if let Err(e) = run_frame_sim(frames, &args) {
+            eprintln!("Error en la simulación: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(input) = &args.batch {
+        std::process::exit(run_batch_mode(input, &args));
+    }
+    if let Some(path) = &args.replay {
+        if let Err(e) = run_replay(path, args.config) {
+            eprintln!("Error en la reproducción: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let cfg = args.config;
+    let banner = format!("=== RECEPTOR (Parte 2) – escuchando en {} ===", cfg.listen);
+    server::emit(cfg.output == OutputFormat::Json, &banner);
+    let _ = io::stdout().flush();
+
+    let listen = cfg.listen.clone();
+    let metrics_addr = cfg.metrics.clone();
+    let server = Server::new(cfg)?;
+    if let Some(addr) = metrics_addr {
+        let listener = TcpListener::bind(&addr)?;
+        let metrics = server.metrics();
+        std::thread::spawn(move || metrics::serve(listener, metrics));
+        server.logger().log(LogLevel::Info, Ctx::default(), &format!("Métricas en http://{}/metrics", addr));
+    }
+    match &listen {
+        Listen::Tcp(addr) => {
+            let listener = TcpListener::bind(addr)?;
+            shutdown::install(server.stop_flag());
+            Arc::new(server).serve_stream(listener)
+        }
+        #[cfg(unix)]
+        Listen::Unix(path) => {
+            let listener = transport::bind_unix(path)?;
+            shutdown::install(server.stop_flag());
+            let result = Arc::new(server).serve_stream(listener);
+            let _ = std::fs::remove_file(path);
+            result
+        }
+        #[cfg(not(unix))]
+        Listen::Unix(_) => Err(io::Error::new(io::ErrorKind::Unsupported, "sockets Unix no disponibles en esta plataforma")),
+        // Ctrl+C termina el proceso como siempre; el fin normal es el EOF de stdin
+        Listen::Stdio => server.serve_stdio(),
+        Listen::Udp(addr) => {
+            let socket = UdpSocket::bind(addr)?;
+            shutdown::install(server.stop_flag());
+            server.serve_udp(socket)
+        }
+    }
+}
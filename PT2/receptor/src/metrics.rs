@@ -0,0 +1,251 @@
+// Métricas del servidor en el formato de texto de Prometheus, para pruebas largas:
+// con --metrics HOST:PUERTO un hilo aparte responde GET /metrics por HTTP.
+//
+//   receptor_frames_total{algo,status}      tramas procesadas por algoritmo y resultado
+//   receptor_frames_rejected_total{code}    tramas respondidas con ERR, por código
+//   receptor_corrected_bits{algo}           histograma de bits corregidos por trama
+//   receptor_decode_seconds{algo}           histograma del tiempo de decodificación
+//   receptor_wire_bytes_total               bytes de tramas leídos del cable
+//   receptor_connections_total              conexiones atendidas (TCP/Unix)
+//   receptor_connections_active             conexiones abiertas en este momento
+//   receptor_connections_busy_total         conexiones rechazadas con el pool lleno
+//   receptor_uptime_seconds                 segundos desde que arrancó el receptor
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::frame::Status;
+use crate::wire;
+
+const CORRECTED_BITS_BUCKETS: [f64; 8] = [0.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0];
+const DECODE_SECONDS_BUCKETS: [f64; 9] = [1e-5, 5e-5, 1e-4, 5e-4, 1e-3, 5e-3, 0.01, 0.05, 0.1];
+// Un cliente HTTP lento no debe bloquear el hilo de métricas
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_HEADERS: usize = 100;
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>, // por cubeta, sin acumular; la última es +Inf
+    sum: f64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Histogram {
+        Histogram { bounds, counts: vec![0; bounds.len() + 1], sum: 0.0 }
+    }
+
+    fn observe(&mut self, v: f64) {
+        let i = self.bounds.iter().position(|b| v <= *b).unwrap_or(self.bounds.len());
+        self.counts[i] += 1;
+        self.sum += v;
+    }
+
+    // Cubetas acumuladas (le = "menor o igual"), como las espera Prometheus.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let mut acc = 0;
+        for (i, n) in self.counts.iter().enumerate() {
+            acc += n;
+            let le = self.bounds.get(i).map(|b| b.to_string()).unwrap_or_else(|| "+Inf".into());
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, le, acc);
+        }
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, self.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, acc);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    frames: BTreeMap<(String, &'static str), u64>,
+    rejected: BTreeMap<u16, u64>,
+    corrected_bits: BTreeMap<String, Histogram>,
+    decode_seconds: BTreeMap<String, Histogram>,
+    wire_bytes: u64,
+    connections: u64,
+    active: u64,
+    busy: u64,
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    counters: Mutex<Counters>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics { started: Instant::now(), counters: Mutex::new(Counters::default()) }
+    }
+}
+
+fn label(v: &str) -> String {
+    v.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl Metrics {
+    fn counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.counters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn frame(&self, algo: &str, status: Status, corrected_bits: usize, decode: Duration, wire_bytes: usize) {
+        let mut c = self.counters();
+        *c.frames.entry((algo.to_string(), status.as_str())).or_insert(0) += 1;
+        c.corrected_bits
+            .entry(algo.to_string())
+            .or_insert_with(|| Histogram::new(&CORRECTED_BITS_BUCKETS))
+            .observe(corrected_bits as f64);
+        c.decode_seconds
+            .entry(algo.to_string())
+            .or_insert_with(|| Histogram::new(&DECODE_SECONDS_BUCKETS))
+            .observe(decode.as_secs_f64());
+        c.wire_bytes += wire_bytes as u64;
+    }
+
+    pub fn rejected(&self, code: u16) {
+        *self.counters().rejected.entry(code).or_insert(0) += 1;
+    }
+
+    pub fn connection_opened(&self) {
+        let mut c = self.counters();
+        c.connections += 1;
+        c.active += 1;
+    }
+
+    pub fn connection_closed(&self) {
+        let mut c = self.counters();
+        c.active = c.active.saturating_sub(1);
+    }
+
+    pub fn busy(&self) {
+        self.counters().busy += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let c = self.counters();
+        let mut out = String::new();
+        let header = |out: &mut String, name: &str, kind: &str, help: &str| {
+            let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+        };
+
+        header(&mut out, "receptor_frames_total", "counter", "Tramas procesadas por algoritmo y resultado.");
+        for ((algo, status), n) in &c.frames {
+            let _ = writeln!(out, "receptor_frames_total{{algo=\"{}\",status=\"{}\"}} {}", label(algo), status, n);
+        }
+        header(&mut out, "receptor_frames_rejected_total", "counter", "Tramas respondidas con ERR, por código.");
+        for (code, n) in &c.rejected {
+            let _ = writeln!(out, "receptor_frames_rejected_total{{code=\"{}\"}} {}", code, n);
+        }
+        header(&mut out, "receptor_corrected_bits", "histogram", "Bits corregidos por trama.");
+        for (algo, h) in &c.corrected_bits {
+            h.render(&mut out, "receptor_corrected_bits", &format!("algo=\"{}\"", label(algo)));
+        }
+        header(&mut out, "receptor_decode_seconds", "histogram", "Tiempo de decodificación de una trama.");
+        for (algo, h) in &c.decode_seconds {
+            h.render(&mut out, "receptor_decode_seconds", &format!("algo=\"{}\"", label(algo)));
+        }
+        let scalars = [
+            ("receptor_wire_bytes_total", "counter", "Bytes de tramas leídos del cable.", c.wire_bytes as f64),
+            ("receptor_connections_total", "counter", "Conexiones atendidas.", c.connections as f64),
+            ("receptor_connections_active", "gauge", "Conexiones abiertas.", c.active as f64),
+            ("receptor_connections_busy_total", "counter", "Conexiones rechazadas con el receptor ocupado.", c.busy as f64),
+            ("receptor_uptime_seconds", "gauge", "Segundos desde el arranque.", self.started.elapsed().as_secs_f64()),
+        ];
+        for (name, kind, help, v) in scalars {
+            header(&mut out, name, kind, help);
+            let _ = writeln!(out, "{} {}", name, v);
+        }
+        out
+    }
+}
+
+// Atiende las peticiones de a una, en el hilo que la llama, hasta que se cierre el proceso.
+pub fn serve(listener: TcpListener, metrics: Arc<Metrics>) {
+    for conn in listener.incoming() {
+        let Ok(stream) = conn else { continue };
+        let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+        let Ok(writer) = stream.try_clone() else { continue };
+        let _ = respond(BufReader::new(stream), writer, &metrics);
+    }
+}
+
+// Lee una petición HTTP/1.x (línea de petición y encabezados, que se ignoran) y responde
+// GET /metrics; cualquier otra cosa recibe 404 o 405. La conexión se cierra siempre.
+fn respond<R: BufRead, W: Write>(mut reader: R, mut writer: W, metrics: &Metrics) -> io::Result<()> {
+    let too_long = || io::Error::new(io::ErrorKind::InvalidData, "petición HTTP demasiado larga");
+    let (request, _) = wire::read_line_limited(&mut reader, wire::MAX_HEADER_LINE, too_long)?;
+    for _ in 0..MAX_REQUEST_HEADERS {
+        let (line, read) = wire::read_line_limited(&mut reader, wire::MAX_HEADER_LINE, too_long)?;
+        if line.is_empty() || read == 0 {
+            break;
+        }
+    }
+    let mut parts = request.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let (status, content_type, body, extra) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", "text/plain; version=0.0.4; charset=utf-8", metrics.render(), ""),
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "solo /metrics\n".to_string(), ""),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "solo GET\n".to_string(), "Allow: GET\r\n"),
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        extra,
+        body
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formato_de_exposicion() {
+        let m = Metrics::default();
+        m.frame("HAMMING", Status::Corrected, 3, Duration::from_micros(20), 40);
+        m.frame("HAMMING", Status::Valid, 0, Duration::from_micros(20), 40);
+        m.frame("CRC32", Status::Detected, 0, Duration::from_millis(2), 50);
+        m.rejected(422);
+        m.connection_opened();
+        let text = m.render();
+        assert!(text.contains("receptor_frames_total{algo=\"HAMMING\",status=\"corregido\"} 1\n"));
+        assert!(text.contains("receptor_frames_total{algo=\"CRC32\",status=\"descartado\"} 1\n"));
+        assert!(text.contains("receptor_frames_rejected_total{code=\"422\"} 1\n"));
+        // Cubetas acumuladas: 0 bits en le=0, 3 bits entre 2 y 4
+        assert!(text.contains("receptor_corrected_bits_bucket{algo=\"HAMMING\",le=\"0\"} 1\n"));
+        assert!(text.contains("receptor_corrected_bits_bucket{algo=\"HAMMING\",le=\"2\"} 1\n"));
+        assert!(text.contains("receptor_corrected_bits_bucket{algo=\"HAMMING\",le=\"4\"} 2\n"));
+        assert!(text.contains("receptor_corrected_bits_bucket{algo=\"HAMMING\",le=\"+Inf\"} 2\n"));
+        assert!(text.contains("receptor_corrected_bits_sum{algo=\"HAMMING\"} 3\n"));
+        assert!(text.contains("receptor_decode_seconds_count{algo=\"CRC32\"} 1\n"));
+        assert!(text.contains("receptor_wire_bytes_total 130\n"));
+        assert!(text.contains("# TYPE receptor_connections_active gauge\nreceptor_connections_active 1\n"));
+    }
+
+    #[test]
+    fn peticiones_http() {
+        let m = Metrics::default();
+        let mut out = Vec::new();
+        respond(&b"GET /metrics HTTP/1.1\r\nHost: x\r\n\r\n"[..], &mut out, &m).unwrap();
+        let resp = String::from_utf8(out).unwrap();
+        assert!(resp.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4"));
+        let body = resp.split("\r\n\r\n").nth(1).unwrap();
+        assert!(resp.contains(&format!("Content-Length: {}\r\n", body.len())));
+        assert!(body.contains("# TYPE receptor_frames_total counter"));
+
+        let mut out = Vec::new();
+        respond(&b"GET / HTTP/1.1\r\n\r\n"[..], &mut out, &m).unwrap();
+        assert!(out.starts_with(b"HTTP/1.1 404"));
+        let mut out = Vec::new();
+        respond(&b"POST /metrics HTTP/1.1\r\n\r\n"[..], &mut out, &m).unwrap();
+        assert!(out.starts_with(b"HTTP/1.1 405"));
+    }
+}
@@ -0,0 +1,95 @@
+// Pool fijo de hilos con cola acotada para atender conexiones en paralelo.
+// Si todos los hilos están ocupados y la cola está llena, try_execute rechaza el
+// trabajo en vez de acumular conexiones sin límite.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub struct ThreadPool {
+    sender: Option<SyncSender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    pub fn new(size: usize, queue: usize) -> ThreadPool {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(queue);
+        let receiver: Arc<Mutex<Receiver<Job>>> = Arc::new(Mutex::new(receiver));
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    // El lock se suelta al terminar la sentencia, antes de ejecutar el trabajo
+                    let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    match job {
+                        // Un trabajo que entra en pánico no se lleva el hilo del pool
+                        Ok(job) => { let _ = panic::catch_unwind(AssertUnwindSafe(job)); }
+                        Err(_) => break, // pool cerrado
+                    }
+                })
+            })
+            .collect();
+        ThreadPool { sender: Some(sender), workers }
+    }
+
+    // false si la cola está llena (el trabajo se descarta).
+    pub fn try_execute<F: FnOnce() + Send + 'static>(&self, f: F) -> bool {
+        match &self.sender {
+            Some(s) => s.try_send(Box::new(f)).is_ok(),
+            None => false,
+        }
+    }
+}
+
+// Cierra la cola y espera a que terminen los trabajos pendientes.
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for w in self.workers.drain(..) {
+            let _ = w.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn pool_acotado_rechaza_con_cola_llena() {
+        let done = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = channel::<()>();
+        let release_rx = Arc::new(Mutex::new(release_rx));
+        let pool = ThreadPool::new(1, 1);
+        // El primer trabajo ocupa el único hilo hasta que se lo libera
+        let (started_tx, started_rx) = channel();
+        let rx = Arc::clone(&release_rx);
+        assert!(pool.try_execute(move || {
+            started_tx.send(()).unwrap();
+            rx.lock().unwrap().recv().unwrap();
+        }));
+        started_rx.recv().unwrap();
+        let d = Arc::clone(&done);
+        assert!(pool.try_execute(move || { d.fetch_add(1, Ordering::SeqCst); })); // en cola
+        assert!(!pool.try_execute(|| {}));                                       // cola llena
+        release_tx.send(()).unwrap();
+        drop(pool);
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn pool_sobrevive_a_un_panico() {
+        let done = Arc::new(AtomicUsize::new(0));
+        let pool = ThreadPool::new(1, 2);
+        assert!(pool.try_execute(|| panic!("trabajo fallido")));
+        let d = Arc::clone(&done);
+        assert!(pool.try_execute(move || { d.fetch_add(1, Ordering::SeqCst); }));
+        drop(pool);
+        assert_eq!(done.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,198 @@
+// Negociación de versión y capacidades (opcional) antes de la trama.
+//
+//   emisor:   HELLO 1
+//   receptor: HELLO 1 ALGOS=HAMMING,CRC32 WIRE=text,bin
+//             CAP HAMMING n=3.. k=auto pad content=ascii,latin1,utf8,bytes
+//             CAP CRC32 mode=PURE content=ascii,latin1,utf8,bytes
+//             END
+//   emisor:   SELECT HAMMING
+//   receptor: OK HAMMING
+//   emisor:   (trama en texto o binario)
+//   receptor: OK <estado> | ERR <código> <motivo>
+//   ...       (más tramas en la misma conexión, hasta que el emisor la cierre)
+//
+// Un emisor que empieza directamente con la trama (ALGO=... o binario) sigue
+// funcionando igual; en ambos casos los errores de protocolo se responden con ERR.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::wire::{self, FrameTooLarge, WireFrame};
+
+pub const PROTOCOL_VERSION: u32 = 1;
+pub const ALGORITHMS: [&str; 2] = ["HAMMING", "CRC32"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    Malformed(String),                              // línea o encabezado que no se pudo interpretar
+    UnsupportedVersion(String),                     // HELLO sin ninguna versión conocida
+    UnknownAlgo { algo: String, enabled: Vec<&'static str> }, // ALGO/SELECT fuera de los habilitados
+    AlgoMismatch { selected: String, got: String },           // trama con un ALGO distinto al negociado
+    Timeout,                                                  // el emisor no envió nada dentro del tiempo de espera
+    TooLarge(FrameTooLarge),                                  // trama más larga que max_frame_bits
+    InvalidParam(String),                                     // PARAM fuera de las restricciones del algoritmo
+    Internal,                                                 // falla del receptor (no del emisor)
+}
+
+impl ProtocolError {
+    // Tras un error de lectura no se sabe dónde empieza la siguiente trama: se cierra
+    // la conexión. Los demás errores llegan con la trama ya consumida.
+    pub fn ends_connection(&self) -> bool {
+        matches!(
+            self,
+            ProtocolError::Malformed(_)
+                | ProtocolError::UnsupportedVersion(_)
+                | ProtocolError::Timeout
+                | ProtocolError::TooLarge(_)
+                | ProtocolError::Internal
+        )
+    }
+
+    pub fn code(&self) -> u16 {
+        match self {
+            ProtocolError::Malformed(_) => 400,
+            ProtocolError::UnsupportedVersion(_) => 505,
+            ProtocolError::UnknownAlgo { .. } => 415,
+            ProtocolError::AlgoMismatch { .. } => 409,
+            ProtocolError::Timeout => 408,
+            ProtocolError::TooLarge(_) => 413,
+            ProtocolError::InvalidParam(_) => 422,
+            ProtocolError::Internal => 500,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Malformed(m) => write!(f, "trama mal formada: {}", m),
+            ProtocolError::UnsupportedVersion(v) => {
+                write!(f, "versión de protocolo no soportada: {} (soportada: {})", v, PROTOCOL_VERSION)
+            }
+            ProtocolError::UnknownAlgo { algo, enabled } => {
+                write!(f, "algoritmo no soportado: {} (soportados: {})", algo, enabled.join(","))
+            }
+            ProtocolError::AlgoMismatch { selected, got } => {
+                write!(f, "ALGO={} no coincide con el negociado ({})", got, selected)
+            }
+            ProtocolError::Timeout => write!(f, "tiempo de espera agotado"),
+            ProtocolError::TooLarge(e) => write!(f, "{}", e),
+            ProtocolError::InvalidParam(e) => write!(f, "PARAM inválido: {}", e),
+            ProtocolError::Internal => write!(f, "error interno del receptor"),
+        }
+    }
+}
+
+impl From<io::Error> for ProtocolError {
+    fn from(e: io::Error) -> Self {
+        if let Some(too_large) = e.get_ref().and_then(|inner| inner.downcast_ref::<FrameTooLarge>()) {
+            return ProtocolError::TooLarge(too_large.clone());
+        }
+        // set_read_timeout produce WouldBlock (Unix) o TimedOut (Windows)
+        match e.kind() {
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => ProtocolError::Timeout,
+            _ => ProtocolError::Malformed(e.to_string()),
+        }
+    }
+}
+
+// Respuesta de error que se envía al emisor.
+pub fn error_line(e: &ProtocolError) -> String {
+    format!("ERR {} {}", e.code(), e)
+}
+
+fn capabilities(enabled: &[&'static str]) -> Vec<String> {
+    let mut lines = vec![format!("HELLO {} ALGOS={} WIRE=text,bin", PROTOCOL_VERSION, enabled.join(","))];
+    for algo in enabled {
+        lines.push(match *algo {
+            "HAMMING" => "CAP HAMMING n=3.. k=auto pad content=ascii,latin1,utf8,bytes".to_string(),
+            other => format!("CAP {} mode=PURE content=ascii,latin1,utf8,bytes", other),
+        });
+    }
+    lines.push("END".to_string());
+    lines
+}
+
+pub fn starts_with_hello<R: BufRead>(r: &mut R) -> io::Result<bool> {
+    Ok(r.fill_buf()?.starts_with(b"HELLO"))
+}
+
+fn read_line<R: BufRead>(r: &mut R) -> Result<String, ProtocolError> {
+    let too_long = || io::Error::new(io::ErrorKind::InvalidData, format!("línea de negociación de más de {} bytes", wire::MAX_HEADER_LINE));
+    let (line, n) = wire::read_line_limited(r, wire::MAX_HEADER_LINE, too_long)?;
+    if n == 0 {
+        return Err(ProtocolError::Malformed("conexión cerrada durante la negociación".into()));
+    }
+    Ok(line.trim().to_string())
+}
+
+// HELLO -> capacidades -> SELECT. Devuelve el algoritmo elegido por el emisor.
+pub fn handshake<R: BufRead, W: Write>(r: &mut R, w: &mut W, enabled: &[&'static str]) -> Result<String, ProtocolError> {
+    let hello = read_line(r)?;
+    let versions: Vec<&str> = hello.split_whitespace().skip(1).collect();
+    if !versions.iter().any(|v| v.parse() == Ok(PROTOCOL_VERSION)) {
+        return Err(ProtocolError::UnsupportedVersion(versions.join(",")));
+    }
+    w.write_all(format!("{}\n", capabilities(enabled).join("\n")).as_bytes())?;
+    w.flush()?;
+
+    let select = read_line(r)?;
+    let algo = match select.split_once(' ') {
+        Some(("SELECT", algo)) => algo.trim().to_ascii_uppercase(),
+        _ => return Err(ProtocolError::Malformed(format!("se esperaba SELECT <ALGO>, se recibió: {}", select))),
+    };
+    if !enabled.contains(&algo.as_str()) {
+        return Err(ProtocolError::UnknownAlgo { algo, enabled: enabled.to_vec() });
+    }
+    w.write_all(format!("OK {}\n", algo).as_bytes())?;
+    w.flush()?;
+    Ok(algo)
+}
+
+// Encabezado de la trama contra lo habilitado y, si hubo HELLO, lo negociado.
+pub fn check_frame(frame: &WireFrame, selected: Option<&str>, enabled: &[&'static str]) -> Result<(), ProtocolError> {
+    if !enabled.contains(&frame.algo.as_str()) {
+        return Err(ProtocolError::UnknownAlgo { algo: frame.algo.clone(), enabled: enabled.to_vec() });
+    }
+    match selected {
+        Some(sel) if sel != frame.algo => Err(ProtocolError::AlgoMismatch { selected: sel.to_string(), got: frame.algo.clone() }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wire;
+
+    #[test]
+    fn negociacion_completa() {
+        let mut input = &b"HELLO 2 1\nSELECT crc32\nALGO=CRC32\nPARAM=\nBITS=1\n"[..];
+        let mut out = Vec::new();
+        assert!(starts_with_hello(&mut input).unwrap());
+        assert_eq!(handshake(&mut input, &mut out, &ALGORITHMS).unwrap(), "CRC32");
+        let reply = String::from_utf8(out).unwrap();
+        assert!(reply.starts_with("HELLO 1 ALGOS=HAMMING,CRC32"));
+        assert!(reply.ends_with("END\nOK CRC32\n"));
+        let frame = wire::read_frame(&mut input, usize::MAX).unwrap();
+        assert_eq!(check_frame(&frame, Some("CRC32"), &ALGORITHMS), Ok(()));
+        assert_eq!(check_frame(&frame, Some("HAMMING"), &ALGORITHMS).unwrap_err().code(), 409);
+        // Con solo HAMMING habilitado, CRC32 no aparece en las capacidades ni se acepta
+        let mut out = Vec::new();
+        let e = handshake(&mut &b"HELLO 1\nSELECT CRC32\n"[..], &mut out, &["HAMMING"]).unwrap_err();
+        assert_eq!(error_line(&e), "ERR 415 algoritmo no soportado: CRC32 (soportados: HAMMING)");
+        assert!(!String::from_utf8(out).unwrap().contains("CAP CRC32"));
+        assert_eq!(check_frame(&frame, None, &["HAMMING"]).unwrap_err().code(), 415);
+    }
+
+    #[test]
+    fn errores_de_negociacion() {
+        let mut out = Vec::new();
+        let e = handshake(&mut &b"HELLO 7\n"[..], &mut out, &ALGORITHMS).unwrap_err();
+        assert_eq!(error_line(&e), "ERR 505 versión de protocolo no soportada: 7 (soportada: 1)");
+        let e = handshake(&mut &b"HELLO 1\nSELECT PARITY\n"[..], &mut out, &ALGORITHMS).unwrap_err();
+        assert_eq!(e, ProtocolError::UnknownAlgo { algo: "PARITY".into(), enabled: ALGORITHMS.to_vec() });
+        let e = handshake(&mut &b"HELLO 1\nALGO=CRC32\n"[..], &mut out, &ALGORITHMS).unwrap_err();
+        assert_eq!(e.code(), 400);
+    }
+}
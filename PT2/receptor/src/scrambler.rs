@@ -0,0 +1,209 @@
+// Aleatorizadores (scramblers) con LFSR: una etapa antes de la codificación de línea
+// que rompe las rachas largas de 0 o 1 del código, para que la línea tenga
+// transiciones y el espectro no dependa de los datos.
+//
+// Aditivo (sincronizado con la trama): se suma (XOR) a los datos la secuencia de un
+// LFSR que arranca en la misma semilla al comienzo de cada trama, en el emisor y en
+// el receptor. Un bit cambiado en el canal es un solo bit cambiado después de
+// desaleatorizar, pero el receptor tiene que saber dónde empieza la trama.
+//
+// Multiplicativo (autosincronizado): la realimentación sale de los bits ya enviados,
+// y el receptor los toma de lo que recibe: y[n] = x[n] ^ y[n-t1] ^ y[n-t2] ...
+// Después de `grado` bits el receptor queda sincronizado sin importar su estado
+// inicial, pero cada bit cambiado en el canal vuelve a aparecer t1, t2, ... bits
+// después: 1 + x^43 duplica los errores y 1 + x^4 + x^7 los triplica. Eso es lo que
+// mide `--line-sim ... --scrambler`.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Additive,
+    Multiplicative,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scrambler {
+    pub name: String,
+    pub kind: Kind,
+    pub taps: Vec<u32>, // exponentes del polinomio sin el término 1; el mayor es el grado
+    pub seed: u64,      // estado inicial: bit k-1 = salida de hace k pasos
+}
+
+// (nombre, tipo, exponentes, semilla)
+const PRESETS: [(&str, Kind, &[u32], u64); 4] = [
+    ("80211a", Kind::Additive, &[7, 4], 0x7F),       // 802.11a/g OFDM: 1 + x^4 + x^7
+    ("sonet", Kind::Additive, &[7, 6], 0x7F),        // SONET/SDH, por trama: 1 + x^6 + x^7
+    ("80211b", Kind::Multiplicative, &[7, 4], 0),    // 802.11b DSSS: 1 + x^4 + x^7
+    ("x43", Kind::Multiplicative, &[43], 0),         // carga útil SONET/SDH (POS, ATM): 1 + x^43
+];
+
+impl Scrambler {
+    pub fn presets() -> Vec<Scrambler> {
+        PRESETS
+            .iter()
+            .map(|&(name, kind, taps, seed)| Scrambler { name: name.to_string(), kind, taps: taps.to_vec(), seed })
+            .collect()
+    }
+
+    // Un preset (80211a, sonet, 80211b, x43) o un polinomio: add:7,4 (semilla de
+    // unos) o mult:43, con semilla opcional en hexadecimal (add:7,4@5d).
+    pub fn parse(s: &str) -> Result<Scrambler, String> {
+        let lower = s.to_ascii_lowercase();
+        if let Some(preset) = Scrambler::presets().into_iter().find(|p| p.name == lower) {
+            return Ok(preset);
+        }
+        let usage = || format!("Aleatorizador desconocido: {} (80211a, sonet, 80211b, x43, add:7,4 o mult:43)", s);
+        let (kind, spec) = match lower.split_once(':') {
+            Some(("add", spec)) => (Kind::Additive, spec),
+            Some(("mult", spec)) => (Kind::Multiplicative, spec),
+            _ => return Err(usage()),
+        };
+        let (taps, seed) = match spec.split_once('@') {
+            Some((taps, seed)) => (taps, Some(u64::from_str_radix(seed, 16).map_err(|_| usage())?)),
+            None => (spec, None),
+        };
+        let mut taps = taps.split(',').map(|t| t.trim().parse::<u32>().map_err(|_| usage())).collect::<Result<Vec<_>, _>>()?;
+        taps.sort_unstable_by(|a, b| b.cmp(a));
+        taps.dedup();
+        let degree = taps[0];
+        if !(1..=64).contains(&degree) || taps.contains(&0) {
+            return Err(format!("Exponentes del aleatorizador fuera de rango (1 a 64): {}", s));
+        }
+        let ones = if degree == 64 { u64::MAX } else { (1 << degree) - 1 };
+        let seed = seed.unwrap_or(if kind == Kind::Additive { ones } else { 0 }) & ones;
+        if kind == Kind::Additive && seed == 0 {
+            return Err("La semilla de un aleatorizador aditivo no puede ser 0".into());
+        }
+        Ok(Scrambler { name: lower, kind, taps, seed })
+    }
+
+    fn degree(&self) -> u32 {
+        self.taps[0]
+    }
+
+    // Realimentación: XOR de las salidas de hace t pasos para cada exponente t.
+    fn feedback(&self, state: u64) -> u8 {
+        self.taps.iter().fold(0, |acc, &t| acc ^ ((state >> (t - 1)) & 1) as u8)
+    }
+
+    fn shift(&self, state: u64, bit: u8) -> u64 {
+        let next = (state << 1) | bit as u64;
+        if self.degree() == 64 { next } else { next & ((1 << self.degree()) - 1) }
+    }
+
+    pub fn scramble(&self, bits: &[u8]) -> Vec<u8> {
+        let mut state = self.seed;
+        bits.iter()
+            .map(|&b| {
+                let fb = self.feedback(state);
+                let out = b ^ fb;
+                // El aditivo avanza con su propia salida; el multiplicativo con lo enviado
+                state = self.shift(state, if self.kind == Kind::Additive { fb } else { out });
+                out
+            })
+            .collect()
+    }
+
+    pub fn descramble(&self, bits: &[u8]) -> Vec<u8> {
+        match self.kind {
+            // La suma es su propia inversa
+            Kind::Additive => self.scramble(bits),
+            Kind::Multiplicative => {
+                let mut state = self.seed;
+                bits.iter()
+                    .map(|&y| {
+                        let x = y ^ self.feedback(state);
+                        state = self.shift(state, y);
+                        x
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // Bits dañados después de desaleatorizar por cada bit cambiado en el canal (lejos
+    // del final de la trama).
+    pub fn error_spread(&self) -> usize {
+        match self.kind {
+            Kind::Additive => 1,
+            Kind::Multiplicative => 1 + self.taps.len(),
+        }
+    }
+}
+
+impl fmt::Display for Scrambler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let poly: Vec<String> = self.taps.iter().rev().map(|t| format!("x^{}", t)).collect();
+        let kind = match self.kind {
+            Kind::Additive => "aditivo",
+            Kind::Multiplicative => "multiplicativo",
+        };
+        write!(f, "{} ({} 1 + {}, semilla {:x})", self.name, kind, poly.join(" + "), self.seed)
+    }
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::Rng;
+
+    fn bits(s: &str) -> Vec<u8> {
+        s.bytes().filter(|c| *c != b' ').map(|c| c - b'0').collect()
+    }
+
+    #[test]
+    fn secuencia_de_80211a() {
+        // Con la semilla de unos, la secuencia de la norma empieza 00001110 11110010 11001001
+        let s = Scrambler::parse("80211a").unwrap();
+        assert_eq!(s.scramble(&[0; 24]), bits("00001110 11110010 11001001"));
+        // y tiene período 127
+        let seq = s.scramble(&[0; 254]);
+        assert_eq!(seq[..127], seq[127..]);
+        assert_eq!(s.to_string(), "80211a (aditivo 1 + x^4 + x^7, semilla 7f)");
+    }
+
+    #[test]
+    fn ida_y_vuelta_y_rachas() {
+        let data = Rng::new(9).bits(500);
+        let zeros = vec![0u8; 500];
+        for s in Scrambler::presets().into_iter().chain([Scrambler::parse("add:5,3@1").unwrap()]) {
+            assert_eq!(s.descramble(&s.scramble(&data)), data, "{}", s);
+            // Una racha de ceros ya no es una racha en la línea (salvo al comienzo del
+            // multiplicativo, que arranca en 0 y tarda hasta tener un 1 realimentado)
+            let line = s.scramble(&[&[1u8][..], &zeros].concat());
+            let longest = line.split(|&b| b == 1).map(|run| run.len()).max().unwrap();
+            assert!(longest < 64, "{}: racha de {} ceros", s, longest);
+        }
+        assert!(Scrambler::parse("add:7,4@0").is_err());
+        assert!(Scrambler::parse("mult:65").is_err());
+        assert!(Scrambler::parse("v34").is_err());
+    }
+
+    #[test]
+    fn autosincronizacion_y_multiplicacion_de_errores() {
+        let data = Rng::new(2).bits(300);
+        for s in Scrambler::presets() {
+            let mut line = s.scramble(&data);
+            line[100] ^= 1;
+            let rx = s.descramble(&line);
+            let errors: Vec<usize> = (0..data.len()).filter(|&i| rx[i] != data[i]).collect();
+            let expected: Vec<usize> = match s.kind {
+                Kind::Additive => vec![100],
+                Kind::Multiplicative => [100].into_iter().chain(s.taps.iter().rev().map(|&t| 100 + t as usize)).collect(),
+            };
+            assert_eq!(errors, expected, "{}", s);
+            assert_eq!(errors.len(), s.error_spread());
+
+            if s.kind == Kind::Multiplicative {
+                // Un receptor que arranca en otro estado se sincroniza en `grado` bits
+                let other = Scrambler { seed: 0x55, ..s.clone() };
+                let rx = other.descramble(&s.scramble(&data));
+                let degree = s.degree() as usize;
+                assert_eq!(rx[degree..], data[degree..], "{}", s);
+            }
+        }
+    }
+}
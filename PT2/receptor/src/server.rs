@@ -0,0 +1,703 @@
+// Modo servidor: atiende conexiones (TCP o socket Unix) en paralelo con un pool acotado de hilos.
+// Cada conexión lleva una negociación opcional (HELLO) y luego tramas hasta que el
+// emisor la cierre; el estado compartido (archivos en curso, contadores, totales)
+// vive en Server detrás de Mutex/atómicos.
+//
+// Con UDP cada datagrama es una trama completa y se responde con otro datagrama;
+// no hay negociación ni conexión, así que las pérdidas y el desorden de la red
+// llegan tal cual a la capa de enlace.
+
+use std::collections::HashMap;
+use std::io::{self, BufReader, Read, Write};
+use std::net::{SocketAddr, UdpSocket};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::capture::{Capture, Recording};
+use crate::config::{Config, LogLevel, OutputFormat};
+use crate::frame::{self, FrameOutcome, Status};
+use crate::logging::{json_str, Ctx, Logger};
+use crate::metrics::Metrics;
+use crate::pool::ThreadPool;
+use crate::protocol::{self, ProtocolError};
+use crate::stats::{ConnStats, SeqOrder};
+use crate::transfer::{Reassembler, TransferHeader};
+use crate::transport::{Deadline, Listen, Listener, Stream};
+use crate::wire;
+
+// Cada cuánto el bucle de aceptación revisa si se pidió apagar.
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+// Mayor carga útil de un datagrama UDP sobre IPv4.
+const MAX_DATAGRAM: usize = 65_507;
+
+#[derive(Debug, Default)]
+struct Totals {
+    connections: usize,
+    busy_rejected: usize, // conexiones rechazadas con el pool lleno
+    stats: ConnStats,
+}
+
+// Emisor UDP: sus tramas y, por archivo (o "" sin file=), el orden de los seq= recibidos.
+#[derive(Debug, Default)]
+struct Peer {
+    stats: ConnStats,
+    order: HashMap<String, SeqOrder>,
+}
+
+pub struct Server {
+    cfg: Config,
+    reassembler: Mutex<Reassembler>,
+    frames: AtomicUsize,
+    totals: Mutex<Totals>,
+    // Conexiones abiertas: cómo cortarles la lectura al apagar
+    active: Mutex<HashMap<usize, Box<dyn Fn() + Send>>>,
+    next_conn: AtomicUsize,
+    metrics: Arc<Metrics>,
+    logger: Logger,
+    capture: Option<Capture>,
+    // Pedido de apagado: lo marca el manejador de SIGINT/SIGTERM (shutdown::install)
+    stop: Arc<AtomicBool>,
+}
+
+// Escribe una línea sin entrar en pánico: println! aborta el hilo si stdout ya no
+// acepta datos (tubería cerrada por el otro extremo).
+pub fn emit(to_stderr: bool, msg: &str) {
+    let _ = if to_stderr {
+        writeln!(io::stderr().lock(), "{}", msg)
+    } else {
+        writeln!(io::stdout().lock(), "{}", msg)
+    };
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // Un hilo que entró en pánico no invalida contadores ni fragmentos
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Resultado de una trama como objeto JSON de una línea (output = "json"); las claves
+// siguen las columnas del CSV del modo batch.
+fn frame_json(frame_no: usize, head: &wire::WireFrame, outcome: &FrameOutcome, notes: &[String]) -> String {
+    let corrections: Vec<String> = outcome.corrected_positions.iter().map(|(b, p)| format!("[{},{}]", b, p)).collect();
+    let notes: Vec<String> = notes.iter().map(|n| json_str(n)).collect();
+    format!(
+        "{{\"trama\":{},\"algoritmo\":{},\"param\":{},\"bits_rx\":{},\"formato\":{},\"bytes\":{},\"resultado\":{},\"correcciones\":[{}],\"mensaje\":{},\"detalle\":{},\"notas\":[{}]}}",
+        frame_no,
+        json_str(&head.algo),
+        json_str(&head.param),
+        head.bits.len(),
+        json_str(head.format.as_str()),
+        head.wire_bytes(),
+        json_str(outcome.status.as_str()),
+        corrections.join(","),
+        outcome.message.as_deref().map_or("null".to_string(), json_str),
+        json_str(&outcome.summary),
+        notes.join(",")
+    )
+}
+
+// Posiciones corregidas como se graban en la captura: "(bloque,posición) ...".
+fn corrections_tag(corrections: &[(usize, usize)]) -> String {
+    corrections.iter().map(|(b, p)| format!("({},{})", b, p)).collect::<Vec<_>>().join(" ")
+}
+
+// Guarda la carga útil binaria de una trama (content=bytes) en DIR/trama_N.bin.
+fn save_payload(dir: &str, frame_no: usize, payload: &[u8]) -> io::Result<String> {
+    std::fs::create_dir_all(dir)?;
+    let path = std::path::Path::new(dir).join(format!("trama_{}.bin", frame_no));
+    std::fs::write(&path, payload)?;
+    Ok(path.display().to_string())
+}
+
+impl Server {
+    pub fn new(cfg: Config) -> io::Result<Server> {
+        let logger = Logger::open(cfg.log_level, cfg.log_format, cfg.log_file.as_deref())?;
+        let capture = match &cfg.capture {
+            Some(path) => Some(Capture::create(path, &cfg.listen.to_string(), &cfg.recorded_settings())?),
+            None => None,
+        };
+        Ok(Server {
+            reassembler: Mutex::new(Reassembler::new(&cfg.recv_dir)),
+            cfg,
+            frames: AtomicUsize::new(0),
+            totals: Mutex::new(Totals::default()),
+            active: Mutex::new(HashMap::new()),
+            next_conn: AtomicUsize::new(0),
+            metrics: Arc::new(Metrics::default()),
+            logger,
+            capture,
+            stop: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn logger(&self) -> &Logger {
+        &self.logger
+    }
+
+    // Para el hilo que sirve /metrics (--metrics).
+    pub fn metrics(&self) -> Arc<Metrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    // Para shutdown::install: marcarla detiene serve_stream y serve_udp.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    fn stopping(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+
+    fn log(&self, level: LogLevel, ctx: Ctx, msg: &str) {
+        self.logger.log(level, ctx, msg);
+    }
+
+    // Resúmenes al terminar (totales, archivos incompletos): son parte del resultado,
+    // como las tramas, y salen siempre. Con salida json van a stderr, así stdout lleva
+    // solo las tramas.
+    fn summary(&self, msg: &str) {
+        emit(self.cfg.output == OutputFormat::Json, msg);
+    }
+
+    // Con --capture, los bytes de cada trama y lo que se hizo con ella quedan en el
+    // pcapng, un comentario clave=valor por dato (--replay lee peer, trama, seleccion,
+    // rechazo, resultado y correcciones).
+    fn capture(&self, raw: &[u8], ctx: Ctx, selected: Option<&str>, tags: impl FnOnce() -> Vec<String>) {
+        let Some(capture) = &self.capture else { return };
+        let mut comments = vec![format!("peer={}", ctx.peer.unwrap_or("-"))];
+        comments.extend(ctx.frame.map(|n| format!("trama={}", n)));
+        comments.extend(selected.map(|a| format!("seleccion={}", a)));
+        comments.extend(tags());
+        if let Err(e) = capture.record(raw, &comments) {
+            self.log(LogLevel::Error, ctx, &format!("No se pudo escribir la captura: {}", e));
+        }
+    }
+
+    // Trama rechazada: los bytes leídos (aunque no se haya podido interpretar) con el
+    // código de rechazo y la respuesta enviada.
+    fn capture_rejected(&self, raw: &[u8], frame: Option<&wire::WireFrame>, ctx: Ctx, selected: Option<&str>, e: &ProtocolError) {
+        if raw.is_empty() {
+            return;
+        }
+        self.capture(raw, ctx, selected, || {
+            let mut tags: Vec<String> = frame.map(|f| vec![format!("algo={}", f.algo), format!("param={}", f.param)]).unwrap_or_default();
+            tags.extend([format!("rechazo={}", e.code()), format!("resultado={}", protocol::error_line(e))]);
+            tags
+        });
+    }
+
+    fn reject<W: Write>(&self, writer: &mut W, e: &ProtocolError, peer: &str) {
+        let line = protocol::error_line(e);
+        self.metrics.rejected(e.code());
+        self.log(LogLevel::Warn, Ctx::peer(peer), &format!("Trama rechazada: {}", line));
+        let _ = writer.write_all(format!("{}\n", line).as_bytes());
+    }
+
+    // Bucle de aceptación hasta SIGINT/SIGTERM; al salir espera a las conexiones
+    // activas e imprime los totales.
+    pub fn serve_stream<L: Listener>(self: Arc<Self>, listener: L) -> io::Result<()> {
+        let started = Instant::now();
+        listener.set_nonblocking(true)?;
+        let pool = ThreadPool::new(self.cfg.workers, self.cfg.workers * 4);
+        while !self.stopping() {
+            let (stream, peer) = match listener.accept() {
+                Ok(conn) => conn,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(ACCEPT_POLL);
+                    continue;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            stream.configure(self.cfg.read_timeout)?;
+            let mut busy = stream.try_clone()?;
+            let server = Arc::clone(&self);
+            let name = peer.clone();
+            if !pool.try_execute(move || server.connection(stream, name)) {
+                lock(&self.totals).busy_rejected += 1;
+                self.metrics.busy();
+                self.log(LogLevel::Warn, Ctx::peer(&peer), "Conexión rechazada: receptor ocupado");
+                let _ = busy.write_all(b"ERR 503 receptor ocupado, reintente\n");
+            }
+        }
+
+        self.log(LogLevel::Info, Ctx::default(), "Apagando: esperando a las conexiones activas…");
+        for cut in lock(&self.active).values() {
+            cut();
+        }
+        drop(pool);
+        self.finish_transfers();
+        let totals = lock(&self.totals);
+        self.summary(&format!(
+            "Receptor detenido: {} conexiones ({} rechazadas por ocupado), {}",
+            totals.connections,
+            totals.busy_rejected,
+            totals.stats.render(started.elapsed())
+        ));
+        Ok(())
+    }
+
+    fn connection<S: Stream>(&self, stream: S, peer: String) {
+        let mut stream = stream;
+        let id = self.next_conn.fetch_add(1, Ordering::SeqCst);
+        let clones = stream.try_clone().and_then(|w| Ok((w, stream.try_clone()?, stream.try_clone()?)));
+        let (writer, registered, mut fallback) = match clones {
+            Ok(s) => s,
+            Err(e) => {
+                self.log(LogLevel::Error, Ctx::peer(&peer), &format!("Error en la conexión: {}", e));
+                return;
+            }
+        };
+        // La revisión y el registro van bajo el mismo lock que usa el apagado para cortar
+        // las conexiones: o esta conexión ve el pedido, o el apagado la ve a ella.
+        {
+            let mut active = lock(&self.active);
+            if self.stopping() {
+                drop(active);
+                let _ = stream.write_all(b"ERR 503 receptor apagandose\n");
+                return;
+            }
+            active.insert(id, Box::new(move || registered.shutdown_read()));
+        }
+        self.log(LogLevel::Info, Ctx::peer(&peer), "Conexión abierta");
+        self.metrics.connection_opened();
+        // Un fallo inesperado al procesar corta solo esta conexión, con ERR 500
+        let result = panic::catch_unwind(AssertUnwindSafe(|| self.handle(stream, writer, &peer, self.cfg.read_timeout)));
+        lock(&self.active).remove(&id);
+        self.metrics.connection_closed();
+        match result {
+            Ok(Ok(stats)) => {
+                self.log(LogLevel::Info, Ctx::peer(&peer), &format!("Conexión cerrada: {}", stats.render(stats.started.elapsed())));
+                let mut totals = lock(&self.totals);
+                totals.connections += 1;
+                totals.stats.merge(&stats);
+            }
+            Ok(Err(e)) => self.log(LogLevel::Error, Ctx::peer(&peer), &format!("Error en la conexión: {}", e)),
+            Err(_) => {
+                self.reject(&mut fallback, &ProtocolError::Internal, &peer);
+                self.log(LogLevel::Error, Ctx::peer(&peer), "Error interno: conexión cerrada");
+            }
+        }
+    }
+
+    // Negociación opcional (HELLO) y luego tramas hasta que el emisor cierre la
+    // conexión, cada una con su respuesta OK/ERR. La negociación y cada trama tienen
+    // que llegar completas dentro de `frame_timeout`.
+    fn handle<R: Read, W: Write>(&self, input: R, mut writer: W, peer: &str, frame_timeout: Option<Duration>) -> io::Result<ConnStats> {
+        let mut reader = BufReader::new(Deadline::new(input, frame_timeout));
+        reader.get_mut().start();
+        let mut stats = ConnStats::default();
+        let mut selected = None;
+        let hello = match protocol::starts_with_hello(&mut reader) {
+            Ok(h) => h,
+            Err(e) => {
+                self.reject(&mut writer, &ProtocolError::from(e), peer);
+                return Ok(stats);
+            }
+        };
+        if hello {
+            match protocol::handshake(&mut reader, &mut writer, &self.cfg.codecs) {
+                Ok(algo) => selected = Some(algo),
+                Err(e) => {
+                    stats.rejected += 1;
+                    self.reject(&mut writer, &e, peer);
+                    return Ok(stats);
+                }
+            }
+        }
+        loop {
+            reader.get_mut().stop();
+            match wire::at_eof(&mut reader) {
+                Ok(true) => break,
+                Ok(false) => {}
+                // Conexión inactiva más allá del tiempo de espera
+                Err(e) => {
+                    self.reject(&mut writer, &ProtocolError::from(e), peer);
+                    break;
+                }
+            }
+            reader.get_mut().start();
+            let mut recorder = wire::Recorder::new(&mut reader);
+            let received = wire::read_frame(&mut recorder, self.cfg.max_frame_bits).map_err(ProtocolError::from);
+            if let Err(e) = &received {
+                // Lo leído y lo que quedó en el búfer sin consumir (la línea demasiado
+                // larga, por ejemplo): la conexión se cierra y no se va a leer
+                let raw = [&recorder.bytes[..], reader.buffer()].concat();
+                self.capture_rejected(&raw, None, Ctx::peer(peer), selected.as_deref(), e);
+            }
+            match received.and_then(|frame| self.process(frame, selected.as_deref(), &mut stats, peer)).map(|o| o.status) {
+                // Un emisor sin HELLO puede haber cerrado ya la conexión: la respuesta es opcional
+                Ok(status) => { let _ = writer.write_all(format!("OK {}\n", status.as_str()).as_bytes()); }
+                Err(e) => {
+                    stats.rejected += 1;
+                    self.reject(&mut writer, &e, peer);
+                    if e.ends_connection() { break; }
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    fn process(&self, frame: wire::WireFrame, selected: Option<&str>, stats: &mut ConnStats, peer: &str) -> Result<FrameOutcome, ProtocolError> {
+        let param = self.cfg.effective_param(&frame.algo, &frame.param);
+        let checked = protocol::check_frame(&frame, selected, &self.cfg.codecs)
+            .and_then(|_| frame::validate_param(&frame.algo, &param).map_err(ProtocolError::InvalidParam));
+        if let Err(e) = checked {
+            self.capture_rejected(&frame.raw, Some(&frame), Ctx::peer(peer), selected, &e);
+            return Err(e);
+        }
+        let frame_no = self.frames.fetch_add(1, Ordering::SeqCst) + 1;
+        let decode_started = Instant::now();
+        let outcome = frame::process_frame(&frame.algo, &param, &frame.bits, &self.cfg.decode);
+        let decode_time = decode_started.elapsed();
+        let ctx = Ctx::peer(peer).frame(frame_no).algo(&frame.algo);
+        self.capture(&frame.raw, ctx, selected, || {
+            let mut tags = vec![
+                format!("algo={}", frame.algo),
+                format!("param={}", frame.param),
+                format!("resultado={}", outcome.status.as_str()),
+                format!("detalle={}", outcome.summary),
+            ];
+            if !outcome.corrected_positions.is_empty() {
+                tags.push(format!("correcciones={}", corrections_tag(&outcome.corrected_positions)));
+            }
+            tags
+        });
+        self.log(LogLevel::Debug, ctx, &format!(
+            "{}: {} bits corregidos, decodificada en {} µs",
+            outcome.status.as_str(),
+            outcome.corrected_positions.len(),
+            decode_time.as_micros()
+        ));
+
+        // Mensajes que siguen al resultado (carga útil guardada, progreso de un archivo)
+        let mut notes = Vec::new();
+        if let (Some(dir), Some(payload)) = (&self.cfg.payload_dir, &outcome.payload) {
+            match save_payload(dir, frame_no, payload) {
+                Ok(path) => notes.push(format!("Carga útil guardada en {}", path)),
+                Err(e) => self.log(LogLevel::Error, ctx, &format!("No se pudo guardar la carga útil: {}", e)),
+            }
+        }
+        // Fragmento de una transferencia de archivo (PARAM con file=...)
+        match TransferHeader::from_params(&frame::parse_param_map(&param)) {
+            Some(Ok(header)) => match lock(&self.reassembler).accept(&header, &outcome) {
+                Ok(Some(report)) => notes.push(report.render()),
+                Ok(None) => notes.push(format!("Archivo {}: trama {}/{}", header.file, header.seq + 1, header.total)),
+                Err(e) if e.kind() == io::ErrorKind::InvalidData => notes.push(format!("Archivo {}: {}", header.file, e)),
+                Err(e) => self.log(LogLevel::Error, ctx, &format!("No se pudo guardar {}: {}", header.file, e)),
+            },
+            Some(Err(e)) => notes.push(format!("Transferencia: PARAM inválido ({}), trama ignorada", e)),
+            None => {}
+        }
+
+        match self.cfg.output {
+            OutputFormat::Json => emit(false, &frame_json(frame_no, &frame, &outcome, &notes)),
+            // Las líneas de una trama se imprimen juntas para que no se mezclen con las
+            // de otras conexiones
+            OutputFormat::Text if self.cfg.log_level >= LogLevel::Info => {
+                let shown = if self.cfg.log_level >= LogLevel::Debug { frame.bits.len() } else { 32 };
+                let mut out = vec![format!(
+                    "ALGO={} | PARAM={} | bits={}… ({} bits) | {}: {} bytes",
+                    frame.algo,
+                    frame.param,
+                    &frame.bits.chars().take(shown).collect::<String>(),
+                    frame.bits.len(),
+                    frame.format.as_str(),
+                    frame.wire_bytes()
+                )];
+                out.push(outcome.summary.clone());
+                out.extend(notes);
+                emit(false, &out.join("\n"));
+            }
+            OutputFormat::Text => {}
+        }
+        stats.record(outcome.status, frame.wire_bytes(), frame.bits.len(), outcome.corrected_positions.len());
+        self.metrics.frame(&frame.algo, outcome.status, outcome.corrected_positions.len(), decode_time, frame.wire_bytes());
+        Ok(outcome)
+    }
+
+    // Tramas por stdin hasta EOF, como una única conexión sin respuestas (stdout queda
+    // para los mensajes, así el receptor puede cerrar una tubería).
+    pub fn serve_stdio(&self) -> io::Result<()> {
+        let stats = self.handle(io::stdin().lock(), io::sink(), "stdin", None)?;
+        self.finish_transfers();
+        self.summary(&format!("Entrada cerrada: {}", stats.render(stats.started.elapsed())));
+        Ok(())
+    }
+
+    // Reproduce una captura (--replay): cada paquete vuelve a pasar por read_frame y
+    // process en el orden grabado, con el número de trama y la selección del HELLO
+    // originales, así que la salida es la misma que al recibirlos. El resultado y las
+    // correcciones de cada paquete se comparan con los grabados; devuelve cuántos
+    // paquetes difieren.
+    pub fn replay(&self, recording: &Recording) -> io::Result<usize> {
+        let udp = matches!(self.cfg.listen, Listen::Udp(_));
+        if udp {
+            lock(&self.reassembler).tolerate_reordering();
+        }
+        let mut stats = ConnStats::default();
+        let mut mismatches = 0;
+        for (i, packet) in recording.packets.iter().enumerate() {
+            let peer = packet.meta("peer").unwrap_or("-");
+            if let Some(n) = packet.meta("trama").and_then(|n| n.parse::<usize>().ok()).filter(|n| *n > 0) {
+                self.frames.store(n - 1, Ordering::SeqCst);
+            }
+            let received = match packet.meta("rechazo") {
+                // El plazo vencido depende de la red: se repite el rechazo grabado
+                Some("408") => Err(ProtocolError::Timeout),
+                _ if udp => self.read_datagram(&packet.data),
+                _ => wire::read_frame(&mut &packet.data[..], self.cfg.max_frame_bits).map_err(ProtocolError::from),
+            };
+            let (result, corrections) = match received.and_then(|frame| self.process(frame, packet.meta("seleccion"), &mut stats, peer)) {
+                Ok(outcome) => (outcome.status.as_str().to_string(), corrections_tag(&outcome.corrected_positions)),
+                Err(e) => {
+                    stats.rejected += 1;
+                    self.reject(&mut io::sink(), &e, peer);
+                    (protocol::error_line(&e), String::new())
+                }
+            };
+            // Capturas sin resultado grabado (de otras herramientas) no se comparan
+            let Some(recorded) = packet.meta("resultado") else { continue };
+            let recorded_corrections = packet.meta("correcciones").unwrap_or("");
+            if recorded != result || recorded_corrections != corrections {
+                mismatches += 1;
+                self.log(LogLevel::Error, Ctx::peer(peer), &format!(
+                    "Paquete {}: grabado {} [{}], reproducido {} [{}]",
+                    i + 1, recorded, recorded_corrections, result, corrections
+                ));
+            }
+        }
+        self.finish_transfers();
+        let span = match (recording.packets.first(), recording.packets.last()) {
+            (Some(first), Some(last)) => last.micros.saturating_sub(first.micros) as f64 / 1e6,
+            _ => 0.0,
+        };
+        self.summary(&format!(
+            "Captura reproducida: {} paquetes grabados en {:.3} s, {} con un resultado distinto del grabado; {}",
+            recording.packets.len(),
+            span,
+            mismatches,
+            stats.render(stats.started.elapsed())
+        ));
+        Ok(mismatches)
+    }
+
+    // Un datagrama = una trama. Se atiende en este mismo hilo, en el orden de llegada,
+    // hasta SIGINT/SIGTERM; al salir se imprime el resumen de cada emisor.
+    pub fn serve_udp(&self, socket: UdpSocket) -> io::Result<()> {
+        let started = Instant::now();
+        // Sin datagramas, recv_from vuelve cada ACCEPT_POLL para revisar el apagado
+        socket.set_read_timeout(Some(ACCEPT_POLL))?;
+        // Reordenados por la red, el último fragmento de un archivo puede llegar antes que otros
+        lock(&self.reassembler).tolerate_reordering();
+        let mut peers: HashMap<SocketAddr, Peer> = HashMap::new();
+        let mut buf = vec![0u8; MAX_DATAGRAM];
+        while !self.stopping() {
+            let (len, addr) = match socket.recv_from(&mut buf) {
+                Ok(d) => d,
+                Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut | io::ErrorKind::Interrupted) => continue,
+                Err(e) => return Err(e),
+            };
+            let name = addr.to_string();
+            let peer = peers.entry(addr).or_insert_with(|| {
+                self.log(LogLevel::Info, Ctx::peer(&name), "Primer datagrama");
+                Peer::default()
+            });
+            let result = panic::catch_unwind(AssertUnwindSafe(|| self.datagram(&buf[..len], peer, &name)))
+                .unwrap_or(Err(ProtocolError::Internal));
+            let reply = match result {
+                Ok(status) => format!("OK {}", status.as_str()),
+                Err(e) => {
+                    peer.stats.rejected += 1;
+                    self.metrics.rejected(e.code());
+                    let line = protocol::error_line(&e);
+                    self.log(LogLevel::Warn, Ctx::peer(&name), &format!("Trama rechazada: {}", line));
+                    line
+                }
+            };
+            // El emisor puede no esperar la respuesta: un fallo al enviarla no es un error
+            let _ = socket.send_to(format!("{}\n", reply).as_bytes(), addr);
+        }
+
+        self.log(LogLevel::Info, Ctx::default(), "Apagando…");
+        self.finish_transfers();
+        let mut total = ConnStats::default();
+        let mut peers: Vec<_> = peers.into_iter().collect();
+        peers.sort_by_key(|(addr, _)| *addr);
+        for (addr, peer) in &peers {
+            self.summary(&format!("Datagramas de {}: {}", addr, peer.stats.render(started.elapsed())));
+            let mut order: Vec<_> = peer.order.iter().collect();
+            order.sort_by(|a, b| a.0.cmp(b.0));
+            for (file, o) in order {
+                let label = if file.is_empty() { String::new() } else { format!(" ({})", file) };
+                self.summary(&format!("  secuencia{}: {}", label, o.render()));
+            }
+            total.merge(&peer.stats);
+        }
+        self.summary(&format!("Receptor detenido: {} emisores, {}", peers.len(), total.render(started.elapsed())));
+        Ok(())
+    }
+
+    // Un datagrama (o un paquete de una captura UDP) lleva exactamente una trama.
+    fn read_datagram(&self, data: &[u8]) -> Result<wire::WireFrame, ProtocolError> {
+        let mut reader = data;
+        let frame = wire::read_frame(&mut reader, self.cfg.max_frame_bits)?;
+        if !wire::at_eof(&mut reader)? {
+            return Err(ProtocolError::Malformed("datos después de la trama (una trama por datagrama)".into()));
+        }
+        Ok(frame)
+    }
+
+    fn datagram(&self, data: &[u8], peer: &mut Peer, name: &str) -> Result<Status, ProtocolError> {
+        let frame = self.read_datagram(data).inspect_err(|e| self.capture_rejected(data, None, Ctx::peer(name), None, e))?;
+        let params = frame::parse_param_map(&frame.param);
+        let seq = params.get("seq").and_then(|v| v.parse::<usize>().ok());
+        let file = params.get("file").cloned().unwrap_or_default();
+        let status = self.process(frame, None, &mut peer.stats, name)?.status;
+        if let Some(seq) = seq {
+            peer.order.entry(file).or_default().observe(seq);
+        }
+        Ok(status)
+    }
+
+    // Al apagar: reporta los archivos a los que les faltan fragmentos.
+    fn finish_transfers(&self) {
+        for report in lock(&self.reassembler).drain() {
+            match report {
+                Ok(report) => self.summary(&report.render()),
+                Err(e) => self.log(LogLevel::Error, Ctx::default(), &format!("No se pudo cerrar una transferencia: {}", e)),
+            }
+        }
+    }
+}
+
+// --------------------------------- Tests ---------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{TcpListener, TcpStream};
+
+    fn quiet_server(read_timeout: Option<Duration>) -> Server {
+        let cfg = Config { read_timeout, log_level: LogLevel::Error, ..Config::default() };
+        Server::new(cfg).unwrap()
+    }
+
+    #[test]
+    fn emisor_que_gotea_bytes_recibe_408() {
+        let server = quiet_server(Some(Duration::from_millis(300)));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Un byte cada 40 ms: cada read() vuelve a tiempo, la trama nunca termina
+        let client = thread::spawn(move || {
+            let mut s = TcpStream::connect(addr).unwrap();
+            for b in b"ALGO=CRC32\nPARAM=mode=PURE\nBITS=0101" {
+                if s.write_all(&[*b]).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(40));
+            }
+            let mut reply = String::new();
+            let _ = s.read_to_string(&mut reply);
+            reply
+        });
+        let (stream, _) = listener.accept().unwrap();
+        stream.configure(server.cfg.read_timeout).unwrap();
+        let started = Instant::now();
+        let stats = server.handle(stream.try_clone().unwrap(), &stream, "goteo", server.cfg.read_timeout).unwrap();
+        assert!(started.elapsed() < Duration::from_millis(1000), "{:?}", started.elapsed());
+        assert_eq!(stats.frames, 0);
+        drop(stream);
+        assert!(client.join().unwrap().starts_with("ERR 408 "));
+    }
+
+    #[test]
+    fn la_captura_guarda_tambien_las_tramas_rechazadas() {
+        let path = std::env::temp_dir().join(format!("receptor_rechazos_{}.pcapng", std::process::id()));
+        let cfg = Config {
+            log_level: LogLevel::Error,
+            max_frame_bits: 16,
+            capture: Some(path.display().to_string()),
+            ..Config::default()
+        };
+        let server = Server::new(cfg).unwrap();
+        let ok = "ALGO=CRC32\nPARAM=mode=PURE\nBITS=1\n";
+        let bad = "ALGO=CRC32\nPARAM=mode=PURE\nBITS=10x1\n";
+        let input = format!("{}{}", ok, bad);
+        server.handle(input.as_bytes(), io::sink(), "tcp", None).unwrap();
+        server.handle(format!("ALGO=CRC32\nPARAM=\nBITS={}\n", "1".repeat(40)).as_bytes(), io::sink(), "grande", None).unwrap();
+        let mut peer = Peer::default();
+        let datagram = format!("{}basura", ok);
+        assert!(server.datagram(datagram.as_bytes(), &mut peer, "udp").is_err());
+
+        let recording = crate::capture::read(std::fs::File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let packets = &recording.packets;
+        assert_eq!(packets.len(), 4);
+        assert_eq!((packets[0].data.as_slice(), packets[0].meta("rechazo")), (ok.as_bytes(), None));
+        assert_eq!((packets[1].data.as_slice(), packets[1].meta("rechazo")), (bad.as_bytes(), Some("400")));
+        assert!(packets[1].meta("resultado").unwrap().starts_with("ERR 400 "));
+        assert_eq!(packets[2].meta("rechazo"), Some("413"));
+        assert!(packets[2].data.starts_with(b"ALGO=CRC32\nPARAM=\nBITS=1111"));
+        assert_eq!((packets[3].data.as_slice(), packets[3].meta("rechazo")), (datagram.as_bytes(), Some("400")));
+        assert_eq!(packets[3].meta("peer"), Some("udp"));
+    }
+
+    #[test]
+    fn la_reproduccion_compara_con_lo_grabado() {
+        let path = std::env::temp_dir().join(format!("receptor_replay_{}.pcapng", std::process::id()));
+        let cfg = Config { log_level: LogLevel::Error, capture: Some(path.display().to_string()), ..Config::default() };
+        let (param, bits) = crate::channel::encode_message("HAMMING", 7, b"Hola").unwrap();
+        let mut damaged = bits.clone().into_bytes();
+        damaged[3] ^= 1;
+        let input = format!(
+            "ALGO=HAMMING\nPARAM={}\nBITS={}\nALGO=HAMMING\nPARAM={}\nBITS={}\nALGO=CRC32\nPARAM=mode=PURE\nBITS=10x\n",
+            param, bits, param, String::from_utf8(damaged).unwrap()
+        );
+        Server::new(cfg.clone()).unwrap().handle(input.as_bytes(), io::sink(), "tcp", None).unwrap();
+        let mut recording = crate::capture::read(std::fs::File::open(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(recording.packets[1].meta("correcciones"), Some("(0,4)"));
+
+        let replaying = Config { capture: None, ..cfg };
+        assert_eq!(Server::new(replaying.clone()).unwrap().replay(&recording).unwrap(), 0);
+        // Un resultado y unas correcciones que no son los que da la decodificación
+        let tamper = |comments: &mut Vec<String>, key: &str, value: &str| {
+            comments.retain(|c| !c.starts_with(&format!("{}=", key)));
+            comments.push(format!("{}={}", key, value));
+        };
+        tamper(&mut recording.packets[0].comments, "resultado", "corregido");
+        assert_eq!(Server::new(replaying.clone()).unwrap().replay(&recording).unwrap(), 1);
+        tamper(&mut recording.packets[1].comments, "correcciones", "(0,5)");
+        tamper(&mut recording.packets[2].comments, "resultado", "ERR 413 trama de más de 16 bits");
+        assert_eq!(Server::new(replaying).unwrap().replay(&recording).unwrap(), 3);
+    }
+
+    #[test]
+    fn el_apagado_corta_las_conexiones_abiertas() {
+        // Sin tiempo de espera: la conexión quedaría bloqueada para siempre si el apagado
+        // no la cortara
+        let server = Arc::new(quiet_server(None));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let serving = Arc::clone(&server);
+        thread::spawn(move || done_tx.send(serving.serve_stream(listener).is_ok()).unwrap());
+
+        let mut client = TcpStream::connect(addr).unwrap();
+        let registered = Instant::now();
+        while lock(&server.active).is_empty() {
+            assert!(registered.elapsed() < Duration::from_secs(5), "la conexión no se registró");
+            thread::sleep(Duration::from_millis(10));
+        }
+        server.stop.store(true, Ordering::SeqCst);
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(true), "el servidor no terminó");
+        assert!(lock(&server.active).is_empty());
+        // El servidor cerró la conexión sin respuesta
+        let mut rest = Vec::new();
+        client.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        assert_eq!(client.read_to_end(&mut rest).unwrap(), 0);
+    }
+}
@@ -0,0 +1,40 @@
+// Apagado ordenado con SIGINT/SIGTERM sin dependencias externas: en Unix se registra
+// con signal(2) de la libc (ya enlazada por std) un manejador que solo marca la
+// bandera atómica del servidor; el bucle de aceptación la consulta periódicamente.
+// En otras plataformas Ctrl+C termina el proceso como antes.
+
+use std::sync::atomic::AtomicBool;
+#[cfg(unix)]
+use std::sync::{atomic::Ordering, OnceLock};
+use std::sync::Arc;
+
+// La bandera que marca el manejador: la del servidor que instaló las señales.
+#[cfg(unix)]
+static FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+#[cfg(unix)]
+extern "C" fn on_signal(_signum: std::os::raw::c_int) {
+    if let Some(flag) = FLAG.get() {
+        flag.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+pub fn install(flag: Arc<AtomicBool>) {
+    use std::os::raw::c_int;
+    const SIGINT: c_int = 2;
+    const SIGTERM: c_int = 15;
+    extern "C" {
+        fn signal(signum: c_int, handler: extern "C" fn(c_int)) -> usize;
+    }
+    // Un solo servidor por proceso: si ya había una bandera, queda esa
+    let _ = FLAG.set(flag);
+    // Seguro: el manejador solo escribe un AtomicBool
+    unsafe {
+        signal(SIGINT, on_signal);
+        signal(SIGTERM, on_signal);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn install(_flag: Arc<AtomicBool>) {}
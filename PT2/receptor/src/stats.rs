@@ -0,0 +1,150 @@
+// Estadísticas del modo servidor: por conexión (se imprimen al cerrarla) y totales
+// de todas las conexiones (al apagar el receptor).
+
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::frame::Status;
+
+#[derive(Debug)]
+pub struct ConnStats {
+    pub started: Instant,
+    pub frames: usize,
+    pub by_status: HashMap<&'static str, usize>,
+    pub rejected: usize,       // tramas respondidas con ERR
+    pub wire_bytes: usize,     // bytes de tramas leídos del cable
+    pub bits: usize,           // bits de trama (con redundancia)
+    pub corrected_bits: usize,
+}
+
+impl Default for ConnStats {
+    fn default() -> Self {
+        ConnStats {
+            started: Instant::now(),
+            frames: 0,
+            by_status: HashMap::new(),
+            rejected: 0,
+            wire_bytes: 0,
+            bits: 0,
+            corrected_bits: 0,
+        }
+    }
+}
+
+impl ConnStats {
+    pub fn record(&mut self, status: Status, wire_bytes: usize, bits: usize, corrected_bits: usize) {
+        self.frames += 1;
+        *self.by_status.entry(status.as_str()).or_insert(0) += 1;
+        self.wire_bytes += wire_bytes;
+        self.bits += bits;
+        self.corrected_bits += corrected_bits;
+    }
+
+    pub fn merge(&mut self, other: &ConnStats) {
+        self.frames += other.frames;
+        for (st, n) in &other.by_status {
+            *self.by_status.entry(st).or_insert(0) += n;
+        }
+        self.rejected += other.rejected;
+        self.wire_bytes += other.wire_bytes;
+        self.bits += other.bits;
+        self.corrected_bits += other.corrected_bits;
+    }
+
+    // "12 tramas (valido 10, corregido 2), 0 rechazadas, 1830 bytes, 0.015 s, 800.0 tramas/s"
+    pub fn render(&self, elapsed: Duration) -> String {
+        let statuses: Vec<String> = [Status::Valid, Status::Corrected, Status::Partial, Status::Uncorrectable, Status::Detected, Status::Error]
+            .iter()
+            .filter_map(|st| self.by_status.get(st.as_str()).map(|n| format!("{} {}", st.as_str(), n)))
+            .collect();
+        let secs = elapsed.as_secs_f64();
+        let rate = if secs > 0.0 { self.frames as f64 / secs } else { 0.0 };
+        format!(
+            "{} tramas ({}), {} rechazadas, {} bits corregidos, {} bytes, {} bits de trama, {:.3} s, {:.1} tramas/s",
+            self.frames,
+            if statuses.is_empty() { "-".to_string() } else { statuses.join(", ") },
+            self.rejected,
+            self.corrected_bits,
+            self.wire_bytes,
+            self.bits,
+            secs,
+            rate
+        )
+    }
+}
+
+// Orden de llegada de las tramas numeradas (seq= en PARAM) de un emisor. Con UDP
+// deja ver datagramas perdidos, duplicados o fuera de orden; las pérdidas cuentan
+// solo los huecos antes del mayor seq recibido.
+#[derive(Debug, Default)]
+pub struct SeqOrder {
+    highest: Option<usize>,
+    seen: HashSet<usize>,
+    reordered: usize,  // llegaron después de un seq mayor
+    duplicates: usize,
+}
+
+impl SeqOrder {
+    pub fn observe(&mut self, seq: usize) {
+        if !self.seen.insert(seq) {
+            self.duplicates += 1;
+            return;
+        }
+        match self.highest {
+            Some(h) if seq < h => self.reordered += 1,
+            _ => self.highest = Some(seq),
+        }
+    }
+
+    pub fn lost(&self) -> usize {
+        // seq llega del emisor: con seq=usize::MAX, h + 1 desbordaría
+        self.highest.map_or(0, |h| h.saturating_add(1).saturating_sub(self.seen.len()))
+    }
+
+    pub fn render(&self) -> String {
+        format!(
+            "{} recibidas, {} perdidas, {} fuera de orden, {} duplicadas",
+            self.seen.len(),
+            self.lost(),
+            self.reordered,
+            self.duplicates
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumen_de_conexion() {
+        let mut s = ConnStats::default();
+        s.record(Status::Valid, 40, 56, 0);
+        s.record(Status::Corrected, 40, 56, 1);
+        s.rejected += 1;
+        assert_eq!(
+            s.render(Duration::from_millis(500)),
+            "2 tramas (valido 1, corregido 1), 1 rechazadas, 1 bits corregidos, 80 bytes, 112 bits de trama, 0.500 s, 4.0 tramas/s"
+        );
+        let mut total = ConnStats::default();
+        total.merge(&s);
+        total.merge(&s);
+        assert_eq!((total.frames, total.by_status.get("corregido"), total.wire_bytes), (4, Some(&2), 160));
+    }
+
+    #[test]
+    fn orden_de_secuencia() {
+        let mut o = SeqOrder::default();
+        for seq in [0, 2, 1, 5, 5, 3] {
+            o.observe(seq);
+        }
+        assert_eq!(o.lost(), 1); // falta el 4
+        assert_eq!(o.render(), "5 recibidas, 1 perdidas, 2 fuera de orden, 1 duplicadas");
+
+        let mut o = SeqOrder::default();
+        o.observe(usize::MAX);
+        assert_eq!(o.lost(), usize::MAX - 1);
+        o.observe(0);
+        assert_eq!(o.render(), format!("2 recibidas, {} perdidas, 1 fuera de orden, 0 duplicadas", usize::MAX - 2));
+    }
+}
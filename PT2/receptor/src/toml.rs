@@ -0,0 +1,177 @@
+// Subconjunto mínimo de TOML para el archivo de configuración, sin dependencias:
+// comentarios (#), tablas [tabla] / [tabla.sub] y líneas `clave = valor` con strings
+// entre comillas dobles (escapes \" \\ \n \t), enteros, booleanos y arreglos en una
+// sola línea. Devuelve las claves completas ("tabla.clave") en el orden del archivo.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "string",
+            Value::Int(_) => "entero",
+            Value::Bool(_) => "booleano",
+            Value::Array(_) => "arreglo",
+        }
+    }
+}
+
+fn is_bare_key(k: &str) -> bool {
+    !k.is_empty() && k.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Cursor sobre el resto de la línea a partir del valor.
+struct Cursor<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn skip_ws(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_ws();
+        match self.rest.strip_prefix(c) {
+            Some(r) => {
+                self.rest = r;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_ws();
+        if self.eat('"') {
+            return self.string().map(Value::Str);
+        }
+        if self.eat('[') {
+            let mut items = Vec::new();
+            while !self.eat(']') {
+                items.push(self.value()?);
+                if !self.eat(',') {
+                    if !self.eat(']') {
+                        return Err("se esperaba ',' o ']' en el arreglo".into());
+                    }
+                    break;
+                }
+            }
+            return Ok(Value::Array(items));
+        }
+        let end = self.rest.find(|c: char| c.is_whitespace() || c == ',' || c == ']' || c == '#').unwrap_or(self.rest.len());
+        let (token, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        match token {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "" => Err("falta el valor".into()),
+            t => t.replace('_', "").parse().map(Value::Int).map_err(|_| format!("valor no soportado: {}", t)),
+        }
+    }
+
+    // Después de la comilla de apertura.
+    fn string(&mut self) -> Result<String, String> {
+        let mut out = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Ok(out);
+                }
+                '\\' => match chars.next().map(|(_, e)| e) {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('n') => out.push('\n'),
+                    Some('t') => out.push('\t'),
+                    other => return Err(format!("escape no soportado: \\{}", other.map(String::from).unwrap_or_default())),
+                },
+                c => out.push(c),
+            }
+        }
+        Err("string sin cerrar".into())
+    }
+
+    // Tras el valor solo puede venir un comentario.
+    fn end(&mut self) -> Result<(), String> {
+        self.skip_ws();
+        if self.rest.is_empty() || self.rest.starts_with('#') {
+            Ok(())
+        } else {
+            Err(format!("texto inesperado después del valor: {}", self.rest))
+        }
+    }
+}
+
+pub fn parse(src: &str) -> Result<Vec<(String, Value)>, String> {
+    let mut table = String::new();
+    let mut out: Vec<(String, Value)> = Vec::new();
+    for (i, raw) in src.lines().enumerate() {
+        let err = |m: String| format!("línea {}: {}", i + 1, m);
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let (name, rest) = header.split_once(']').ok_or_else(|| err("falta ']'".into()))?;
+            Cursor { rest }.end().map_err(err)?;
+            let name = name.trim();
+            if !name.split('.').all(|k| is_bare_key(k.trim())) {
+                return Err(err(format!("nombre de tabla inválido: [{}]", name)));
+            }
+            table = name.split('.').map(str::trim).collect::<Vec<_>>().join(".");
+            continue;
+        }
+        let (key, rest) = line.split_once('=').ok_or_else(|| err(format!("se esperaba clave = valor: {}", line)))?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(err(format!("clave inválida: {}", key)));
+        }
+        let mut cur = Cursor { rest };
+        let value = cur.value().map_err(err)?;
+        cur.end().map_err(err)?;
+        let full = if table.is_empty() { key.to_string() } else { format!("{}.{}", table, key) };
+        if out.iter().any(|(k, _)| *k == full) {
+            return Err(err(format!("clave repetida: {}", full)));
+        }
+        out.push((full, value));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subconjunto_de_toml() {
+        let src = r#"
+            # comentario
+            listen = "udp://0.0.0.0:9001"   # al final de la línea
+            workers = 8
+            partial = true
+            codecs = ["HAMMING", "CRC32",]
+
+            [params]
+            HAMMING = "n=15;content=\"x#y\""
+        "#;
+        let kv = parse(src).unwrap();
+        assert_eq!(kv[0], ("listen".to_string(), Value::Str("udp://0.0.0.0:9001".into())));
+        assert_eq!(kv[1].1, Value::Int(8));
+        assert_eq!(kv[2].1, Value::Bool(true));
+        assert_eq!(kv[3].1, Value::Array(vec![Value::Str("HAMMING".into()), Value::Str("CRC32".into())]));
+        assert_eq!(kv[4], ("params.HAMMING".to_string(), Value::Str("n=15;content=\"x#y\"".into())));
+
+        assert_eq!(parse("a = 1\na = 2").unwrap_err(), "línea 2: clave repetida: a");
+        assert_eq!(parse("x = \"abierto").unwrap_err(), "línea 1: string sin cerrar");
+        assert!(parse("x = 1 2").is_err());
+        assert!(parse("[tabla").is_err());
+    }
+}
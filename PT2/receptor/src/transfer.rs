@@ -0,0 +1,359 @@
+// Transferencia de archivos sobre el enlace: el emisor (app.FileSend) parte el archivo en
+// fragmentos y envía cada uno como una trama normal con content=bytes y, en PARAM,
+//   file=NOMBRE;seq=I;total=N;size=BYTES;filecrc=XXXXXXXX
+// (seq 0-based, filecrc = CRC-32 IEEE del archivo completo en hex).
+//
+// El receptor decodifica cada trama como siempre y aquí se guardan los fragmentos hasta
+// haber visto las N tramas; entonces se arma el archivo y se verifica el checksum.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::detection::crc32::crc32_ieee;
+use crate::frame::{FrameOutcome, Status};
+
+// Límites ante un emisor malicioso: fragmentos por archivo y archivos abiertos a la vez.
+pub const MAX_FRAGMENTS: usize = 1 << 16;
+pub const MAX_ACTIVE: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferHeader {
+    pub file: String,
+    pub seq: usize,
+    pub total: usize,
+    pub size: usize,
+    pub crc: u32,
+}
+
+impl TransferHeader {
+    // None si la trama no es parte de una transferencia (no trae `file=`).
+    pub fn from_params(params: &HashMap<String, String>) -> Option<Result<TransferHeader, String>> {
+        let file = params.get("file")?;
+        let num = |key: &str| -> Result<usize, String> {
+            params
+                .get(key)
+                .ok_or(format!("falta {}", key))?
+                .parse()
+                .map_err(|_| format!("{} inválido", key))
+        };
+        let header = (|| {
+            // Solo el nombre: nunca se escribe fuera del directorio de recepción
+            let name = Path::new(file)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .filter(|n| !n.is_empty() && *n != "..")
+                .ok_or(format!("nombre de archivo inválido: {}", file))?;
+            let crc_str = params.get("filecrc").ok_or("falta filecrc")?;
+            let crc = u32::from_str_radix(crc_str, 16).map_err(|_| format!("filecrc inválido: {}", crc_str))?;
+            let (seq, total) = (num("seq")?, num("total")?);
+            if total > MAX_FRAGMENTS {
+                return Err(format!("total={} supera el máximo de {} tramas", total, MAX_FRAGMENTS));
+            }
+            if seq >= total {
+                return Err(format!("seq={} fuera de rango (total={})", seq, total));
+            }
+            Ok(TransferHeader { file: name.to_string(), seq, total, size: num("size")?, crc })
+        })();
+        Some(header)
+    }
+}
+
+#[derive(Debug)]
+struct Fragment {
+    status: Status,
+    corrected: usize,
+    data: Option<Vec<u8>>, // None si la trama no se pudo entregar
+}
+
+#[derive(Debug)]
+struct Transfer {
+    total: usize,
+    size: usize,
+    crc: u32,
+    fragments: BTreeMap<usize, Fragment>,
+    rejected: usize, // tramas con total/size/filecrc distintos de los de la primera
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Intact,                 // todas las tramas entregadas y checksum correcto
+    Corrupt,                // archivo completo, pero el checksum (o el tamaño) no coincide
+    Incomplete(Vec<usize>), // tramas no entregadas (seq)
+}
+
+#[derive(Debug)]
+pub struct TransferReport {
+    pub file: String,
+    pub total: usize,
+    pub corrected: Vec<(usize, usize)>, // (seq, bits corregidos)
+    pub rejected: usize,
+    pub expected_crc: u32,
+    pub actual_crc: Option<u32>,
+    pub saved_to: Option<PathBuf>,
+    pub verdict: Verdict,
+}
+
+impl TransferReport {
+    pub fn render(&self) -> String {
+        let mut out = format!("Archivo {}: {} tramas", self.file, self.total);
+        if !self.corrected.is_empty() {
+            let detail: Vec<String> = self.corrected.iter().map(|(seq, n)| format!("{}:{}", seq, n)).collect();
+            out.push_str(&format!(", con correcciones (seq:bits) {}", detail.join(" ")));
+        }
+        if self.rejected > 0 {
+            out.push_str(&format!(", {} descartadas por no coincidir con la primera (total/size/filecrc)", self.rejected));
+        }
+        out.push_str(". ");
+        match &self.verdict {
+            Verdict::Intact => out.push_str(&format!("CRC-32 {:08x} correcto: archivo íntegro", self.expected_crc)),
+            Verdict::Corrupt => out.push_str(&format!(
+                "CRC-32 esperado {:08x}, obtenido {:08x}: archivo corrupto",
+                self.expected_crc,
+                self.actual_crc.unwrap_or_default()
+            )),
+            Verdict::Incomplete(missing) => out.push_str(&format!("Tramas no entregadas {:?}: archivo incompleto", missing)),
+        }
+        if let Some(path) = &self.saved_to {
+            out.push_str(&format!(". Guardado en {}", path.display()));
+        }
+        out
+    }
+}
+
+// Fragmentos pendientes por archivo; un archivo se cierra al recibir su última trama
+// (o, si el transporte puede reordenar, al tener todas).
+#[derive(Debug)]
+pub struct Reassembler {
+    dir: PathBuf,
+    active: HashMap<String, Transfer>,
+    in_order: bool,
+}
+
+impl Reassembler {
+    pub fn new(dir: impl Into<PathBuf>) -> Reassembler {
+        Reassembler { dir: dir.into(), active: HashMap::new(), in_order: true }
+    }
+
+    // Con UDP la última trama no implica que las anteriores ya llegaron.
+    pub fn tolerate_reordering(&mut self) {
+        self.in_order = false;
+    }
+
+    // Cierra los archivos pendientes (al apagar el receptor); quedan como incompletos.
+    pub fn drain(&mut self) -> Vec<io::Result<TransferReport>> {
+        let mut files: Vec<String> = self.active.keys().cloned().collect();
+        files.sort();
+        files
+            .into_iter()
+            .map(|file| {
+                let t = self.active.remove(&file).expect("transferencia activa");
+                self.finish(&file, t)
+            })
+            .collect()
+    }
+
+    // Registra una trama ya decodificada. Devuelve el reporte cuando se vieron todas
+    // las tramas del archivo (entregadas o no).
+    pub fn accept(&mut self, header: &TransferHeader, outcome: &FrameOutcome) -> io::Result<Option<TransferReport>> {
+        if !self.active.contains_key(&header.file) && self.active.len() >= MAX_ACTIVE {
+            return Err(io::Error::other(format!("demasiadas transferencias simultáneas (máximo {})", MAX_ACTIVE)));
+        }
+        let t = self.active.entry(header.file.clone()).or_insert_with(|| Transfer {
+            total: header.total,
+            size: header.size,
+            crc: header.crc,
+            fragments: BTreeMap::new(),
+            rejected: 0,
+        });
+        // La primera trama fija el archivo: otra que lo contradice no se mezcla con él
+        if (header.total, header.size, header.crc) != (t.total, t.size, t.crc) {
+            t.rejected += 1;
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                "trama {} descartada: total={} size={} filecrc={:08x}, pero la primera trajo total={} size={} filecrc={:08x}",
+                header.seq, header.total, header.size, header.crc, t.total, t.size, t.crc
+            )));
+        }
+        let delivered = matches!(outcome.status, Status::Valid | Status::Corrected);
+        // Un archivo vacío va en una sola trama sin datos: con CRC-32 son solo los 32
+        // bits del CRC, que la verificación rechaza por corta (pide >= 33). No hay nada
+        // que entregar, y el filecrc se sigue comparando al cerrar.
+        let data = if header.size == 0 {
+            Some(Vec::new())
+        } else if delivered {
+            outcome.payload.clone()
+        } else {
+            None
+        };
+        t.fragments.insert(header.seq, Fragment {
+            status: outcome.status,
+            corrected: outcome.corrected_positions.len(),
+            data,
+        });
+        let last_seen = self.in_order && header.seq + 1 >= t.total;
+        if !last_seen && t.fragments.len() < t.total {
+            return Ok(None);
+        }
+        let t = self.active.remove(&header.file).expect("transferencia activa");
+        self.finish(&header.file, t).map(Some)
+    }
+
+    fn finish(&self, file: &str, t: Transfer) -> io::Result<TransferReport> {
+        let missing: Vec<usize> = (0..t.total)
+            .filter(|seq| t.fragments.get(seq).is_none_or(|f| f.data.is_none()))
+            .collect();
+        let corrected: Vec<(usize, usize)> = t
+            .fragments
+            .iter()
+            .filter(|(_, f)| f.status == Status::Corrected)
+            .map(|(&seq, f)| (seq, f.corrected))
+            .collect();
+        let mut report = TransferReport {
+            file: file.to_string(),
+            total: t.total,
+            corrected,
+            rejected: t.rejected,
+            expected_crc: t.crc,
+            actual_crc: None,
+            saved_to: None,
+            verdict: Verdict::Incomplete(missing.clone()),
+        };
+        if !missing.is_empty() {
+            return Ok(report);
+        }
+        let data: Vec<u8> = t.fragments.into_values().flat_map(|f| f.data.unwrap_or_default()).collect();
+        let actual = crc32_ieee(&data);
+        report.actual_crc = Some(actual);
+        // Un archivo que no pasa la verificación no se guarda con el nombre original
+        let name = if actual == t.crc && data.len() == t.size {
+            report.verdict = Verdict::Intact;
+            file.to_string()
+        } else {
+            report.verdict = Verdict::Corrupt;
+            format!("{}.corrupto", file)
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(name);
+        std::fs::write(&path, &data)?;
+        report.saved_to = Some(path);
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::crc32::append_crc32_poly;
+    use crate::frame::{parse_param_map, process_frame, DecodeOptions};
+
+    fn send(r: &mut Reassembler, chunk: &[u8], seq: usize, total: usize, file: &[u8], corrupt: bool) -> Option<TransferReport> {
+        let bits: String = chunk.iter().map(|b| format!("{:08b}", b)).collect();
+        let mut frame = append_crc32_poly(&bits).unwrap();
+        if corrupt {
+            frame.replace_range(0..1, if frame.starts_with('0') { "1" } else { "0" });
+        }
+        let param = format!(
+            "mode=PURE;content=bytes;file=dir/prueba.bin;seq={};total={};size={};filecrc={:08x}",
+            seq, total, file.len(), crc32_ieee(file)
+        );
+        let params = parse_param_map(&param);
+        let header = TransferHeader::from_params(&params).unwrap().unwrap();
+        assert_eq!(header.file, "prueba.bin");
+        let outcome = process_frame("CRC32", &param, &frame, &DecodeOptions::default());
+        r.accept(&header, &outcome).unwrap()
+    }
+
+    #[test]
+    fn transferencia_integra_y_con_trama_perdida() {
+        let dir = std::env::temp_dir().join(format!("receptor_transfer_{}", std::process::id()));
+        let file = b"hola mundo, archivo de prueba".to_vec();
+        let chunks: Vec<&[u8]> = file.chunks(8).collect();
+
+        let mut r = Reassembler::new(&dir);
+        for (i, c) in chunks.iter().enumerate() {
+            let report = send(&mut r, c, i, chunks.len(), &file, false);
+            assert_eq!(report.is_some(), i + 1 == chunks.len());
+            if let Some(rep) = report {
+                assert_eq!(rep.verdict, Verdict::Intact);
+                assert_eq!(std::fs::read(rep.saved_to.unwrap()).unwrap(), file);
+            }
+        }
+
+        let mut r = Reassembler::new(&dir);
+        let mut last = None;
+        for (i, c) in chunks.iter().enumerate() {
+            last = send(&mut r, c, i, chunks.len(), &file, i == 1);
+        }
+        let rep = last.expect("reporte al cerrar");
+        assert_eq!(rep.verdict, Verdict::Incomplete(vec![1]));
+        assert!(rep.saved_to.is_none());
+
+        // Reordenadas (UDP): el último fragmento llega primero y otro nunca llega
+        let mut r = Reassembler::new(&dir);
+        r.tolerate_reordering();
+        for i in (1..chunks.len()).rev() {
+            assert!(send(&mut r, chunks[i], i, chunks.len(), &file, false).is_none());
+        }
+        let reports = r.drain();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].as_ref().unwrap().verdict, Verdict::Incomplete(vec![0]));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tramas_que_contradicen_la_primera_se_descartan() {
+        let dir = std::env::temp_dir().join(format!("receptor_transfer_mezcla_{}", std::process::id()));
+        let file = b"0123456789abcdef".to_vec();
+        let mut r = Reassembler::new(&dir);
+        assert!(send(&mut r, &file[..8], 0, 2, &file, false).is_none());
+
+        // Otra versión del archivo con el mismo nombre: otro tamaño y otro filecrc
+        let other = b"otra cosa".to_vec();
+        let bits: String = other.iter().map(|b| format!("{:08b}", b)).collect();
+        let param = format!("mode=PURE;content=bytes;file=prueba.bin;seq=1;total=2;size={};filecrc={:08x}", other.len(), crc32_ieee(&other));
+        let header = TransferHeader::from_params(&parse_param_map(&param)).unwrap().unwrap();
+        let outcome = process_frame("CRC32", &param, &append_crc32_poly(&bits).unwrap(), &DecodeOptions::default());
+        let err = r.accept(&header, &outcome).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let rep = send(&mut r, &file[8..], 1, 2, &file, false).expect("reporte al cerrar");
+        assert_eq!(rep.verdict, Verdict::Intact);
+        assert_eq!(rep.rejected, 1);
+        assert!(rep.render().contains("1 descartadas por no coincidir con la primera"), "{}", rep.render());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn archivo_vacio_en_una_trama_de_solo_crc() {
+        let dir = std::env::temp_dir().join(format!("receptor_transfer_vacio_{}", std::process::id()));
+        // Lo que manda FileSend para un archivo de 0 bytes: total=1 y el CRC de nada
+        let param = format!("mode=PURE;content=bytes;file=vacio.txt;seq=0;total=1;size=0;filecrc={:08x}", crc32_ieee(&[]));
+        let header = TransferHeader::from_params(&parse_param_map(&param)).unwrap().unwrap();
+        let outcome = process_frame("CRC32", &param, &"0".repeat(32), &DecodeOptions::default());
+        assert_eq!(outcome.status, Status::Error);
+
+        let mut r = Reassembler::new(&dir);
+        let rep = r.accept(&header, &outcome).unwrap().expect("reporte");
+        assert_eq!(rep.verdict, Verdict::Intact);
+        assert_eq!(std::fs::read(rep.saved_to.unwrap()).unwrap(), b"");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn limites_de_transferencia() {
+        let header = |file: &str, total: usize| {
+            let param = format!("file={};seq=0;total={};size=1;filecrc=0", file, total);
+            TransferHeader::from_params(&parse_param_map(&param)).unwrap()
+        };
+        assert!(header("a", MAX_FRAGMENTS + 1).unwrap_err().starts_with("total="));
+
+        let outcome = process_frame("CRC32", "mode=PURE", "0", &DecodeOptions::default());
+        let mut r = Reassembler::new(std::env::temp_dir());
+        for i in 0..MAX_ACTIVE {
+            assert!(r.accept(&header(&format!("f{}", i), 2).unwrap(), &outcome).unwrap().is_none());
+        }
+        assert!(r.accept(&header("otro", 2).unwrap(), &outcome).is_err());
+        // Los archivos ya abiertos siguen aceptando fragmentos
+        assert!(r.accept(&header("f0", 2).unwrap(), &outcome).is_ok());
+    }
+}
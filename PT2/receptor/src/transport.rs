@@ -0,0 +1,228 @@
+// Transportes del modo servidor, elegidos con --listen:
+//
+//   tcp://HOST:PUERTO (o HOST:PUERTO)   conexiones TCP (por defecto 0.0.0.0:9000)
+//   udp://HOST:PUERTO                    un datagrama por trama
+//   unix:///RUTA                         socket de dominio Unix (pruebas locales sin puertos)
+//   -                                    una sola "conexión" por stdin, para encadenar en
+//                                        tuberías (emisor | canal | receptor)
+//
+// TCP y Unix comparten el mismo servidor de conexiones (Server::serve_stream) a través
+// de los rasgos Listener y Stream.
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Listen {
+    Tcp(String),
+    Udp(String),
+    Unix(String),
+    Stdio,
+}
+
+impl Listen {
+    pub fn parse(s: &str) -> Result<Listen, String> {
+        if s == "-" {
+            return Ok(Listen::Stdio);
+        }
+        let (scheme, addr) = s.split_once("://").unwrap_or(("tcp", s));
+        if addr.is_empty() {
+            return Err(format!("Dirección de escucha vacía: {}", s));
+        }
+        match scheme {
+            "tcp" => Ok(Listen::Tcp(addr.to_string())),
+            "udp" => Ok(Listen::Udp(addr.to_string())),
+            "unix" => Ok(Listen::Unix(addr.to_string())),
+            other => Err(format!("Transporte desconocido: {} (use tcp://, udp://, unix:// o -)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Listen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Listen::Tcp(a) => write!(f, "tcp://{}", a),
+            Listen::Udp(a) => write!(f, "udp://{}", a),
+            Listen::Unix(p) => write!(f, "unix://{}", p),
+            Listen::Stdio => write!(f, "stdin"),
+        }
+    }
+}
+
+// Conexión aceptada: se lee y escribe desde copias del mismo socket.
+pub trait Stream: Read + Write + Send + Sized + 'static {
+    fn try_clone(&self) -> io::Result<Self>;
+    // Ajustes de cada conexión antes de atenderla
+    fn configure(&self, read_timeout: Option<Duration>) -> io::Result<()>;
+    // Desbloquea una lectura en curso (al apagar el receptor)
+    fn shutdown_read(&self);
+}
+
+// Plazo para recibir una trama completa. El tiempo de espera del socket vale para cada
+// read(): un emisor que manda un byte cada tanto lo renovaría para siempre y ocuparía
+// un hilo del pool. Vencido el plazo, la próxima lectura falla con TimedOut (ERR 408);
+// como una lectura en curso puede esperar otro tiempo de espera, la trama tarda a lo
+// sumo el doble.
+pub struct Deadline<R> {
+    inner: R,
+    limit: Option<Duration>, // None = sin plazo
+    until: Option<Instant>,
+}
+
+impl<R> Deadline<R> {
+    pub fn new(inner: R, limit: Option<Duration>) -> Deadline<R> {
+        Deadline { inner, limit, until: None }
+    }
+
+    // Empieza a contar (al comienzo de la negociación o de una trama).
+    pub fn start(&mut self) {
+        self.until = self.limit.map(|l| Instant::now() + l);
+    }
+
+    // Entre tramas solo rige el tiempo de espera de cada lectura.
+    pub fn stop(&mut self) {
+        self.until = None;
+    }
+}
+
+impl<R: Read> Read for Deadline<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.until.is_some_and(|t| Instant::now() >= t) {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "trama incompleta al vencer el plazo"));
+        }
+        self.inner.read(buf)
+    }
+}
+
+pub trait Listener {
+    type Stream: Stream;
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()>;
+    // Conexión y nombre del otro extremo para los mensajes
+    fn accept(&self) -> io::Result<(Self::Stream, String)>;
+}
+
+impl Stream for TcpStream {
+    fn try_clone(&self) -> io::Result<Self> {
+        TcpStream::try_clone(self)
+    }
+
+    fn configure(&self, read_timeout: Option<Duration>) -> io::Result<()> {
+        // En algunas plataformas el socket aceptado hereda el modo no bloqueante
+        self.set_nonblocking(false)?;
+        self.set_read_timeout(read_timeout)?;
+        // Respuestas cortas, una por trama: sin Nagle cada una esperaría al ACK diferido
+        self.set_nodelay(true)
+    }
+
+    fn shutdown_read(&self) {
+        let _ = self.shutdown(Shutdown::Read);
+    }
+}
+
+impl Listener for TcpListener {
+    type Stream = TcpStream;
+
+    fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        TcpListener::set_nonblocking(self, nonblocking)
+    }
+
+    fn accept(&self) -> io::Result<(TcpStream, String)> {
+        TcpListener::accept(self).map(|(s, addr)| (s, addr.to_string()))
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    impl Stream for UnixStream {
+        fn try_clone(&self) -> io::Result<Self> {
+            UnixStream::try_clone(self)
+        }
+
+        fn configure(&self, read_timeout: Option<Duration>) -> io::Result<()> {
+            self.set_nonblocking(false)?;
+            self.set_read_timeout(read_timeout)
+        }
+
+        fn shutdown_read(&self) {
+            let _ = self.shutdown(Shutdown::Read);
+        }
+    }
+
+    impl Listener for UnixListener {
+        type Stream = UnixStream;
+
+        fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+            UnixListener::set_nonblocking(self, nonblocking)
+        }
+
+        // Los clientes de un socket Unix no tienen dirección: se identifican por la ruta
+        fn accept(&self) -> io::Result<(UnixStream, String)> {
+            let (stream, _) = UnixListener::accept(self)?;
+            let path = self.local_addr()?.as_pathname().map(|p| p.display().to_string()).unwrap_or_default();
+            Ok((stream, format!("unix:{}", path)))
+        }
+    }
+
+    // Un socket que quedó de una ejecución anterior impide el bind; se reemplaza solo
+    // si nadie lo está usando. Cualquier otra cosa en esa ruta (un archivo, un
+    // directorio, un enlace) no se toca.
+    pub fn bind(path: &str) -> io::Result<UnixListener> {
+        use std::os::unix::fs::FileTypeExt;
+        match std::fs::symlink_metadata(path) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+            Ok(meta) if !meta.file_type().is_socket() => {
+                return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} existe y no es un socket", path)));
+            }
+            Ok(_) => {
+                if UnixStream::connect(path).is_ok() {
+                    return Err(io::Error::new(io::ErrorKind::AddrInUse, format!("{} ya tiene un receptor escuchando", path)));
+                }
+                std::fs::remove_file(path)?;
+            }
+        }
+        UnixListener::bind(path)
+    }
+}
+
+#[cfg(unix)]
+pub use unix::bind as bind_unix;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn direccion_de_escucha() {
+        assert_eq!(Listen::parse("0.0.0.0:9000"), Ok(Listen::Tcp("0.0.0.0:9000".into())));
+        assert_eq!(Listen::parse("udp://127.0.0.1:9001"), Ok(Listen::Udp("127.0.0.1:9001".into())));
+        assert_eq!(Listen::parse("unix:///tmp/receptor.sock"), Ok(Listen::Unix("/tmp/receptor.sock".into())));
+        assert_eq!(Listen::parse("-"), Ok(Listen::Stdio));
+        assert_eq!(Listen::parse("udp://127.0.0.1:9001").unwrap().to_string(), "udp://127.0.0.1:9001");
+        assert!(Listen::parse("sctp://x:1").is_err());
+        assert!(Listen::parse("udp://").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bind_unix_no_borra_archivos_comunes() {
+        let dir = std::env::temp_dir().join(format!("receptor-bind-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("no-es-socket");
+        std::fs::write(&file, "datos").unwrap();
+        let err = bind_unix(file.to_str().unwrap()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "datos");
+
+        // Un socket abandonado sí se reemplaza
+        let sock = dir.join("viejo.sock");
+        drop(bind_unix(sock.to_str().unwrap()).unwrap());
+        assert!(bind_unix(sock.to_str().unwrap()).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
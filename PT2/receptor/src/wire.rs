@@ -0,0 +1,396 @@
+// Formatos de trama en el cable.
+//
+// Texto (original): tres líneas ASCII, un carácter por bit.
+//   ALGO=HAMMING\nPARAM=n=7;pad=3\nBITS=0110011...\n
+//
+// Binario compacto (8 veces menos bytes para la trama):
+//   byte 0      MAGIC (0xB7, nunca es el primer byte de una trama de texto)
+//   byte 1      versión (1)
+//   byte 2      algoritmo (1 = HAMMING, 2 = CRC32)
+//   bytes 3-4   largo de PARAM (u16 big-endian), seguido de PARAM en ASCII
+//   4 bytes     cantidad de bits de la trama (u32 big-endian)
+//   ...         bits empaquetados MSB primero; el último byte se rellena con ceros
+//
+// El receptor distingue ambos formatos por el primer byte de cada trama, así que una
+// misma conexión puede llevar varias tramas seguidas (y mezclar formatos).
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Read};
+
+pub const MAGIC: u8 = 0xB7;
+pub const VERSION: u8 = 1;
+// Largo máximo de una línea de encabezado (ALGO=, PARAM=, HELLO, SELECT) y de PARAM
+// en el formato binario.
+pub const MAX_HEADER_LINE: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    Text,
+    Binary,
+}
+
+impl WireFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WireFormat::Text => "texto",
+            WireFormat::Binary => "binario",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WireFrame {
+    pub algo: String,
+    pub param: String,
+    pub bits: String,
+    pub format: WireFormat,
+    pub raw: Vec<u8>, // bytes leídos del cable para esta trama, tal cual (capturas)
+}
+
+impl WireFrame {
+    pub fn wire_bytes(&self) -> usize {
+        self.raw.len()
+    }
+}
+
+// Trama más larga que el máximo configurado (viaja dentro de un io::Error). En texto
+// se deja de leer al pasar el máximo, así que no se conoce el largo real (bits = None).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameTooLarge {
+    pub bits: Option<usize>,
+    pub max: usize,
+}
+
+impl fmt::Display for FrameTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bits {
+            Some(bits) => write!(f, "trama de {} bits, el máximo es {}", bits, self.max),
+            None => write!(f, "trama de más de {} bits", self.max),
+        }
+    }
+}
+
+impl Error for FrameTooLarge {}
+
+fn too_large(bits: Option<usize>, max: usize) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, FrameTooLarge { bits, max })
+}
+
+// read_line con tope: nunca acumula más de `limit` bytes aunque el emisor no mande el
+// fin de línea. Devuelve la línea sin "\n"/"\r\n" y los bytes consumidos (0 = EOF);
+// si se pasa del tope devuelve el error de `too_long`.
+pub fn read_line_limited<R: BufRead>(
+    r: &mut R,
+    limit: usize,
+    too_long: impl Fn() -> io::Error,
+) -> io::Result<(String, usize)> {
+    let raw = read_raw_line(r, limit, too_long)?;
+    Ok((line_text(&raw)?, raw.len()))
+}
+
+// La misma línea tal como llegó, con su fin de línea (vacía = EOF).
+fn read_raw_line<R: BufRead>(r: &mut R, limit: usize, too_long: impl Fn() -> io::Error) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    loop {
+        let buf = r.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        let (chunk, done) = match buf.iter().position(|&b| b == b'\n') {
+            Some(i) => (&buf[..=i], true),
+            None => (buf, false),
+        };
+        if line.len() + chunk.len() > limit.saturating_add(2) {
+            return Err(too_long());
+        }
+        line.extend_from_slice(chunk);
+        let n = chunk.len();
+        r.consume(n);
+        if done {
+            break;
+        }
+    }
+    if strip_eol(&line).len() > limit {
+        return Err(too_long());
+    }
+    Ok(line)
+}
+
+fn strip_eol(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., b'\n' | b'\r'] = line {
+        line = rest;
+    }
+    line
+}
+
+fn line_text(raw: &[u8]) -> io::Result<String> {
+    String::from_utf8(strip_eol(raw).to_vec()).map_err(|_| invalid("línea con texto que no es UTF-8".into()))
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+fn algo_id(algo: &str) -> Option<u8> {
+    match algo {
+        "HAMMING" => Some(1),
+        "CRC32" => Some(2),
+        _ => None,
+    }
+}
+
+fn algo_name(id: u8) -> Option<&'static str> {
+    match id {
+        1 => Some("HAMMING"),
+        2 => Some("CRC32"),
+        _ => None,
+    }
+}
+
+pub(crate) fn pack_bits(bits: &str) -> Vec<u8> {
+    bits.as_bytes()
+        .chunks(8)
+        .map(|chunk| chunk.iter().enumerate().fold(0u8, |acc, (i, &c)| acc | (((c == b'1') as u8) << (7 - i))))
+        .collect()
+}
+
+pub(crate) fn unpack_bits(bytes: &[u8], bit_len: usize) -> String {
+    (0..bit_len)
+        .map(|i| if (bytes[i / 8] >> (7 - i % 8)) & 1 == 1 { '1' } else { '0' })
+        .collect()
+}
+
+// Codifica una trama binaria (lo que hace el emisor con -Dwire=bin).
+#[cfg(test)]
+fn encode_binary(algo: &str, param: &str, bits: &str) -> Result<Vec<u8>, String> {
+    let id = algo_id(algo).ok_or(format!("Algoritmo no soportado: {}", algo))?;
+    let param_len = u16::try_from(param.len()).map_err(|_| "PARAM demasiado largo".to_string())?;
+    let bit_len = u32::try_from(bits.len()).map_err(|_| "Trama demasiado larga".to_string())?;
+    let mut out = vec![MAGIC, VERSION, id];
+    out.extend_from_slice(&param_len.to_be_bytes());
+    out.extend_from_slice(param.as_bytes());
+    out.extend_from_slice(&bit_len.to_be_bytes());
+    out.extend(pack_bits(bits));
+    Ok(out)
+}
+
+fn read_binary<R: Read>(r: &mut R, max_bits: usize) -> io::Result<WireFrame> {
+    let mut head = [0u8; 5];
+    r.read_exact(&mut head)?;
+    let [_, version, id, p0, p1] = head;
+    if version != VERSION {
+        return Err(invalid(format!("versión de formato binario no soportada: {}", version)));
+    }
+    let algo = algo_name(id).ok_or_else(|| invalid(format!("id de algoritmo desconocido: {}", id)))?;
+    let param_len = u16::from_be_bytes([p0, p1]) as usize;
+    if param_len > MAX_HEADER_LINE {
+        return Err(invalid(format!("PARAM de {} bytes, el máximo es {}", param_len, MAX_HEADER_LINE)));
+    }
+    let mut param = vec![0u8; param_len];
+    r.read_exact(&mut param)?;
+    let param = String::from_utf8(param).map_err(|_| invalid("PARAM no es texto válido".into()))?;
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let bit_len = u32::from_be_bytes(len) as usize;
+    // Antes de reservar memoria para los datos
+    if bit_len > max_bits {
+        return Err(too_large(Some(bit_len), max_bits));
+    }
+    let mut payload = vec![0u8; bit_len.div_ceil(8)];
+    r.read_exact(&mut payload)?;
+    let raw = [&head[..], param.as_bytes(), &len, &payload].concat();
+    Ok(WireFrame { algo: algo.to_string(), bits: unpack_bits(&payload, bit_len), param, format: WireFormat::Binary, raw })
+}
+
+// Cada línea debe empezar exactamente con su campo; cualquier otra cosa es un error
+// (antes se aceptaba y el campo quedaba vacío o con basura).
+fn read_text<R: BufRead>(r: &mut R, max_bits: usize) -> io::Result<WireFrame> {
+    let mut raw = Vec::new();
+    let mut field = |prefix: &str, limit: usize| -> io::Result<String> {
+        let too_long = || {
+            if prefix == "BITS=" {
+                too_large(None, max_bits)
+            } else {
+                invalid(format!("línea {} de más de {} bytes", prefix, MAX_HEADER_LINE))
+            }
+        };
+        let line_raw = read_raw_line(r, limit.saturating_add(prefix.len()), too_long)?;
+        if line_raw.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, format!("trama incompleta: falta {}", prefix)));
+        }
+        let line = line_text(&line_raw)?;
+        raw.extend_from_slice(&line_raw);
+        let value = line
+            .strip_prefix(prefix)
+            .ok_or_else(|| invalid(format!("se esperaba {}, se recibió: {}", prefix, line.trim_end())))?;
+        Ok(value.trim().to_string())
+    };
+    let algo = field("ALGO=", MAX_HEADER_LINE)?;
+    let param = field("PARAM=", MAX_HEADER_LINE)?;
+    let bits = field("BITS=", max_bits)?;
+    if bits.len() > max_bits {
+        return Err(too_large(Some(bits.len()), max_bits));
+    }
+    if let Some(i) = bits.find(|c| c != '0' && c != '1') {
+        return Err(invalid(format!("BITS solo admite 0 y 1 (carácter inválido en la posición {})", i)));
+    }
+    Ok(WireFrame { algo, param, bits, format: WireFormat::Text, raw })
+}
+
+// Salta líneas vacías entre tramas; true si el emisor cerró la conexión.
+pub fn at_eof<R: BufRead>(r: &mut R) -> io::Result<bool> {
+    loop {
+        let buf = r.fill_buf()?;
+        let blank = buf.iter().take_while(|b| matches!(b, b'\r' | b'\n')).count();
+        if buf.is_empty() {
+            return Ok(true);
+        }
+        let data_follows = blank < buf.len();
+        r.consume(blank);
+        if data_follows {
+            return Ok(false);
+        }
+    }
+}
+
+// BufRead que guarda todo lo que se consume de `inner`: los bytes de una trama
+// rechazada al leerla, de la que no queda un WireFrame (capturas).
+pub struct Recorder<'a, R> {
+    inner: &'a mut R,
+    pub bytes: Vec<u8>,
+}
+
+impl<'a, R: BufRead> Recorder<'a, R> {
+    pub fn new(inner: &'a mut R) -> Recorder<'a, R> {
+        Recorder { inner, bytes: Vec::new() }
+    }
+}
+
+impl<R: BufRead> Read for Recorder<'_, R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let buf = self.fill_buf()?;
+        let n = buf.len().min(out.len());
+        out[..n].copy_from_slice(&buf[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for Recorder<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, n: usize) {
+        // Con n > 0 los datos ya están en el búfer: fill_buf no vuelve a leer
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.bytes.extend_from_slice(&buf[..n.min(buf.len())]);
+        }
+        self.inner.consume(n);
+    }
+}
+
+// Lee una trama en cualquiera de los dos formatos, de a lo sumo max_bits bits.
+pub fn read_frame<R: BufRead>(r: &mut R, max_bits: usize) -> io::Result<WireFrame> {
+    let first = r.fill_buf()?.first().copied();
+    match first {
+        Some(MAGIC) => read_binary(r, max_bits),
+        Some(_) => read_text(r, max_bits),
+        None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "conexión cerrada sin trama")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binario_ida_y_vuelta() {
+        let bits = "0110011001";
+        let bytes = encode_binary("HAMMING", "n=7;pad=3", bits).unwrap();
+        // 5 de encabezado + 9 de PARAM + 4 de largo + 2 de datos
+        assert_eq!(bytes.len(), 20);
+        assert_eq!(&bytes[bytes.len() - 2..], &[0b0110_0110, 0b0100_0000]);
+        let f = read_frame(&mut &bytes[..], usize::MAX).unwrap();
+        assert_eq!((f.algo.as_str(), f.param.as_str(), f.bits.as_str()), ("HAMMING", "n=7;pad=3", bits));
+        assert_eq!((f.format, &f.raw), (WireFormat::Binary, &bytes));
+
+        let text = b"ALGO=CRC32\nPARAM=mode=PURE\nBITS=101\n";
+        let f = read_frame(&mut &text[..], usize::MAX).unwrap();
+        assert_eq!((f.algo.as_str(), f.bits.as_str(), f.format), ("CRC32", "101", WireFormat::Text));
+        assert_eq!(f.raw, text);
+    }
+
+    #[test]
+    fn binario_truncado_o_version_desconocida() {
+        let mut bytes = encode_binary("CRC32", "", "1".repeat(40).as_str()).unwrap();
+        bytes.pop();
+        assert_eq!(read_frame(&mut &bytes[..], usize::MAX).unwrap_err().kind(), io::ErrorKind::UnexpectedEof);
+        let long = encode_binary("CRC32", "", "1".repeat(40).as_str()).unwrap();
+        let e = read_frame(&mut &long[..], 32).unwrap_err();
+        assert_eq!(e.to_string(), "trama de 40 bits, el máximo es 32");
+        let bad = [MAGIC, 1, 1, 0xff, 0xff];
+        assert!(read_frame(&mut &bad[..], usize::MAX).unwrap_err().to_string().contains("PARAM de 65535 bytes"));
+        let bad = [MAGIC, 9, 1, 0, 0];
+        assert_eq!(read_frame(&mut &bad[..], usize::MAX).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn texto_con_campos_fuera_de_orden() {
+        let text = b"ALGO=CRC32\nBITS=101\nPARAM=\n";
+        let e = read_frame(&mut &text[..], usize::MAX).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(e.to_string(), "se esperaba PARAM=, se recibió: BITS=101");
+        let e = read_frame(&mut &b"ALGO=CRC32\n"[..], usize::MAX).unwrap_err();
+        assert_eq!(e.to_string(), "trama incompleta: falta PARAM=");
+    }
+
+    #[test]
+    fn lineas_acotadas() {
+        // Una línea BITS= sin fin no se acumula más allá del máximo
+        let mut endless = io::Cursor::new(b"ALGO=CRC32\nPARAM=\nBITS=".iter().copied().chain(std::iter::repeat_n(b'1', 10_000)).collect::<Vec<u8>>());
+        let e = read_frame(&mut io::BufReader::with_capacity(64, &mut endless), 100).unwrap_err();
+        assert_eq!(e.to_string(), "trama de más de 100 bits");
+        assert!(endless.position() < 1_000);
+
+        let long_param = format!("ALGO=CRC32\nPARAM={}\nBITS=1\n", "x".repeat(MAX_HEADER_LINE + 1));
+        let e = read_frame(&mut long_param.as_bytes(), usize::MAX).unwrap_err();
+        assert_eq!(e.to_string(), format!("línea PARAM= de más de {} bytes", MAX_HEADER_LINE));
+
+        let f = read_frame(&mut &b"ALGO=CRC32\r\nPARAM=\r\nBITS=0101\r\n"[..], 4).unwrap();
+        assert_eq!((f.bits.as_str(), f.wire_bytes()), ("0101", 31));
+        assert_eq!(f.raw, b"ALGO=CRC32\r\nPARAM=\r\nBITS=0101\r\n");
+        let e = read_frame(&mut &b"ALGO=CRC32\nPARAM=\nBITS=01a1\n"[..], usize::MAX).unwrap_err();
+        assert_eq!(e.to_string(), "BITS solo admite 0 y 1 (carácter inválido en la posición 2)");
+    }
+
+    #[test]
+    fn lineas_vacias_entre_tramas_en_un_mismo_buffer() {
+        for sep in ["\n", "\r\n", "\r\n\n"] {
+            let input = format!("ALGO=CRC32\nPARAM=\nBITS=101\n{}ALGO=CRC32\nPARAM=\nBITS=011\n{}", sep, sep);
+            let mut r = io::BufReader::new(input.as_bytes());
+            let mut got = Vec::new();
+            while !at_eof(&mut r).unwrap() {
+                got.push(read_frame(&mut r, usize::MAX).unwrap().bits);
+            }
+            assert_eq!(got, ["101", "011"], "separador {:?}", sep);
+        }
+    }
+
+    #[test]
+    fn recorder_guarda_lo_consumido_aunque_falle() {
+        let input = b"ALGO=CRC32\nPARAM=\nBITS=10x1\nALGO=";
+        let mut reader = &input[..];
+        let mut rec = Recorder::new(&mut reader);
+        assert!(read_frame(&mut rec, 64).is_err());
+        assert_eq!(rec.bytes, b"ALGO=CRC32\nPARAM=\nBITS=10x1\n");
+
+        let bin = encode_binary("HAMMING", "n=7", "1010101").unwrap();
+        let mut reader = &bin[..bin.len() - 1];
+        let mut rec = Recorder::new(&mut reader);
+        assert!(read_frame(&mut rec, 64).is_err());
+        assert_eq!(rec.bytes, bin[..bin.len() - 1]);
+    }
+}